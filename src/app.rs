@@ -1,13 +1,32 @@
-use crate::database::db::{SavedCommand, DB};
-use crate::display::menuopts::OPTION_PADDING_MID;
+use crate::database::bruno::BrunoCollection;
+use crate::database::db::{
+    EnvVariable, HistoryEntry, ImportSummary, ImportedCollection, SavedCommand, DB,
+};
+use crate::database::har::Har;
+use crate::database::httpfile::HttpFile;
+use crate::database::insomnia::InsomniaExport;
+use crate::database::openapi::OpenApi;
+use crate::database::postman::{PostmanCollection, PostmanEnvironment};
+use crate::display::inputopt::InputOpt;
+use crate::display::menuopts::{NEW_COLLECTION, OPTION_PADDING_MID};
+use crate::display::viewer::{BodyViewer, Syntax};
 use crate::display::AppOptions;
+use crate::request::bench::{BenchConfig, BenchReport, BenchRun};
+use crate::request::charset::{charset_for_label, decode};
+use crate::request::codegen::{generate, Language};
 use crate::request::curl::Curl;
+use crate::request::diff::{ResponseDiff, ResponseSnapshot};
+use crate::request::format::Formatter;
+use crate::request::problem::http_failure;
+use crate::request::runner::{CollectionRun, RunResult};
+use crate::request::variables::{mask, mask_json, substitute_json};
 use crate::request::ExecuteOption;
 use crate::screens::screen::Screen;
 use crate::Config;
 use arboard::Clipboard;
 use std::io::Write;
 use std::ops::DerefMut;
+use std::time::{Duration, Instant};
 use std::{error, mem};
 use tui::widgets::{ListItem, ListState};
 use tui_input::Input;
@@ -45,10 +64,24 @@ pub struct App<'a> {
     pub items: Vec<ListItem<'a>>,
     /// list state for tui
     pub state: Option<ListState>,
-    /// http response from executed command
-    pub response: Option<String>,
     /// database connection
     pub db: Box<DB>,
+    /// results of the last collection run
+    pub collection_run: Option<CollectionRun>,
+    /// results of the last benchmark
+    pub benchmark: Option<BenchReport>,
+    /// the benchmark still running in the background
+    pub bench_run: Option<BenchRun>,
+    /// history entry picked as the left side of a diff
+    pub diff_base: Option<i32>,
+    /// the responses being compared on the diff screen
+    pub diff: Option<ResponseDiff>,
+    /// response body shown in the body viewer
+    pub viewer: BodyViewer,
+    /// environment picked with `cute run --env`, instead of the selected one
+    pub environment: Option<i32>,
+    /// saved commands marked in the list to be moved together
+    pub marked: Vec<i32>,
 }
 
 impl<'a> Default for App<'a> {
@@ -66,8 +99,15 @@ impl<'a> Default for App<'a> {
             input: Input::default(),
             state: None,
             current_screen: Screen::Home,
-            response: None,
             db: Box::new(DB::new().expect("Failed to create database")),
+            collection_run: None,
+            benchmark: None,
+            bench_run: None,
+            diff_base: None,
+            diff: None,
+            viewer: BodyViewer::default(),
+            environment: None,
+            marked: Vec::new(),
         }
     }
 }
@@ -100,7 +140,7 @@ impl<'a> App<'a> {
     }
 
     pub fn copy_to_clipboard_from_response(&self) -> Result<(), String> {
-        if let Some(resp) = self.response.as_ref() {
+        if let Some(resp) = self.response() {
             if let Ok(mut clipboard) = Clipboard::new() {
                 if let Err(e) = clipboard.set_text(resp) {
                     return Err(e.to_string());
@@ -130,6 +170,19 @@ impl<'a> App<'a> {
         self.current_screen = screen.clone();
         self.screen_stack.push(screen.clone());
         self.cursor = 0;
+        if !matches!(screen, Screen::History { .. }) {
+            self.diff_base = None;
+        }
+        if !matches!(
+            screen,
+            Screen::SavedCommands { .. } | Screen::MoveCommands { .. }
+        ) {
+            self.marked.clear();
+        }
+        // a benchmark only runs while its results are on screen
+        if !matches!(screen, Screen::Benchmark) {
+            self.cancel_benchmark();
+        }
         match screen {
             Screen::Method => {
                 // If The Method Screen Is Hit, We Reset options
@@ -163,6 +216,40 @@ impl<'a> App<'a> {
                 self.selected = None;
                 return;
             }
+            Screen::Environments(_) => {
+                self.items = self
+                    .db
+                    .as_ref()
+                    .get_environments()
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|env| ListItem::new(format!("{}{}", env, OPTION_PADDING_MID)))
+                    .collect();
+                self.selected = None;
+                return;
+            }
+            Screen::History { filter, .. } => {
+                self.items = self
+                    .db
+                    .as_ref()
+                    .get_history(filter.as_deref())
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|entry| ListItem::new(format!("{}{}", entry, OPTION_PADDING_MID)))
+                    .collect();
+                self.selected = None;
+                return;
+            }
+            Screen::Diff => {
+                self.items = self
+                    .diff
+                    .as_ref()
+                    .map(|diff| diff.rows.iter().map(|_| ListItem::new("")).collect())
+                    .unwrap_or_default();
+            }
+            Screen::MoveCommands { .. } => {
+                self.items = screen.get_opts(self.get_database_items());
+            }
             Screen::ViewSavedCollections => {
                 self.items = self
                     .db
@@ -193,7 +280,9 @@ impl<'a> App<'a> {
             Some(
                 Screen::InputMenu(_)
                 | Screen::CmdMenu { .. }
+                | Screen::HistoryMenu(_)
                 | Screen::ColMenu(_)
+                | Screen::EnvironmentMenu(_)
                 | Screen::KeysMenu(_),
             ) => self.go_back_screen(),
             Some(Screen::RequestBodyInput) => self.goto_screen(&Screen::Method),
@@ -211,7 +300,7 @@ impl<'a> App<'a> {
 
     pub fn quit(&mut self) {
         std::io::stdout()
-            .write_all(self.response.as_ref().unwrap_or(&String::new()).as_bytes())
+            .write_all(self.response().unwrap_or_default().as_bytes())
             .unwrap();
         std::io::stdout().flush().unwrap();
         self.running = false;
@@ -235,6 +324,11 @@ impl<'a> App<'a> {
                     self.cursor += 1;
                 }
             }
+            Screen::ViewBody(_) => {
+                if self.cursor + 1 < self.viewer.len() {
+                    self.cursor += 1;
+                }
+            }
             _ => {
                 if self.items.is_empty() {
                     return;
@@ -257,6 +351,9 @@ impl<'a> App<'a> {
                     self.cursor = res;
                 }
             }
+            Screen::ViewBody(_) => {
+                self.cursor = self.cursor.saturating_sub(1);
+            }
             _ => {
                 if self.items.is_empty() {
                     return;
@@ -316,6 +413,17 @@ impl<'a> App<'a> {
                     .map(|x| x.get_name().to_string())
                     .collect::<Vec<String>>(),
             ),
+            // the last entry creates a collection to move them to
+            Screen::MoveCommands { .. } => Some(
+                self.db
+                    .as_ref()
+                    .get_collections()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|x| x.get_name().to_string())
+                    .chain(std::iter::once(String::from(NEW_COLLECTION)))
+                    .collect::<Vec<String>>(),
+            ),
             _ => None,
         }
     }
@@ -329,39 +437,658 @@ impl<'a> App<'a> {
     }
 
     pub fn execute_command(&mut self) -> Result<(), String> {
-        self.command.execute(Some(Box::new(self.db.deref_mut())))
+        let start = Instant::now();
+        self.command
+            .set_max_body_size(self.config.get_max_body_size());
+        let result = self.command.execute(Some(Box::new(self.db.deref_mut())));
+        let json = serde_json::to_string(&self.command).unwrap_or_default();
+        self.log_history(&json, start.elapsed(), result.as_ref().err());
+        result
+    }
+
+    // Records the command that was just executed in the history, a failure
+    // to log should never get in the way of the request itself
+    fn log_history(&mut self, json: &str, duration: Duration, error: Option<&String>) {
+        let limit = self.config.get_history_limit();
+        if limit == 0 {
+            return;
+        }
+        // the response has columns of its own, where its body is capped,
+        // an executed command would carry the whole of it along
+        let json = match serde_json::from_str::<serde_json::Value>(json) {
+            Ok(mut value) => {
+                value["resp"] = serde_json::Value::Null;
+                value.to_string()
+            }
+            Err(_) => json.to_string(),
+        };
+        // secrets of the environment are stored as their {{name}}
+        let secrets = self.secret_variables();
+        let mut entry = HistoryEntry::new(
+            &mask_json(&json, &secrets),
+            &self.command.get_method().to_string(),
+            &mask(self.command.get_url(), &secrets),
+        );
+        let body = match error {
+            Some(e) => e.clone(),
+            None => self.command.get_response().unwrap_or_default(),
+        };
+        entry.set_response(
+            self.command.get_response_code(),
+            duration,
+            self.command.get_response_size(),
+            &self.command.get_response_headers(),
+            &body,
+        );
+        let _ = self.db.add_history(&entry, limit);
     }
 
+    // Folders become collections of their own, what can't be imported is listed in the summary
     pub fn import_postman_collection(
         &mut self,
         path: &str,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    ) -> Result<ImportSummary, Box<dyn std::error::Error>> {
         let file = std::fs::File::open(path)?;
-        let collection: Result<crate::database::postman::PostmanCollection, String> =
-            serde_json::from_reader(file).map_err(|e| e.to_string());
-        match collection {
-            Ok(collection) => {
-                let name = collection.info.name.clone();
-                let description = collection.info.description.clone();
-                let cmds: Vec<SavedCommand> = collection.into();
-                self.db.add_collection(&name, &description, cmds.as_slice())
+        let collection: PostmanCollection = serde_json::from_reader(file)?;
+        let (collections, skipped) = collection.import();
+        self.save_imported(&collections, skipped)
+    }
+
+    // Bruno collections are a directory with a bruno.json, HAR and .http/.rest
+    // files are told apart by their extension, OpenAPI
+    // specs by being YAML or by their 'openapi'/'swagger' field, Insomnia exports by their
+    // '_type', Postman environments by their scope, the rest are Postman collections
+    pub fn import_collection(
+        &mut self,
+        path: &str,
+    ) -> Result<ImportSummary, Box<dyn std::error::Error>> {
+        if BrunoCollection::is_bruno_path(path) {
+            return self.import_bruno(path);
+        }
+        let lower = path.to_lowercase();
+        if lower.ends_with(".har") {
+            return self.import_har(path);
+        }
+        if lower.ends_with(".yaml") || lower.ends_with(".yml") {
+            return self.import_openapi(path);
+        }
+        if lower.ends_with(".http") || lower.ends_with(".rest") {
+            return self.import_http_file(path);
+        }
+        let text = std::fs::read_to_string(path)?;
+        if InsomniaExport::is_insomnia_json(&text) {
+            return self.import_insomnia(path);
+        }
+        if PostmanEnvironment::is_environment_json(&text) {
+            return self.import_postman_environment(path);
+        }
+        match OpenApi::is_openapi_json(&text) {
+            true => self.import_openapi(path),
+            false => self.import_postman_collection(path),
+        }
+    }
+
+    fn save_imported(
+        &mut self,
+        collections: &[ImportedCollection],
+        skipped: Vec<String>,
+    ) -> Result<ImportSummary, Box<dyn std::error::Error>> {
+        if collections.is_empty() {
+            return Err("No requests found to import".into());
+        }
+        for collection in collections.iter() {
+            self.db.add_imported_collection(collection)?;
+        }
+        Ok(ImportSummary::new(collections, skipped))
+    }
+
+    // Importing an environment again replaces the one with the same name.
+    // Secret values are stored with the saved keys
+    pub fn import_postman_environment(
+        &mut self,
+        path: &str,
+    ) -> Result<ImportSummary, Box<dyn std::error::Error>> {
+        let file = std::fs::File::open(path)?;
+        let env: PostmanEnvironment = serde_json::from_reader(file)?;
+        if env.name.is_empty() {
+            return Err("The environment has no name".into());
+        }
+        for old in self.db.get_environments()?.iter() {
+            if old.name == env.name {
+                self.db.delete_environment(old.get_id())?;
+            }
+        }
+        let mut variables = Vec::new();
+        for (name, value, secret) in env.variables() {
+            let key_id = match secret {
+                true => Some(
+                    self.db
+                        .add_secret(&value, &format!("{}: {}", env.name, name))?,
+                ),
+                false => None,
+            };
+            variables.push(EnvVariable {
+                name,
+                value: if secret { String::new() } else { value },
+                key_id,
+            });
+        }
+        self.db.add_environment(&env.name, &variables)?;
+        Ok(ImportSummary {
+            environment: Some((env.name, variables.len())),
+            ..Default::default()
+        })
+    }
+
+    // Takes the directory of a Bruno collection (or its bruno.json), each folder
+    // with requests becomes a collection
+    pub fn import_bruno(
+        &mut self,
+        path: &str,
+    ) -> Result<ImportSummary, Box<dyn std::error::Error>> {
+        let (collections, skipped) = BrunoCollection::open(path)?.import();
+        self.save_imported(&collections, skipped)
+    }
+
+    // Each workspace and request group of the export becomes a collection
+    pub fn import_insomnia(
+        &mut self,
+        path: &str,
+    ) -> Result<ImportSummary, Box<dyn std::error::Error>> {
+        let file = std::fs::File::open(path)?;
+        let export: InsomniaExport = serde_json::from_reader(file)?;
+        let (collections, skipped) = export.import();
+        self.save_imported(&collections, skipped)
+    }
+
+    // Every operation of the spec becomes a saved command, the server URL is
+    // kept in the {{baseUrl}} variable of the collection
+    pub fn import_openapi(
+        &mut self,
+        path: &str,
+    ) -> Result<ImportSummary, Box<dyn std::error::Error>> {
+        let spec = OpenApi::parse(&std::fs::read_to_string(path)?)?;
+        let collection = ImportedCollection {
+            name: spec.name(),
+            description: spec.description(),
+            variables: spec.variables(),
+            commands: spec.into(),
+        };
+        self.save_imported(&[collection], Vec::new())
+    }
+
+    // Every entry of the HAR file becomes a saved command, in a collection named after the file
+    pub fn import_har(&mut self, path: &str) -> Result<ImportSummary, Box<dyn std::error::Error>> {
+        let file = std::fs::File::open(path)?;
+        let har: Har = serde_json::from_reader(file)?;
+        let collection = ImportedCollection {
+            name: std::path::Path::new(path)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.to_string()),
+            description: String::from("Imported from HAR"),
+            variables: Vec::new(),
+            commands: har.into(),
+        };
+        self.save_imported(&[collection], Vec::new())
+    }
+
+    // The requests of a VS Code REST Client / JetBrains HTTP Client file become a
+    // collection named after the file, its `@name = value` lines the collection's variables
+    pub fn import_http_file(
+        &mut self,
+        path: &str,
+    ) -> Result<ImportSummary, Box<dyn std::error::Error>> {
+        let file = HttpFile::parse(&std::fs::read_to_string(path)?);
+        let name = std::path::Path::new(path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string());
+        let (collection, skipped) = file.import(&name);
+        if collection.commands.is_empty() {
+            return Err(match skipped.is_empty() {
+                true => "No requests found to import".into(),
+                false => format!("No requests imported:\n- {}", skipped.join("\n- ")).into(),
+            });
+        }
+        self.save_imported(&[collection], skipped)
+    }
+
+    // Exports a collection, or every saved command, to a HAR file, a .http/.rest
+    // file or for any other extension a Postman v2.1 collection with its variables
+    pub fn export_commands(&self, id: Option<i32>, path: &str) -> Result<(), String> {
+        let mut commands = self.db.get_commands(id).map_err(|e| e.to_string())?;
+        // only the marked commands, when some are marked on the saved commands screen
+        if !self.marked.is_empty() {
+            commands.retain(|cmd| self.marked.contains(&cmd.get_id()));
+        }
+        let lower = path.to_lowercase();
+        if lower.ends_with(".har") {
+            return Har::from_commands(&commands).write(path);
+        }
+        if lower.ends_with(".http") || lower.ends_with(".rest") {
+            let variables = match id {
+                Some(id) => self
+                    .db
+                    .get_collection_variables(id)
+                    .map_err(|e| e.to_string())?,
+                None => Vec::new(),
+            };
+            return HttpFile::from_commands(&commands, &variables).write(path);
+        }
+        let (name, description, variables) = match id {
+            Some(id) => {
+                let collection = self.db.get_collection_by_id(id)?;
+                let variables = self
+                    .db
+                    .get_collection_variables(id)
+                    .map_err(|e| e.to_string())?;
+                (
+                    collection.name,
+                    collection.description.unwrap_or_default(),
+                    variables,
+                )
+            }
+            None => (
+                String::from("CuTE"),
+                String::from("Saved commands exported from CuTE"),
+                Vec::new(),
+            ),
+        };
+        PostmanCollection::from_commands(&name, &description, &commands, &variables).write(path)
+    }
+
+    // Exports the history entries matching the filter, with their responses
+    pub fn export_history_har(&self, filter: Option<&str>, path: &str) -> Result<(), String> {
+        let history = self.db.get_history(filter).map_err(|e| e.to_string())?;
+        Har::from_history(&history).write(path)
+    }
+
+    // The variables of the selected environment (or the one given to `cute run --env`)
+    pub fn environment_variables(&self) -> Vec<(String, String)> {
+        self.environment_values()
+            .into_iter()
+            .map(|(name, value, _)| (name, value))
+            .collect()
+    }
+
+    // The secret variables of the environment, these are masked in the history
+    pub fn secret_variables(&self) -> Vec<(String, String)> {
+        self.environment_values()
+            .into_iter()
+            .filter(|(_, _, secret)| *secret)
+            .map(|(name, value, _)| (name, value))
+            .collect()
+    }
+
+    fn environment_values(&self) -> Vec<(String, String, bool)> {
+        let env = match self.environment {
+            Some(id) => self
+                .db
+                .get_environments()
+                .ok()
+                .and_then(|envs| envs.into_iter().find(|env| env.get_id() == id)),
+            None => self.db.get_selected_environment(),
+        };
+        env.map(|env| env.variables)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|var| {
+                let value = match var.key_id {
+                    Some(id) => self
+                        .db
+                        .get_key_by_id(id)
+                        .map(|key| key.get_key().to_string())
+                        .unwrap_or_default(),
+                    None => var.value,
+                };
+                (var.name, value, var.key_id.is_some())
+            })
+            .collect()
+    }
+
+    // The serialized curl command of a saved command, with the {{variables}} of its
+    // collection resolved. The environment's variables win over the collection's
+    pub fn resolve_variables(&self, cmd: &SavedCommand) -> String {
+        let mut variables = cmd
+            .collection_id
+            .and_then(|id| self.db.get_collection_variables(id).ok())
+            .unwrap_or_default();
+        variables.extend(self.environment_variables());
+        substitute_json(cmd.get_curl_json(), &variables)
+    }
+
+    // Marks or unmarks the saved command under the cursor
+    pub fn toggle_mark(&mut self) {
+        if let Screen::SavedCommands { id, .. } = self.current_screen {
+            let commands = self.db.get_commands(id).unwrap_or_default();
+            if let Some(cmd) = commands.get(self.cursor) {
+                let id = cmd.get_id();
+                match self.marked.iter().position(|marked| *marked == id) {
+                    Some(index) => {
+                        self.marked.remove(index);
+                    }
+                    None => self.marked.push(id),
+                }
+            }
+        }
+    }
+
+    // The marked commands, or the one under the cursor when none are marked
+    pub fn commands_to_move(&self) -> Vec<i32> {
+        if !self.marked.is_empty() {
+            return self.marked.clone();
+        }
+        match self.current_screen {
+            Screen::SavedCommands { id, .. } => self
+                .db
+                .get_commands(id)
+                .unwrap_or_default()
+                .get(self.cursor)
+                .map(|cmd| vec![cmd.get_id()])
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        }
+    }
+
+    // Moves (or copies) saved commands into a collection
+    pub fn move_commands(
+        &mut self,
+        ids: &[i32],
+        collection: i32,
+        copy: bool,
+    ) -> Result<String, String> {
+        let name = self.db.get_collection_by_id(collection)?.name;
+        for id in ids {
+            match copy {
+                true => self.db.copy_command_to_collection(*id, collection),
+                false => self.db.set_command_collection(*id, Some(collection)),
             }
-            Err(e) => Err(e.into()),
+            .map_err(|e| e.to_string())?;
         }
+        self.marked.clear();
+        let commands = match ids.len() {
+            1 => String::from("1 command"),
+            n => format!("{} commands", n),
+        };
+        Ok(match copy {
+            true => format!("Copied {} to {}", commands, name),
+            false => format!("Moved {} to {}", commands, name),
+        })
     }
 
-    // Takes an array index of the selected item
-    pub fn execute_saved_command(&mut self, json: &str) {
+    // Creates an empty collection and moves (or copies) the commands into it, returns its id
+    pub fn create_collection(
+        &mut self,
+        name: &str,
+        ids: &[i32],
+        copy: bool,
+    ) -> Result<i32, String> {
+        if name.is_empty() {
+            return Err(String::from("The collection needs a name"));
+        }
+        let id = self
+            .db
+            .add_collection(name, "", &[])
+            .map_err(|e| e.to_string())?;
+        if !ids.is_empty() {
+            self.move_commands(ids, id, copy)?;
+        }
+        Ok(id)
+    }
+
+    // Renders a saved command (variables resolved) or the current command as a code snippet
+    pub fn generate_code(&self, id: Option<i32>, language: Language) -> Result<String, String> {
+        match id {
+            Some(id) => {
+                let cmd = self.db.get_command_by_id(id).map_err(|e| e.to_string())?;
+                let curl: Curl = serde_json::from_str(&self.resolve_variables(&cmd))
+                    .map_err(|e| e.to_string())?;
+                Ok(generate(&curl, language))
+            }
+            None => Ok(generate(&self.command, language)),
+        }
+    }
+
+    // Copies a serialized command (or the current command if None) as a curl
+    // command quoted for the shell in the config
+    pub fn copy_curl_command(&self, json: Option<&str>) -> Result<(), String> {
+        let (shell, multiline) = (
+            self.config.get_command_shell(),
+            self.config.get_multiline_commands(),
+        );
+        let cmd = match json {
+            Some(json) => serde_json::from_str::<Curl>(json)
+                .map_err(|e| e.to_string())?
+                .render_command(shell, multiline),
+            None => self.command.render_command(shell, multiline),
+        };
+        self.copy_to_clipboard(&cmd)
+    }
+
+    // Copies a serialized command (or the current command if None) as a wget
+    // command, the message lists the options wget has no equivalent for
+    pub fn copy_wget_command(&self, json: Option<&str>) -> Result<String, String> {
+        let (shell, multiline) = (
+            self.config.get_command_shell(),
+            self.config.get_multiline_commands(),
+        );
+        let (cmd, unsupported) = match json {
+            Some(json) => serde_json::from_str::<Curl>(json)
+                .map_err(|e| e.to_string())?
+                .build_wget_command(shell, multiline),
+            None => self.command.build_wget_command(shell, multiline),
+        };
+        self.copy_to_clipboard(&cmd)?;
+        if unsupported.is_empty() {
+            Ok(String::from("wget command copied to clipboard"))
+        } else {
+            Ok(format!(
+                "wget command copied to clipboard, wget has no equivalent for: {}",
+                unsupported.join(", ")
+            ))
+        }
+    }
+
+    // Takes the serialized curl command of a saved command, the executed
+    // command is kept as the current command so the response can be inspected
+    pub fn execute_saved_command(&mut self, json: &str) -> Result<(), String> {
         let command: Result<Curl, String> = serde_json::from_str(json).map_err(|e| e.to_string());
         match command {
             Ok(mut cmd) => {
                 cmd.easy_from_opts();
-                match cmd.execute(None) {
-                    Ok(_) => self.set_response(&cmd.get_response().unwrap_or("".to_string())),
-                    Err(e) => self.set_response(&e),
-                };
+                cmd.set_max_body_size(self.config.get_max_body_size());
+                let start = Instant::now();
+                let result = cmd.execute(None);
+                self.command = cmd;
+                self.log_history(json, start.elapsed(), result.as_ref().err());
+                match result {
+                    Ok(_) => Ok(()),
+                    Err(e) => {
+                        self.set_response(&e);
+                        Err(e)
+                    }
+                }
+            }
+            Err(e) => {
+                self.set_response(&e);
+                Err(e)
             }
-            Err(e) => self.set_response(&e),
+        }
+    }
+
+    // Shows the response menu after executing a request. Failed requests and 4xx/5xx
+    // responses are shown on the error screen, which goes back to the response menu
+    pub fn show_response(&mut self, result: Result<(), String>) {
+        match result {
+            Ok(()) => {
+                self.goto_screen(&Screen::Response(None));
+                let summary = self.command.get_response_summary();
+                if summary.status.is_some_and(|status| status >= 400) {
+                    let body = self.command.get_response().unwrap_or_default();
+                    self.goto_screen(&Screen::Error(http_failure(&summary, &body)));
+                }
+            }
+            Err(e) => self.goto_screen(&Screen::Error(e)),
+        }
+    }
+
+    // Executes a saved command and collects what we need to report on it
+    // outside of the TUI (status, headers, body)
+    pub fn run_saved_command(&mut self, cmd: &SavedCommand) -> RunResult {
+        let name = cmd
+            .label
+            .clone()
+            .unwrap_or_else(|| format!("Command {}", cmd.get_id()));
+        let start = std::time::Instant::now();
+        let result = self.execute_saved_command(&self.resolve_variables(cmd));
+        let duration = start.elapsed();
+        match result {
+            Ok(()) => RunResult {
+                name,
+                status: self.command.get_response_code(),
+                headers: self.command.get_response_headers(),
+                body: self.command.get_response().unwrap_or_default(),
+                error: None,
+                duration,
+                summary: self.command.get_response_summary(),
+            },
+            Err(e) => RunResult {
+                name,
+                status: self.command.get_response_code(),
+                headers: self.command.get_response_headers(),
+                body: String::new(),
+                error: Some(e),
+                duration,
+                summary: self.command.get_response_summary(),
+            },
+        }
+    }
+
+    // Runs every saved command in the collection, keeping the results
+    // in self.collection_run so we can report on them
+    pub fn run_collection(&mut self, id: i32) -> Result<(), String> {
+        let collection = self.db.get_collection_by_id(id)?;
+        let commands = self.db.get_commands(Some(id)).map_err(|e| e.to_string())?;
+        let mut run = CollectionRun::new(collection.get_name());
+        for cmd in commands.iter() {
+            run.results.push(self.run_saved_command(cmd));
+        }
+        self.collection_run = Some(run);
+        Ok(())
+    }
+
+    // Starts benchmarking a saved command, or the current command if no id
+    // is given, on a worker thread. Its report is polled into self.benchmark
+    pub fn run_benchmark(&mut self, id: Option<i32>, config: &BenchConfig) -> Result<(), String> {
+        let json = match id {
+            Some(id) => {
+                self.resolve_variables(&self.db.get_command_by_id(id).map_err(|e| e.to_string())?)
+            }
+            None => serde_json::to_string(&self.command).map_err(|e| e.to_string())?,
+        };
+        self.cancel_benchmark();
+        self.benchmark = Some(BenchReport::new(*config));
+        self.bench_run = Some(BenchRun::start(json, *config));
+        Ok(())
+    }
+
+    // Copies the progress of the running benchmark into self.benchmark,
+    // and the final report once it's done
+    pub fn poll_benchmark(&mut self) -> Result<(), String> {
+        match self.bench_run.take() {
+            Some(run) if run.is_finished() => self.benchmark = Some(run.finish()?),
+            Some(run) => {
+                self.benchmark = Some(run.progress());
+                self.bench_run = Some(run);
+            }
+            None => {}
+        }
+        Ok(())
+    }
+
+    pub fn is_benchmark_running(&self) -> bool {
+        self.bench_run.is_some()
+    }
+
+    // The thread stops on its own, keeping whatever was recorded so far
+    pub fn cancel_benchmark(&mut self) {
+        if let Some(run) = self.bench_run.take() {
+            let finished = run.is_finished();
+            run.cancel();
+            let mut report = run.progress();
+            report.cancelled |= !finished;
+            self.benchmark = Some(report);
+        }
+    }
+
+    // Compares the responses of two history entries
+    pub fn diff_history(&self, left: i32, right: i32) -> Result<ResponseDiff, String> {
+        let left = self.db.get_history_entry(left).map_err(|e| e.to_string())?;
+        let right = self
+            .db
+            .get_history_entry(right)
+            .map_err(|e| e.to_string())?;
+        Ok(ResponseDiff::new(
+            &ResponseSnapshot::from(&left),
+            &ResponseSnapshot::from(&right),
+        ))
+    }
+
+    // Compares a saved example response (a file) with a history entry
+    pub fn diff_example(&self, path: &str, id: i32) -> Result<ResponseDiff, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let example = ResponseSnapshot::from_example(&format!("Example {}", path), &text);
+        let entry = self.db.get_history_entry(id).map_err(|e| e.to_string())?;
+        Ok(ResponseDiff::new(&example, &ResponseSnapshot::from(&entry)))
+    }
+
+    // Runs a saved command against two environments, given as "staging, prod",
+    // and compares the responses. The selected environment is left as it was
+    pub fn diff_environments(&mut self, id: i32, names: &str) -> Result<ResponseDiff, String> {
+        let environments = self.db.get_environments().map_err(|e| e.to_string())?;
+        let picked = names
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| {
+                environments
+                    .iter()
+                    .find(|env| env.name.eq_ignore_ascii_case(name))
+                    .ok_or_else(|| format!("No environment named {}", name))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        if picked.len() != 2 {
+            return Err(String::from(
+                "Two environments are needed, e.g. staging, prod",
+            ));
+        }
+        let cmd = self.db.get_command_by_id(id).map_err(|e| e.to_string())?;
+        let previous = self.environment;
+        let snapshots = picked
+            .iter()
+            .map(|env| {
+                self.environment = Some(env.get_id());
+                let result = self.run_saved_command(&cmd);
+                ResponseSnapshot {
+                    label: format!("{}: {}", env.name, result.name),
+                    headers: result.headers,
+                    body: result.error.unwrap_or(result.body),
+                }
+            })
+            .collect::<Vec<ResponseSnapshot>>();
+        self.environment = previous;
+        Ok(ResponseDiff::new(&snapshots[0], &snapshots[1]))
+    }
+
+    // The diff is computed once, the screen only renders it
+    pub fn show_diff(&mut self, diff: Result<ResponseDiff, String>) {
+        match diff {
+            Ok(diff) => {
+                self.diff = Some(diff);
+                self.goto_screen(&Screen::Diff);
+            }
+            Err(e) => self.goto_screen(&Screen::Error(e)),
         }
     }
 
@@ -403,7 +1130,7 @@ impl<'a> App<'a> {
     pub fn clear_all_options(&mut self) {
         self.command.opts.clear();
         self.messages.clear();
-        self.response = None;
+        self.command.clear_response();
     }
 
     fn has_app_option(&self, opt: &AppOptions) -> bool {
@@ -429,8 +1156,178 @@ impl<'a> App<'a> {
         }
     }
 
+    // Loads the current response into the body viewer
+    pub fn open_body_viewer(&mut self) {
+        self.format_body(None, false, false);
+    }
+
+    // Loads the raw response body into the viewer, formatted for its Content-Type
+    // unless a formatter is given. Large bodies are only formatted once fully loaded
+    fn format_body(&mut self, formatter: Option<Formatter>, raw: bool, full: bool) {
+        let content_type = self.command.get_response_content_type();
+        let encoding = self.command.get_response_charset();
+        let truncated = self.command.is_response_truncated();
+        let whole = match truncated && full {
+            true => match self.command.read_response_body() {
+                Ok(body) => Some(body),
+                Err(e) => {
+                    self.viewer.status = Some(format!("Error: {}", e));
+                    return;
+                }
+            },
+            false => None,
+        };
+        let bytes = whole
+            .as_deref()
+            .unwrap_or(self.command.get_response_bytes());
+        if bytes.is_empty() || (truncated && !full) {
+            let body = self.command.get_response_str().unwrap_or_default();
+            self.viewer = BodyViewer::new(body, Syntax::detect(content_type.as_deref(), body));
+            if truncated {
+                self.viewer.truncated = Some(self.command.get_response_size());
+            }
+            return;
+        }
+        let detected = Formatter::detect(content_type.as_deref(), bytes);
+        let format = formatter.unwrap_or(detected);
+        let (body, status) = match raw {
+            true => (decode(bytes, encoding), None),
+            false => match format.format(bytes, encoding) {
+                Ok(body) => (body, None),
+                Err(e) => (
+                    decode(bytes, encoding),
+                    Some(format!("Not valid {}: {}", format.name(), e)),
+                ),
+            },
+        };
+        let syntax = match raw || status.is_some() {
+            true => Syntax::detect(content_type.as_deref(), &body),
+            false => format.syntax(),
+        };
+        self.viewer = BodyViewer::new(&body, syntax);
+        self.viewer.format = Some(format);
+        self.viewer.raw = raw;
+        self.viewer.overridden = format != detected;
+        self.viewer.status = status;
+    }
+
+    // Replaces the start of a large body in the viewer with all of it
+    pub fn load_full_body(&mut self) {
+        if self.viewer.truncated.is_none() {
+            return;
+        }
+        self.format_body(None, false, true);
+        if self.viewer.status.is_none() {
+            self.viewer.status = Some(format!("Loaded {} lines", self.viewer.line_count()));
+        }
+        self.cursor = 0;
+    }
+
+    // Decodes the response again with the given charset, an empty label
+    // goes back to the charset the server declared
+    pub fn set_response_charset(&mut self, label: &str) -> Result<(), String> {
+        let charset = match label.trim() {
+            "" => None,
+            label => Some(charset_for_label(label)?),
+        };
+        self.command.set_charset(charset);
+        Ok(())
+    }
+
+    // Switches the body viewer between the formatted body and the body as received
+    pub fn toggle_raw_body(&mut self) {
+        if let Some(format) = self.viewer.format {
+            self.format_body(Some(format), !self.viewer.raw, true);
+            self.cursor = 0;
+        }
+    }
+
+    // Formats the body with the next formatter, for servers sending the wrong Content-Type
+    pub fn cycle_body_format(&mut self) {
+        if let Some(format) = self.viewer.format {
+            self.format_body(Some(format.next()), false, true);
+            self.cursor = 0;
+        }
+    }
+
+    // Moves the body viewer cursor a screen up or down
+    pub fn scroll_page(&mut self, down: bool) {
+        let page = self.viewer.page.max(1);
+        self.cursor = match down {
+            true => (self.cursor + page).min(self.viewer.len().saturating_sub(1)),
+            false => self.cursor.saturating_sub(page),
+        };
+    }
+
+    // Moves the body viewer cursor to the first or last line
+    pub fn scroll_to_end(&mut self, bottom: bool) {
+        self.cursor = match bottom {
+            true => self.viewer.len().saturating_sub(1),
+            false => 0,
+        };
+    }
+
+    // Loads the headers of the last response into the viewer, so they can be searched
+    pub fn open_headers_viewer(&mut self) {
+        self.viewer = BodyViewer::new(&self.command.get_response_headers(), Syntax::Headers);
+    }
+
+    // Opens a prompt over the body viewer, keeping the cursor where it was
+    pub fn open_viewer_prompt(&mut self, opt: InputOpt) {
+        let cursor = self.cursor;
+        let current = match opt {
+            InputOpt::BodyFilter => self.viewer.filter.clone(),
+            InputOpt::BodySearch => self.viewer.search.clone(),
+            _ => None,
+        };
+        self.goto_screen(&Screen::ViewBody(Some(opt)));
+        self.set_app_input(&current.unwrap_or_default());
+        self.cursor = cursor;
+        self.input_mode = InputMode::Editing;
+    }
+
+    // Moves the body viewer cursor to the next or previous search match
+    pub fn jump_to_match(&mut self, forward: bool) {
+        self.cursor = self.viewer.jump_to_match(self.cursor, forward);
+    }
+
+    // Folds or unfolds the block under the cursor in the body viewer
+    pub fn toggle_fold(&mut self) {
+        self.cursor = self.viewer.toggle_fold(self.cursor);
+    }
+
+    pub fn toggle_all_folds(&mut self) {
+        self.viewer.toggle_all();
+        self.cursor = self.cursor.min(self.viewer.len().saturating_sub(1));
+    }
+
+    // Copies everything the body viewer shows, e.g. the output of a filter
+    pub fn copy_view(&mut self) {
+        let text = self.viewer.text();
+        self.viewer.status = Some(match self.copy_to_clipboard(text) {
+            Ok(()) => format!("Copied {} lines to clipboard", self.viewer.line_count()),
+            Err(e) => format!("Error: {}", e),
+        });
+    }
+
+    // Copies the block under the cursor in the body viewer
+    pub fn yank_subtree(&mut self) {
+        let status = match self.viewer.subtree(self.cursor) {
+            Some(text) => match self.copy_to_clipboard(&text) {
+                Ok(()) => format!("Copied {} lines to clipboard", text.lines().count()),
+                Err(e) => format!("Error: {}", e),
+            },
+            None => String::from("Nothing to copy"),
+        };
+        self.viewer.status = Some(status);
+    }
+
+    /// http response from executed command
+    pub fn response(&self) -> Option<&str> {
+        self.command.get_response_str()
+    }
+
     pub fn set_response(&mut self, response: &str) {
-        self.response = Some(response.to_string());
         self.command.set_response(response);
     }
 
@@ -473,7 +1370,10 @@ impl<'a> App<'a> {
 #[cfg(test)]
 pub mod tests {
     use super::App;
+    use crate::request::codegen::Language;
     use crate::request::curl::{AuthKind, Curl};
+    use crate::request::diff::DiffKind;
+    use crate::request::shell::Shell;
 
     #[test]
     fn test_basic_get_method() {
@@ -505,7 +1405,10 @@ pub mod tests {
     }
     #[test]
     fn test_basic_put_method() {
-        let mut server = mockito::Server::new_with_port(12343);
+        let mut server = mockito::Server::new_with_opts(mockito::ServerOpts {
+            port: 12343,
+            ..Default::default()
+        });
         let mut app = App::default();
         let url = server.url();
         app.command.set_url(&url);
@@ -541,12 +1444,15 @@ pub mod tests {
                     ))
                 .unwrap()
                 .get_curl_flag_value(),
-            "-H \"Content-Type: application/json\""
+            "-H 'Content-Type: application/json'"
         );
     }
     #[test]
     fn test_basic_delete_method() {
-        let mut server = mockito::Server::new_with_port(12344);
+        let mut server = mockito::Server::new_with_opts(mockito::ServerOpts {
+            port: 12344,
+            ..Default::default()
+        });
         let mut app = App::default();
         let url = server.url();
         app.command.set_url(&url);
@@ -607,12 +1513,630 @@ pub mod tests {
         let mock = server.mock("GET", "/").with_body("hello world").create();
         let _ = app.execute_command();
         let json = serde_json::to_string(&app.command).unwrap();
-        app.execute_saved_command(&json);
+        let _ = app.execute_saved_command(&json);
         mock.match_header("Content-Type", "application/json")
             .expect(2)
             .assert();
     }
 
+    #[test]
+    fn test_parse_input_return_screen() {
+        use crate::display::inputopt::InputOpt;
+        use crate::request::report::ReportFormat;
+        use crate::screens::input::input_screen::parse_input;
+        use crate::screens::screen::Screen;
+        let mut app = App::default();
+        // the report prompt shows where the report went, or why it wasn't written
+        parse_input(
+            String::from("report.xml"),
+            InputOpt::RunReport(ReportFormat::JUnit),
+            &mut app,
+        );
+        assert!(matches!(
+            app.current_screen,
+            Screen::RunResults(Some(InputOpt::RequestError(_)))
+        ));
+        parse_input(String::from("5"), InputOpt::MaxRedirects, &mut app);
+        assert_eq!(app.current_screen, Screen::RequestMenu(None));
+    }
+
+    #[test]
+    fn test_diff_sources() {
+        use crate::database::db::EnvVariable;
+        use crate::screens::screen::Screen;
+        let mut app = App::new_test_db();
+        let mut server = mockito::Server::new();
+        let url = server.url();
+        let staging = server
+            .mock("GET", "/staging/items")
+            .with_body(r#"{"items": [1, 2]}"#)
+            .create();
+        let prod = server
+            .mock("GET", "/prod/items")
+            .with_body(r#"{"items": [1]}"#)
+            .create();
+        for name in ["staging", "prod"] {
+            let base = EnvVariable {
+                name: String::from("base"),
+                value: format!("{url}/{name}"),
+                key_id: None,
+            };
+            app.db.add_environment(name, &[base]).unwrap();
+        }
+        let mut curl = Curl::new_serializing();
+        curl.set_url("{{base}}/items");
+        app.db
+            .add_command(
+                &curl.get_command_string(),
+                serde_json::to_string(&curl).unwrap(),
+                None,
+            )
+            .unwrap();
+        let id = app.db.get_commands(None).unwrap()[0].get_id();
+        assert!(app.diff_environments(id, "staging").is_err());
+        assert!(app.diff_environments(id, "staging, qa").is_err());
+        let diff = app.diff_environments(id, "Staging, prod").unwrap();
+        staging.assert();
+        prod.assert();
+        assert!(diff.left_label.starts_with("staging: "));
+        assert!(diff.right_label.starts_with("prod: "));
+        assert!(!diff.is_identical());
+        assert_eq!(app.environment, None);
+
+        // the last run against a saved example
+        let path = std::env::temp_dir().join("cute_diff_example.json");
+        std::fs::write(&path, r#"{"items": [1]}"#).unwrap();
+        let latest = app.db.get_history(None).unwrap()[0].get_id();
+        let diff = app.diff_example(&path.to_string_lossy(), latest);
+        std::fs::remove_file(&path).unwrap();
+        let diff = diff.unwrap();
+        assert!(diff.rows.iter().all(|row| row.kind == DiffKind::Same));
+        app.show_diff(Ok(diff));
+        assert_eq!(app.current_screen, Screen::Diff);
+        assert_eq!(app.items.len(), app.diff.as_ref().unwrap().rows.len());
+    }
+
+    #[test]
+    fn test_export_marked_commands() {
+        use crate::display::inputopt::InputOpt;
+        use crate::screens::input::input_screen::parse_input;
+        use crate::screens::screen::Screen;
+        let mut app = App::new_test_db();
+        for url in ["https://example.com/a", "https://example.com/b"] {
+            let mut curl = Curl::new_serializing();
+            curl.set_url(url);
+            app.db
+                .add_command(
+                    &curl.get_command_string(),
+                    serde_json::to_string(&curl).unwrap(),
+                    None,
+                )
+                .unwrap();
+        }
+        let id = app.db.get_commands(None).unwrap()[1].get_id();
+        app.goto_screen(&Screen::SavedCommands {
+            id: None,
+            opt: None,
+        });
+        app.toggle_mark();
+        app.cursor = 1;
+        app.toggle_mark();
+        app.toggle_mark();
+        assert_eq!(app.marked.len(), 1);
+        assert_ne!(app.marked[0], id);
+        // the export prompt of the saved commands screen
+        let opt = InputOpt::ExportCollection(None);
+        app.goto_screen(&Screen::SavedCommands {
+            id: None,
+            opt: Some(opt.clone()),
+        });
+        let path = std::env::temp_dir().join("cute_export_marked.har");
+        parse_input(path.to_string_lossy().to_string(), opt, &mut app);
+        let har: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let entries = har["log"]["entries"].as_array().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0]["request"]["url"], "https://example.com/a");
+        assert!(app.marked.is_empty());
+        assert!(matches!(
+            app.current_screen,
+            Screen::SavedCommands {
+                opt: Some(InputOpt::AlertMessage(_)),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_move_commands() {
+        let mut app = App::new_test_db();
+        for url in ["https://example.com/a", "https://example.com/b"] {
+            let mut curl = Curl::new_serializing();
+            curl.set_url(url);
+            app.db
+                .add_command(
+                    &curl.get_command_string(),
+                    serde_json::to_string(&curl).unwrap(),
+                    None,
+                )
+                .unwrap();
+        }
+        let ids = app
+            .db
+            .get_commands(None)
+            .unwrap()
+            .iter()
+            .map(|cmd| cmd.get_id())
+            .collect::<Vec<i32>>();
+        assert!(app.create_collection("", &ids, false).is_err());
+        let api = app.create_collection("API", &ids, false).unwrap();
+        assert_eq!(app.db.get_commands(Some(api)).unwrap().len(), 2);
+
+        let copies = app.create_collection("Copies", &[], false).unwrap();
+        assert_eq!(app.db.get_commands(Some(copies)).unwrap().len(), 0);
+        let msg = app.move_commands(&ids[..1], copies, true).unwrap();
+        assert_eq!(msg, "Copied 1 command to Copies");
+        assert_eq!(app.db.get_commands(Some(api)).unwrap().len(), 2);
+        assert_eq!(app.db.get_commands(None).unwrap().len(), 3);
+
+        // taken out of the collection, but still saved
+        app.db.set_command_collection(ids[1], None).unwrap();
+        assert_eq!(app.db.get_commands(Some(api)).unwrap().len(), 1);
+        assert_eq!(app.db.get_commands(None).unwrap().len(), 3);
+
+        // the result of a move is an alert, not a prompt to type into
+        use crate::display::inputopt::InputOpt;
+        use crate::screens::screen::Screen;
+        assert!(Screen::MoveCommands {
+            ids: ids.clone(),
+            copy: false,
+            opt: Some(InputOpt::NewCollection(ids, false)),
+        }
+        .is_input_screen());
+        assert!(!Screen::SavedCommands {
+            id: None,
+            opt: Some(InputOpt::AlertMessage(msg)),
+        }
+        .is_input_screen());
+    }
+
+    #[test]
+    fn test_build_command_string() {
+        use crate::display::AppOptions;
+        let mut app = App::default();
+        app.command.set_method(crate::request::curl::Method::Post);
+        app.add_app_option(AppOptions::URL("https://example.com/items".to_string()));
+        app.add_app_option(AppOptions::Headers("X-Name: a b".to_string()));
+        app.add_app_option(AppOptions::RequestBody("{\"a\": \"b c\"}".to_string()));
+        app.add_app_option(AppOptions::Auth(AuthKind::Bearer("token".to_string())));
+        assert_eq!(
+            app.command.get_command_string(),
+            "curl -X POST https://example.com/items -H 'X-Name: a b' -d '{\"a\": \"b c\"}' \
+             -H 'Authorization: Bearer token'"
+        );
+        assert_eq!(
+            app.command.render_command(Shell::PowerShell, true),
+            "curl.exe `\n  -X POST `\n  https://example.com/items `\n  -H 'X-Name: a b' `\n  \
+             -d '{\\\"a\\\": \\\"b c\\\"}' `\n  -H 'Authorization: Bearer token'"
+        );
+        // a saved command renders the same, headers aren't repeated
+        let json = serde_json::to_string(&app.command).unwrap();
+        let mut curl: Curl = serde_json::from_str(&json).unwrap();
+        curl.easy_from_opts();
+        assert_eq!(curl.get_command_string(), app.command.get_command_string());
+    }
+
+    #[test]
+    fn test_bearer_auth() {
+        use crate::display::AppOptions;
+        let mut server = mockito::Server::new();
+        let url = server.url();
+        let mock = server
+            .mock("GET", "/me")
+            .match_header("authorization", "Bearer t0ken")
+            .with_status(200)
+            .create();
+        // the scheme is added once, whether or not the token starts with it
+        for token in ["t0ken", "Bearer t0ken", "bearer t0ken"] {
+            let mut app = App::default();
+            app.add_app_option(AppOptions::URL(format!("{url}/me")));
+            app.add_app_option(AppOptions::Auth(AuthKind::Bearer(token.to_string())));
+            app.command.set_method(crate::request::curl::Method::Get);
+            assert!(app
+                .command
+                .get_command_string()
+                .ends_with("-H 'Authorization: Bearer t0ken'"));
+            assert!(app.command.execute(None).is_ok());
+        }
+        mock.expect(3).assert();
+    }
+
+    #[test]
+    fn test_build_wget_command() {
+        use crate::display::AppOptions;
+        let mut app = App::default();
+        app.command.set_method(crate::request::curl::Method::Post);
+        app.add_app_option(AppOptions::URL("https://example.com/items".to_string()));
+        app.add_app_option(AppOptions::Headers(
+            "Content-Type: application/json".to_string(),
+        ));
+        app.add_app_option(AppOptions::RequestBody("{\"name\": \"it's\"}".to_string()));
+        app.add_app_option(AppOptions::Auth(AuthKind::Basic("user:pass".to_string())));
+        app.add_app_option(AppOptions::Outfile("out.json".to_string()));
+        let (cmd, unsupported) = app.command.build_wget_command(Shell::Posix, false);
+        assert_eq!(
+            cmd,
+            "wget --method=POST '--header=Content-Type: application/json' \
+             '--body-data={\"name\": \"it'\\''s\"}' --user=user --password=pass \
+             --auth-no-challenge --output-document=out.json --max-redirect=0 \
+             https://example.com/items"
+        );
+        assert!(unsupported.is_empty());
+
+        app.add_app_option(AppOptions::FollowRedirects);
+        app.add_app_option(AppOptions::ProxyTunnel);
+        app.add_app_option(AppOptions::Auth(AuthKind::Ntlm));
+        let (cmd, unsupported) = app.command.build_wget_command(Shell::Posix, false);
+        assert!(!cmd.contains("--max-redirect=0"));
+        assert_eq!(unsupported, vec!["proxy tunnel", "NTLM authentication"]);
+
+        let mut app = App::default();
+        app.add_app_option(AppOptions::URL("https://example.com/items".to_string()));
+        app.add_app_option(AppOptions::UploadFile("items.json".to_string()));
+        app.add_app_option(AppOptions::NewCookie("session: abc".to_string()));
+        app.add_app_option(AppOptions::NewCookieSession);
+        let (cmd, unsupported) = app.command.build_wget_command(Shell::Posix, false);
+        assert!(cmd.starts_with("wget --method=PUT --body-file=items.json"));
+        assert!(cmd.contains("'--header=Cookie: session=abc'"));
+        assert_eq!(unsupported, vec!["new cookie session"]);
+        assert!(AppOptions::MatchWildcard.get_wget_flag_value().is_err());
+    }
+
+    #[test]
+    fn test_run_saved_command() {
+        let mut app = App::new_test_db();
+        let mut server = mockito::Server::new();
+        let url = server.url();
+        app.add_app_option(crate::display::AppOptions::URL(format!("{url}/missing")));
+        app.command.set_method(crate::request::curl::Method::Get);
+        let json = serde_json::to_string(&app.command).unwrap();
+        app.db.add_command("curl", json, None).unwrap();
+        let mock = server
+            .mock("GET", "/missing")
+            .with_status(404)
+            .with_body("not found")
+            .create();
+        let cmd = app.db.get_commands(None).unwrap()[0].clone();
+        let result = app.run_saved_command(&cmd);
+        mock.expect(1).assert();
+        assert_eq!(result.status, Some(404));
+        assert_eq!(result.body, "not found");
+        assert!(result.headers.starts_with("HTTP/1.1 404"));
+        assert!(!result.passed());
+        assert_eq!(result.exit_code(), 4);
+    }
+
+    #[test]
+    fn test_import_openapi() {
+        let mut app = App::new_test_db();
+        let mut server = mockito::Server::new();
+        let path = std::env::temp_dir().join("cute_import_openapi.yaml");
+        let spec = format!(
+            "openapi: 3.0.0\ninfo:\n  title: Users\nservers:\n  - url: {}/v1\npaths:\n  /users/{{id}}:\n    get:\n      summary: Get a user\n",
+            server.url()
+        );
+        std::fs::write(&path, spec).unwrap();
+        let res = app.import_collection(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+        assert!(res.is_ok());
+        let collections = app.db.get_collections().unwrap();
+        assert_eq!(collections[0].name, "Users");
+        let cmd = app.db.get_commands(None).unwrap()[0].clone();
+        assert_eq!(cmd.description.as_deref(), Some("Get a user"));
+        let id = cmd.collection_id.unwrap();
+        let mut variables = app.db.get_collection_variables(id).unwrap();
+        assert_eq!(
+            variables,
+            vec![(String::from("baseUrl"), format!("{}/v1", server.url()))]
+        );
+        variables.push((String::from("id"), String::from("7")));
+        app.db.set_collection_variables(id, &variables).unwrap();
+        let mock = server.mock("GET", "/v1/users/7").with_body("{}").create();
+        let result = app.run_saved_command(&cmd);
+        mock.expect(1).assert();
+        assert_eq!(result.status, Some(200));
+    }
+
+    #[test]
+    fn test_import_bruno() {
+        let mut app = App::new_test_db();
+        let root = std::env::temp_dir().join("cute_app_bruno_test");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("orders")).unwrap();
+        std::fs::write(
+            root.join("bruno.json"),
+            r#"{"version": "1", "name": "Shop"}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("collection.bru"),
+            "vars:pre-request {\n  host: shop.example.com\n}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("orders/get.bru"),
+            "meta {\n  name: Get order\n  seq: 1\n}\n\nget {\n  url: https://{{host}}/orders/1\n}\n",
+        )
+        .unwrap();
+        let res = app.import_collection(root.to_str().unwrap());
+        std::fs::remove_dir_all(&root).unwrap();
+        assert_eq!(
+            res.unwrap().to_string(),
+            "Imported 1 request into 1 collection"
+        );
+        let cmd = app.db.get_commands(None).unwrap()[0].clone();
+        assert_eq!(cmd.collection_name.as_deref(), Some("Shop / orders"));
+        assert!(app
+            .resolve_variables(&cmd)
+            .contains("https://shop.example.com/orders/1"));
+    }
+
+    #[test]
+    fn test_import_export_http_file() {
+        let mut app = App::new_test_db();
+        let path = std::env::temp_dir().join("cute_import_requests.http");
+        let text = "@baseUrl = https://api.example.com
+
+### Health
+# @name Health
+GET {{baseUrl}}/health
+
+### createItem
+# @name createItem
+POST {{baseUrl}}/items
+Content-Type: application/json
+
+{\"name\": \"CuTE\"}
+";
+        std::fs::write(&path, text).unwrap();
+        let res = app.import_collection(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            res.unwrap().to_string(),
+            "Imported 2 requests into 1 collection"
+        );
+        let collection = app.db.get_collections().unwrap()[0].clone();
+        assert_eq!(collection.get_name(), "cute_import_requests");
+        let cmd = app.db.get_commands(None).unwrap()[1].clone();
+        assert_eq!(cmd.label.as_deref(), Some("createItem"));
+        assert!(app
+            .resolve_variables(&cmd)
+            .contains("https://api.example.com/items"));
+
+        let path = std::env::temp_dir().join("cute_export_requests.http");
+        let res = app.export_commands(Some(collection.get_id()), path.to_str().unwrap());
+        let exported = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(res.is_ok());
+        assert_eq!(exported, text);
+    }
+
+    #[test]
+    fn test_generate_code() {
+        let app = App::new_test_db();
+        let collection = app.db.add_collection("Items", "", &[]).unwrap();
+        app.db
+            .set_collection_variables(
+                collection,
+                &[(
+                    String::from("baseUrl"),
+                    String::from("https://api.example.com"),
+                )],
+            )
+            .unwrap();
+        let mut curl = Curl::new_serializing();
+        curl.set_post_method();
+        curl.set_url("{{baseUrl}}/items");
+        curl.add_headers("Content-Type: application/json");
+        curl.set_request_body("{\"name\": \"CuTE\"}");
+        app.db
+            .add_command(
+                &curl.get_command_string(),
+                serde_json::to_string(&curl).unwrap(),
+                Some(collection),
+            )
+            .unwrap();
+        let id = app.db.get_commands(None).unwrap()[0].get_id();
+        let code = app.generate_code(Some(id), Language::Python).unwrap();
+        assert!(code.contains("    \"POST\",\n    \"https://api.example.com/items\",\n"));
+        assert!(code.contains("\"Content-Type\": \"application/json\""));
+        assert!(app.generate_code(Some(id + 1), Language::Go).is_err());
+    }
+
+    #[test]
+    fn test_import_postman_environment() {
+        let mut app = App::new_test_db();
+        let path = std::env::temp_dir().join("cute_import.postman_environment.json");
+        let env = r#"{"name": "Staging", "_postman_variable_scope": "environment", "values": [
+            {"key": "host", "value": "staging.example.com", "type": "default", "enabled": true},
+            {"key": "token", "value": "s3cret", "type": "secret", "enabled": true}]}"#;
+        std::fs::write(&path, env).unwrap();
+        let res = app.import_collection(path.to_str().unwrap());
+        // importing it again replaces it
+        let again = app.import_collection(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            res.unwrap().to_string(),
+            "Imported environment Staging with 2 variables"
+        );
+        assert!(again.is_ok());
+        let envs = app.db.get_environments().unwrap();
+        assert_eq!(envs.len(), 1);
+        // the secret is kept with the saved keys
+        assert!(envs[0].variables[1].value.is_empty());
+        assert_eq!(app.db.get_keys().unwrap().len(), 1);
+
+        let mut curl = Curl::new_serializing();
+        curl.set_url("https://{{host}}/items");
+        curl.add_headers("Authorization: Bearer {{token}}");
+        app.db
+            .add_command(
+                &curl.get_command_string(),
+                serde_json::to_string(&curl).unwrap(),
+                None,
+            )
+            .unwrap();
+        let cmd = app.db.get_commands(None).unwrap()[0].clone();
+        assert!(app
+            .resolve_variables(&cmd)
+            .contains("https://{{host}}/items"));
+        app.db.select_environment(Some(envs[0].get_id())).unwrap();
+        let json = app.resolve_variables(&cmd);
+        assert!(json.contains("https://staging.example.com/items"));
+        assert!(json.contains("Bearer s3cret"));
+        // the history keeps the secret's name, it's resolved again on replay
+        app.log_history(&json, std::time::Duration::ZERO, None);
+        let logged = app.db.get_history(None).unwrap()[0]
+            .get_curl_json()
+            .to_string();
+        assert!(!logged.contains("s3cret"));
+        assert!(logged.contains("Bearer {{token}}"));
+        assert!(logged.contains("https://staging.example.com/items"));
+        app.db.delete_environment(envs[0].get_id()).unwrap();
+        assert!(app.db.get_keys().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_history_logged() {
+        let mut app = App::new_test_db();
+        let mut server = mockito::Server::new();
+        let url = server.url();
+        app.add_app_option(crate::display::AppOptions::URL(format!("{url}/users")));
+        app.command.set_method(crate::request::curl::Method::Get);
+        let mock = server
+            .mock("GET", "/users")
+            .with_status(201)
+            .with_body("created")
+            .create();
+        let _ = app.execute_command();
+        let json = serde_json::to_string(&app.command).unwrap();
+        let _ = app.execute_saved_command(&json);
+        mock.expect(2).assert();
+        let history = app.db.get_history(None).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].status, Some(201));
+        assert_eq!(history[0].method, "GET");
+        assert_eq!(history[0].url, format!("{url}/users"));
+        assert_eq!(history[0].size, 7);
+        assert_eq!(history[0].body, "created");
+        // the body is only kept in its own column
+        assert!(history
+            .iter()
+            .all(|entry| !entry.get_curl_json().contains("created")));
+        assert_eq!(app.db.get_history(Some("users")).unwrap().len(), 2);
+        assert_eq!(app.db.get_history(Some("201")).unwrap().len(), 2);
+        assert!(app.db.get_history(Some("POST")).unwrap().is_empty());
+        assert!(app.db.get_history(Some("%")).unwrap().is_empty());
+        assert!(app.db.get_history(Some("_sers")).unwrap().is_empty());
+        // only the most recent entries are kept
+        app.db.add_history(&history[0], 1).unwrap();
+        assert_eq!(app.db.get_history(None).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_large_response_truncated() {
+        let mut app = App::new_test_db();
+        let mut server = mockito::Server::new();
+        let body = "{\"line\": 1}\n".repeat(200_000);
+        server.mock("GET", "/").with_body(&body).create();
+        app.add_app_option(crate::display::AppOptions::URL(server.url()));
+        app.command.set_get_method();
+        app.command.set_max_body_size(Some(1024 * 1024));
+        app.command.execute(None).unwrap();
+        assert!(app.command.is_response_truncated());
+        // only the start of the body is kept in memory
+        assert_eq!(app.command.get_response_bytes().len(), 1024 * 1024);
+        assert_eq!(app.command.get_response_size(), body.len());
+        let response = app.command.get_response().unwrap();
+        assert_eq!(response.len(), 1024 * 1024);
+        // what's written to a file is the whole body
+        let path = std::env::temp_dir().join("cute_large_response.json");
+        app.command.set_outfile(&path.to_string_lossy());
+        app.command.write_output().unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(written, body);
+        app.set_response(&response);
+        app.open_body_viewer();
+        assert_eq!(app.viewer.truncated, Some(body.len()));
+        app.load_full_body();
+        assert_eq!(app.viewer.truncated, None);
+        assert_eq!(app.viewer.line_count(), 200_000);
+    }
+
+    #[test]
+    fn test_response_summary() {
+        let mut app = App::new_test_db();
+        let mut server = mockito::Server::new();
+        server
+            .mock("GET", "/")
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_body("{}")
+            .create();
+        app.add_app_option(crate::display::AppOptions::URL(server.url()));
+        app.command.set_get_method();
+        app.command.execute(None).unwrap();
+        let summary = app.command.get_response_summary();
+        assert_eq!(summary.status_text(), "201 Created");
+        assert_eq!(summary.version.as_deref(), Some("HTTP/1.1"));
+        assert_eq!(summary.content_type.as_deref(), Some("application/json"));
+        assert_eq!(summary.size, 2);
+        assert_eq!(summary.url, Some(format!("{}/", server.url())));
+    }
+
+    #[test]
+    fn test_http_failure_messages() {
+        let mut app = App::new_test_db();
+        let mut server = mockito::Server::new();
+        server.mock("GET", "/").with_status(404).create();
+        app.add_app_option(crate::display::AppOptions::URL(server.url()));
+        app.add_app_option(crate::display::AppOptions::FailOnError);
+        app.command.set_get_method();
+        let err = app.command.execute(None).unwrap_err();
+        assert!(err.starts_with("HTTP 404 Not Found"));
+        app.command.set_url("http://127.0.0.1:1");
+        let err = app.command.execute(None).unwrap_err();
+        assert!(err.contains("Connection refused"));
+    }
+
+    #[test]
+    fn test_response_charset() {
+        let mut app = App::new_test_db();
+        let mut server = mockito::Server::new();
+        server
+            .mock("GET", "/")
+            .with_header("content-type", "text/plain; charset=iso-8859-1")
+            .with_body(b"caf\xe9")
+            .create();
+        app.add_app_option(crate::display::AppOptions::URL(server.url()));
+        app.command.set_get_method();
+        app.command.execute(None).unwrap();
+        assert_eq!(app.command.get_response().unwrap(), "café");
+        app.set_response_charset("utf-8").unwrap();
+        assert_eq!(app.response(), Some("caf\u{fffd}"));
+        assert!(app.set_response_charset("klingon").is_err());
+        app.set_response_charset("").unwrap();
+        assert_eq!(app.response(), Some("café"));
+        // the body is written to a file as it was received
+        let path = std::env::temp_dir().join("cute_charset_output.txt");
+        app.command.set_outfile(path.to_str().unwrap());
+        app.command.write_output().unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"caf\xe9");
+        std::fs::remove_file(&path).unwrap();
+    }
+
     #[test]
     fn test_add_bearer_auth() {
         let mut app = App::default();
@@ -694,7 +2218,10 @@ pub mod tests {
     #[test]
     fn test_add_options() {
         let mut app = App::default();
-        let mut server = mockito::Server::new_with_port(12348);
+        let mut server = mockito::Server::new_with_opts(mockito::ServerOpts {
+            port: 12348,
+            ..Default::default()
+        });
         let url = "http://localhost";
         let outfile = "output.txt";
         let response = "response.txt";
@@ -802,7 +2329,10 @@ pub mod tests {
         let commands = db.get_commands(None).unwrap();
         let command = commands[0].clone();
         let curl: Curl = serde_json::from_str(command.get_curl_json()).unwrap();
-        assert!(res.is_ok());
+        assert_eq!(
+            res.unwrap().to_string(),
+            "Imported 1 request into 1 collection"
+        );
         assert_eq!(collections.len(), 1);
         assert_eq!(commands.len(), cmds.len());
         assert_eq!(curl.get_method().to_string(), "POST");
@@ -0,0 +1,566 @@
+use super::db::{ImportedCollection, SavedCommand};
+use super::import::{
+    add_content_type, append_query, form_urlencoded, replace_path_variable, saved_command, Folder,
+    Import,
+};
+use crate::request::curl::{AuthKind, Curl, Method};
+use crate::request::variables::substitute;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+// The blocks a request's method and URL can be in
+const METHOD_BLOCKS: [&str; 9] = [
+    "get", "post", "put", "patch", "delete", "head", "options", "trace", "connect",
+];
+
+// Directories of a collection that don't hold requests
+const SKIPPED_DIRS: [&str; 2] = ["environments", "node_modules"];
+
+// A `name { .. }` or `name [ .. ]` block of a .bru file, without its indentation
+#[derive(Debug, Default, Clone, PartialEq)]
+struct Block {
+    name: String,
+    lines: Vec<String>,
+}
+
+/// A Bruno `.bru` file, a request or the settings of a folder or collection
+/// https://docs.usebruno.com/bru-lang/overview
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BruFile {
+    blocks: Vec<Block>,
+}
+
+impl BruFile {
+    pub fn parse(text: &str) -> Self {
+        let mut blocks = Vec::new();
+        let mut current: Option<(Block, &str)> = None;
+        for line in text.lines() {
+            let trimmed = line.trim_end();
+            if let Some((mut block, close)) = current.take() {
+                // blocks end with a closing brace that isn't indented
+                if trimmed == close {
+                    blocks.push(block);
+                } else {
+                    block
+                        .lines
+                        .push(trimmed.strip_prefix("  ").unwrap_or(trimmed).to_string());
+                    current = Some((block, close));
+                }
+                continue;
+            }
+            if let Some(name) = trimmed.strip_suffix('{') {
+                current = Some((block(name), "}"));
+            } else if let Some(name) = trimmed.strip_suffix('[') {
+                current = Some((block(name), "]"));
+            } else if let Some((name, items)) = trimmed.split_once('[') {
+                // a list on one line, `vars:secret [ token, password ]`
+                let mut list = block(name);
+                list.lines = items
+                    .trim_end_matches(']')
+                    .split(',')
+                    .map(String::from)
+                    .collect();
+                blocks.push(list);
+            }
+        }
+        BruFile { blocks }
+    }
+
+    fn block(&self, name: &str) -> Option<&Block> {
+        self.blocks.iter().find(|block| block.name == name)
+    }
+
+    // The enabled `key: value` lines of a block, disabled ones start with ~
+    pub fn pairs(&self, name: &str) -> Vec<(String, String)> {
+        self.block(name)
+            .map(|block| {
+                block
+                    .lines
+                    .iter()
+                    .map(|line| line.trim())
+                    .filter(|line| !line.is_empty() && !line.starts_with('~'))
+                    .filter_map(|line| line.split_once(':'))
+                    .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn field(&self, name: &str, key: &str) -> Option<String> {
+        self.pairs(name)
+            .into_iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value)
+            .filter(|value| !value.is_empty())
+    }
+
+    // The text of a body, script or docs block
+    pub fn text(&self, name: &str) -> Option<String> {
+        self.block(name)
+            .map(|block| block.lines.join("\n").trim_matches('\n').to_string())
+            .filter(|text| !text.trim().is_empty())
+    }
+
+    // The request's method, with the block holding its URL
+    fn method(&self) -> Option<&str> {
+        self.blocks
+            .iter()
+            .map(|block| block.name.as_str())
+            .find(|name| METHOD_BLOCKS.contains(name))
+    }
+
+    fn seq(&self) -> i64 {
+        self.field("meta", "seq")
+            .and_then(|seq| seq.parse().ok())
+            .unwrap_or(i64::MAX)
+    }
+}
+
+fn block(name: &str) -> Block {
+    Block {
+        name: name.trim().to_string(),
+        lines: Vec::new(),
+    }
+}
+
+/// A Bruno collection, the directory with its `bruno.json`
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrunoCollection {
+    root: PathBuf,
+    pub name: String,
+}
+
+// Auth of a request, folder or collection
+#[derive(Debug, Clone, PartialEq)]
+enum Auth {
+    None,
+    Kind(AuthKind),
+    Header(String, String),
+    Query(String, String),
+    Unsupported(String),
+}
+
+// What is passed down from the collection or a folder to the requests in it
+struct Inherited {
+    headers: Vec<(String, String)>,
+    auth: Auth,
+    variables: Vec<(String, String)>,
+}
+
+impl BrunoCollection {
+    // Takes the collection directory, or its bruno.json
+    pub fn open(path: &str) -> Result<Self, String> {
+        let path = Path::new(path);
+        let root = match path.is_dir() {
+            true => path.to_path_buf(),
+            false => path.parent().unwrap_or(Path::new(".")).to_path_buf(),
+        };
+        let config = std::fs::read_to_string(root.join("bruno.json"))
+            .map_err(|_| format!("{} is not a Bruno collection", root.display()))?;
+        let config: Value = serde_json::from_str(&config).map_err(|e| e.to_string())?;
+        let name = config["name"]
+            .as_str()
+            .map(String::from)
+            .unwrap_or_else(|| dir_name(&root));
+        Ok(BrunoCollection { root, name })
+    }
+
+    pub fn is_bruno_path(path: &str) -> bool {
+        let path = Path::new(path);
+        match path.is_dir() {
+            true => path.join("bruno.json").is_file(),
+            false => path.file_name().is_some_and(|name| name == "bruno.json"),
+        }
+    }
+
+    // Every folder with requests becomes a collection named `Collection / Folder`,
+    // with the requests in their sequence order
+    pub fn import(&self) -> (Vec<ImportedCollection>, Vec<String>) {
+        let settings = read_bru(&self.root.join("collection.bru")).unwrap_or_default();
+        let mut import = Import::default();
+        let folder = Folder {
+            name: self.name.clone(),
+            description: settings.text("docs").unwrap_or_default(),
+            inherited: Inherited {
+                headers: settings.pairs("headers"),
+                auth: read_auth(&settings, &Auth::None),
+                variables: settings.pairs("vars:pre-request"),
+            },
+        };
+        add_folder(&mut import, &self.root, &folder);
+        import.finish()
+    }
+}
+
+fn add_folder(import: &mut Import, dir: &Path, folder: &Folder<Inherited>) {
+    let mut requests = Vec::new();
+    let mut folders = Vec::new();
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+        .unwrap_or_default();
+    entries.sort();
+    for path in entries {
+        let name = dir_name(&path);
+        if path.is_dir() {
+            if !name.starts_with('.') && !SKIPPED_DIRS.contains(&name.as_str()) {
+                let settings = read_bru(&path.join("folder.bru")).unwrap_or_default();
+                folders.push((path, settings));
+            }
+        } else if name.ends_with(".bru") && name != "folder.bru" && name != "collection.bru" {
+            if let Some(file) = read_bru(&path) {
+                requests.push((path, file));
+            }
+        }
+    }
+    requests.sort_by_key(|(_, file)| file.seq());
+    folders.sort_by_key(|(_, settings)| settings.seq());
+    let commands: Vec<SavedCommand> = requests
+        .iter()
+        .filter_map(|(path, file)| read_request(import, path, file, folder))
+        .collect();
+    import.add_collection(folder, folder.inherited.variables.clone(), commands);
+    for (path, settings) in folders {
+        let name = settings
+            .field("meta", "name")
+            .unwrap_or_else(|| dir_name(&path));
+        let mut headers = folder.inherited.headers.clone();
+        headers.extend(settings.pairs("headers"));
+        let mut variables = folder.inherited.variables.clone();
+        variables.extend(settings.pairs("vars:pre-request"));
+        let sub_folder = folder.sub_folder(
+            &name,
+            settings.text("docs").unwrap_or_default(),
+            Inherited {
+                headers,
+                // folders without auth of their own inherit it
+                auth: read_auth(&settings, &folder.inherited.auth),
+                variables,
+            },
+        );
+        add_folder(import, &path, &sub_folder);
+    }
+}
+
+fn read_request(
+    import: &mut Import,
+    path: &Path,
+    file: &BruFile,
+    folder: &Folder<Inherited>,
+) -> Option<SavedCommand> {
+    let name = file
+        .field("meta", "name")
+        .unwrap_or_else(|| dir_name(path).trim_end_matches(".bru").to_string());
+    let Some(method_block) = file.method() else {
+        let kind = file.field("meta", "type").unwrap_or_default();
+        import.skip(&name, &format!("{} requests are not supported", kind));
+        return None;
+    };
+    let Ok(method) = Method::from_str(&method_block.to_uppercase()) else {
+        let kind = method_block.to_uppercase();
+        import.skip(&name, &format!("{} requests are not supported", kind));
+        return None;
+    };
+    if ["script:pre-request", "script:post-response", "tests"]
+        .iter()
+        .any(|script| file.text(script).is_some())
+    {
+        import.skip(&name, "pre-request and test scripts are not run");
+    }
+    if !file.pairs("vars:post-response").is_empty() {
+        import.skip(&name, "post-response vars are not set");
+    }
+    // request vars only apply to this request, they are resolved right away
+    let variables = file.pairs("vars:pre-request");
+    let resolve = |text: &str| substitute(text, &variables);
+    let mut curl = Curl::new_serializing();
+    let mut headers = folder.inherited.headers.clone();
+    headers.extend(file.pairs("headers"));
+    let mut url = file.field(method_block, "url").unwrap_or_default();
+    // path parameters, /users/:id
+    for (key, value) in file.pairs("params:path") {
+        url = replace_path_variable(&url, &key, &value);
+    }
+    let auth = match file.field(method_block, "auth").as_deref() {
+        Some("inherit") => folder.inherited.auth.clone(),
+        Some(mode) => auth(file, mode),
+        None => Auth::None,
+    };
+    match auth {
+        Auth::None => {}
+        Auth::Kind(kind) => curl.set_auth(kind),
+        Auth::Header(key, value) => headers.push((key, value)),
+        Auth::Query(key, value) => url = append_query(&url, &key, &value),
+        Auth::Unsupported(mode) => import.skip(&name, &format!("{} auth is not supported", mode)),
+    }
+    curl.set_url(&resolve(&url));
+    curl.set_method(method);
+    let content_type = match file.field(method_block, "body").as_deref() {
+        Some("json") => body_text(&mut curl, file, "body:json", "application/json"),
+        Some("text") => body_text(&mut curl, file, "body:text", "text/plain"),
+        Some("xml") => body_text(&mut curl, file, "body:xml", "application/xml"),
+        Some("sparql") => body_text(&mut curl, file, "body:sparql", "application/sparql-query"),
+        Some("formUrlEncoded") => {
+            let form = form_urlencoded(&file.pairs("body:form-urlencoded"));
+            curl.set_request_body(&resolve(&form));
+            Some("application/x-www-form-urlencoded")
+        }
+        Some("multipartForm") => {
+            let fields = file.pairs("body:multipart-form");
+            if fields.iter().any(|(_, value)| value.starts_with("@file(")) {
+                import.skip(&name, "file fields of the form are not imported");
+            }
+            let form_data: Vec<(String, String)> = fields
+                .into_iter()
+                .filter(|(_, value)| !value.starts_with("@file("))
+                .map(|(key, value)| (key, resolve(&value)))
+                .collect();
+            curl.set_request_body(&serde_json::to_string(&form_data).unwrap_or_default());
+            None
+        }
+        Some("graphql") => {
+            let graphql = json!({
+                "query": file.text("body:graphql").unwrap_or_default(),
+                "variables": serde_json::from_str::<Value>(
+                    &file.text("body:graphql:vars").unwrap_or_default()
+                ).unwrap_or_else(|_| json!({})),
+            });
+            curl.set_request_body(&resolve(&graphql.to_string()));
+            Some("application/json")
+        }
+        _ => None,
+    };
+    add_content_type(&mut headers, content_type);
+    headers
+        .iter()
+        .for_each(|(key, value)| curl.add_headers(&resolve(&format!("{}: {}", key, value))));
+    if let Some(body) = curl.get_request_body() {
+        curl.set_request_body(&resolve(&body));
+    }
+    Some(saved_command(curl, name, file.text("docs")))
+}
+
+fn body_text(
+    curl: &mut Curl,
+    file: &BruFile,
+    block: &str,
+    content_type: &'static str,
+) -> Option<&'static str> {
+    let text = file.text(block)?;
+    curl.set_request_body(&text);
+    Some(content_type)
+}
+
+// The auth of a request is set by its method block, folders and collections have an auth block
+fn read_auth(settings: &BruFile, inherited: &Auth) -> Auth {
+    match settings.field("auth", "mode").as_deref() {
+        None | Some("inherit") => inherited.clone(),
+        Some(mode) => auth(settings, mode),
+    }
+}
+
+fn auth(file: &BruFile, mode: &str) -> Auth {
+    let block = format!("auth:{}", mode);
+    let param = |key: &str| file.field(&block, key).unwrap_or_default();
+    let login = || format!("{}:{}", param("username"), param("password"));
+    match mode {
+        "none" => Auth::None,
+        "basic" => Auth::Kind(AuthKind::Basic(login())),
+        "digest" => Auth::Kind(AuthKind::Digest(login())),
+        "bearer" => Auth::Kind(AuthKind::Bearer(param("token"))),
+        "ntlm" => Auth::Kind(AuthKind::Ntlm),
+        "apikey" if param("placement") == "queryparams" => {
+            Auth::Query(param("key"), param("value"))
+        }
+        "apikey" => Auth::Header(param("key"), param("value")),
+        mode => Auth::Unsupported(mode.to_string()),
+    }
+}
+
+fn read_bru(path: &Path) -> Option<BruFile> {
+    std::fs::read_to_string(path)
+        .ok()
+        .map(|text| BruFile::parse(&text))
+}
+
+fn dir_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REQUEST: &str = "meta {
+  name: Create user
+  type: http
+  seq: 2
+}
+
+post {
+  url: {{baseUrl}}/users/:team/:teamId
+  body: json
+  auth: bearer
+}
+
+params:path {
+  team: core
+  teamId: 9
+}
+
+headers {
+  X-Request-Id: {{requestId}}
+  ~X-Debug: 1
+}
+
+auth:bearer {
+  token: {{token}}
+}
+
+body:json {
+  {
+    \"name\": \"CuTE\"
+  }
+}
+
+vars:pre-request {
+  requestId: 42
+}
+
+vars:secret [
+  token
+]
+
+docs {
+  Creates a user
+}
+";
+
+    #[test]
+    fn test_parse_bru_file() {
+        let file = BruFile::parse(REQUEST);
+        assert_eq!(file.method(), Some("post"));
+        assert_eq!(file.seq(), 2);
+        assert_eq!(file.field("meta", "name").as_deref(), Some("Create user"));
+        assert_eq!(
+            file.field("post", "url").as_deref(),
+            Some("{{baseUrl}}/users/:team/:teamId")
+        );
+        assert_eq!(
+            file.pairs("headers"),
+            vec![(String::from("X-Request-Id"), String::from("{{requestId}}"))]
+        );
+        assert_eq!(
+            file.text("body:json").as_deref(),
+            Some("{\n  \"name\": \"CuTE\"\n}")
+        );
+        assert_eq!(file.text("docs").as_deref(), Some("Creates a user"));
+        assert_eq!(file.block("vars:secret").unwrap().lines, vec!["token"]);
+    }
+
+    #[test]
+    fn test_import_bruno_collection() {
+        let root = std::env::temp_dir().join("cute_bruno_import_test");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("users")).unwrap();
+        std::fs::create_dir_all(root.join("environments")).unwrap();
+        let write = |path: &str, text: &str| std::fs::write(root.join(path), text).unwrap();
+        write(
+            "bruno.json",
+            r#"{"version": "1", "name": "Shop API", "type": "collection"}"#,
+        );
+        write(
+            "collection.bru",
+            "headers {\n  Accept: application/json\n}\n\nauth {\n  mode: basic\n}\n\nauth:basic {\n  username: admin\n  password: s3cret\n}\n\nvars:pre-request {\n  baseUrl: https://shop.example.com\n}\n",
+        );
+        write(
+            "health.bru",
+            "meta {\n  name: Health\n  seq: 1\n}\n\nget {\n  url: {{baseUrl}}/health\n  auth: inherit\n}\n\ntests {\n  test(\"ok\", () => {});\n}\n",
+        );
+        write(
+            "environments/Local.bru",
+            "vars {\n  baseUrl: http://localhost\n}\n",
+        );
+        write(
+            "users/folder.bru",
+            "meta {\n  name: Users\n}\n\nheaders {\n  X-Team: core\n}\n",
+        );
+        write("users/create.bru", REQUEST);
+        write(
+            "users/list.bru",
+            "meta {\n  name: List users\n  seq: 1\n}\n\nget {\n  url: {{baseUrl}}/users?page=1\n  auth: apikey\n}\n\nauth:apikey {\n  key: api_key\n  value: abc\n  placement: queryparams\n}\n",
+        );
+        write(
+            "users/subscribe.bru",
+            "meta {\n  name: Subscribe\n  type: ws\n}\n",
+        );
+        assert!(BrunoCollection::is_bruno_path(root.to_str().unwrap()));
+        let collection = BrunoCollection::open(root.to_str().unwrap()).unwrap();
+        let (collections, skipped) = collection.import();
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(collections.len(), 2);
+        assert_eq!(collections[0].name, "Shop API");
+        assert_eq!(
+            collections[0].variables,
+            vec![(
+                String::from("baseUrl"),
+                String::from("https://shop.example.com")
+            )]
+        );
+        assert_eq!(collections[1].name, "Shop API / Users");
+        let labels: Vec<_> = collections[1]
+            .commands
+            .iter()
+            .map(|cmd| cmd.label.clone().unwrap())
+            .collect();
+        assert_eq!(labels, vec!["List users", "Create user"]);
+        assert_eq!(
+            skipped,
+            vec![
+                String::from("Health: pre-request and test scripts are not run"),
+                String::from("Subscribe: ws requests are not supported"),
+            ]
+        );
+
+        let health: Curl =
+            serde_json::from_str(collections[0].commands[0].get_curl_json()).unwrap();
+        assert_eq!(
+            health.get_auth(),
+            &AuthKind::Basic(String::from("admin:s3cret"))
+        );
+        let list: Curl = serde_json::from_str(collections[1].commands[0].get_curl_json()).unwrap();
+        assert_eq!(list.get_url(), "{{baseUrl}}/users?page=1&api_key=abc");
+        assert_eq!(
+            list.get_request_headers(),
+            vec!["Accept: application/json", "X-Team: core"]
+        );
+        let create: Curl =
+            serde_json::from_str(collections[1].commands[1].get_curl_json()).unwrap();
+        assert_eq!(create.get_url(), "{{baseUrl}}/users/core/9");
+        assert_eq!(
+            create.get_auth(),
+            &AuthKind::Bearer(String::from("{{token}}"))
+        );
+        assert_eq!(
+            create.get_request_headers(),
+            vec![
+                "Authorization: Bearer {{token}}",
+                "Accept: application/json",
+                "X-Team: core",
+                "X-Request-Id: 42",
+                "Content-Type: application/json",
+            ]
+        );
+        assert_eq!(
+            create.get_request_body().as_deref(),
+            Some("{\n  \"name\": \"CuTE\"\n}")
+        );
+        assert_eq!(
+            collections[1].commands[1].description.as_deref(),
+            Some("Creates a user")
+        );
+    }
+}
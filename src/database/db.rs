@@ -4,11 +4,17 @@ use serde::{Deserialize, Serialize};
 use serde_json;
 use std::env;
 use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use std::{
     fmt::{Display, Formatter},
     path::PathBuf,
 };
 
+// Number of requests kept in the history when not set in the config
+pub const DEFAULT_HISTORY_LIMIT: usize = 500;
+// Response bodies are truncated to this many bytes in the history
+pub const HISTORY_BODY_LIMIT: usize = 16 * 1024;
+
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SavedCommand {
     pub id: i32,
@@ -33,6 +39,100 @@ impl Display for SavedCollection {
     }
 }
 
+// A named set of variables, `{{name}}` in saved commands resolves
+// against the selected environment when they are executed
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Environment {
+    id: i32,
+    pub name: String,
+    pub variables: Vec<EnvVariable>,
+    pub selected: bool,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnvVariable {
+    pub name: String,
+    pub value: String,
+    // secret values are kept with the saved keys, the value is left empty
+    pub key_id: Option<i32>,
+}
+
+// A collection read from another tool's export, before it is saved
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ImportedCollection {
+    pub name: String,
+    pub description: String,
+    pub variables: Vec<(String, String)>,
+    pub commands: Vec<SavedCommand>,
+}
+
+// What an import saved, and what it had to leave out
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ImportSummary {
+    pub collections: usize,
+    pub commands: usize,
+    pub skipped: Vec<String>,
+    // name and number of variables of an imported environment
+    pub environment: Option<(String, usize)>,
+}
+
+impl ImportSummary {
+    pub fn new(collections: &[ImportedCollection], skipped: Vec<String>) -> Self {
+        ImportSummary {
+            collections: collections.len(),
+            commands: collections.iter().map(|c| c.commands.len()).sum(),
+            skipped,
+            environment: None,
+        }
+    }
+}
+
+impl Display for ImportSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some((name, count)) = &self.environment {
+            write!(
+                f,
+                "Imported environment {} with {} variable{}",
+                name,
+                count,
+                if *count == 1 { "" } else { "s" },
+            )?;
+        } else {
+            write!(
+                f,
+                "Imported {} request{} into {} collection{}",
+                self.commands,
+                if self.commands == 1 { "" } else { "s" },
+                self.collections,
+                if self.collections == 1 { "" } else { "s" },
+            )?;
+        }
+        if !self.skipped.is_empty() {
+            write!(f, "\n\nNot imported:\n- {}", self.skipped.join("\n- "))?;
+        }
+        Ok(())
+    }
+}
+
+// Every executed request is logged to the history table
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    id: i32,
+    // unix timestamp (seconds) of when the request was sent
+    pub timestamp: i64,
+    pub method: String,
+    pub url: String,
+    curl_json: String,
+    pub status: Option<u32>,
+    pub duration_ms: i64,
+    // size of the full response body in bytes
+    pub size: i64,
+    // raw status line + response headers
+    pub headers: String,
+    // response body (or error), truncated to HISTORY_BODY_LIMIT
+    pub body: String,
+}
+
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SavedKey {
     id: i32,
@@ -57,7 +157,15 @@ impl DB {
             params![],
         )?;
         conn.execute(
-            "CREATE TABLE collections (id INTEGER PRIMARY KEY, name TEXT, description TEXT);",
+            "CREATE TABLE collections (id INTEGER PRIMARY KEY, name TEXT, description TEXT, variables TEXT);",
+            params![],
+        )?;
+        conn.execute(
+            "CREATE TABLE history (id INTEGER PRIMARY KEY, timestamp INTEGER, method TEXT, url TEXT, curl_json TEXT, status INTEGER, duration_ms INTEGER, size INTEGER, body TEXT, headers TEXT);",
+            params![],
+        )?;
+        conn.execute(
+            "CREATE TABLE environments (id INTEGER PRIMARY KEY, name TEXT, variables TEXT, selected INTEGER DEFAULT 0);",
             params![],
         )?;
         Ok(DB { conn })
@@ -110,9 +218,28 @@ impl DB {
         )?;
 
         conn.execute(
-            "CREATE TABLE IF NOT EXISTS collections (id INTEGER PRIMARY KEY, name TEXT, description TEXT);",
+            "CREATE TABLE IF NOT EXISTS collections (id INTEGER PRIMARY KEY, name TEXT, description TEXT, variables TEXT);",
+            params![],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS history (id INTEGER PRIMARY KEY, timestamp INTEGER, method TEXT, url TEXT, curl_json TEXT, status INTEGER, duration_ms INTEGER, size INTEGER, body TEXT, headers TEXT);",
+            params![],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS environments (id INTEGER PRIMARY KEY, name TEXT, variables TEXT, selected INTEGER DEFAULT 0);",
             params![],
         )?;
+        // collections created before imported collections kept their variables
+        if conn
+            .prepare("SELECT variables FROM collections LIMIT 1")
+            .is_err()
+        {
+            conn.execute(
+                "ALTER TABLE collections ADD COLUMN variables TEXT;",
+                params![],
+            )?;
+        }
 
         conn.execute("COMMIT;", params![])?;
 
@@ -148,7 +275,8 @@ impl DB {
     }
 
 #[rustfmt::skip]
-    pub fn add_collection(&self, name: &str, desc: &str, commands: &[SavedCommand]) -> Result<(), Box<dyn std::error::Error>> {
+    // Returns the id of the new collection
+    pub fn add_collection(&self, name: &str, desc: &str, commands: &[SavedCommand]) -> Result<i32, Box<dyn std::error::Error>> {
         let mut stmt = self
             .conn
             .prepare("INSERT INTO collections (name, description) VALUES (?1, ?2)")?;
@@ -156,9 +284,48 @@ impl DB {
         for command in commands {
             self.add_command_from_collection(&command.command, command.label.as_deref(), command.description.as_deref(), &command.curl_json, id as i32)?;
         }
+        Ok(id as i32)
+    }
+
+    pub fn add_imported_collection(
+        &self,
+        collection: &ImportedCollection,
+    ) -> Result<i32, Box<dyn std::error::Error>> {
+        let id = self.add_collection(
+            &collection.name,
+            &collection.description,
+            &collection.commands,
+        )?;
+        if !collection.variables.is_empty() {
+            self.set_collection_variables(id, &collection.variables)?;
+        }
+        Ok(id)
+    }
+
+    // Variables of a collection, `{{name}}` in its commands resolves to these when executed
+    pub fn set_collection_variables(
+        &self,
+        id: i32,
+        variables: &[(String, String)],
+    ) -> Result<(), rusqlite::Error> {
+        let json = serde_json::to_string(variables).unwrap_or_default();
+        let mut stmt = self
+            .conn
+            .prepare("UPDATE collections SET variables = ? WHERE id = ?")?;
+        stmt.execute(params![json, id])?;
         Ok(())
     }
 
+    pub fn get_collection_variables(&self, id: i32) -> Result<Vec<(String, String)>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT variables FROM collections WHERE id = ?")?;
+        let json: Option<String> = stmt.query_row(params![id], |row| row.get(0))?;
+        Ok(json
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default())
+    }
+
     pub fn get_command_by_id(&self, id: i32) -> Result<SavedCommand> {
         let mut stmt = self.conn.prepare(
                 "SELECT cmd.id, cmd.command, cmd.label, cmd.description, cmd.curl_json, cmd.collection_id, col.name as collection_name FROM commands cmd LEFT JOIN collections col ON cmd.collection_id = col.id WHERE cmd.id = ?"
@@ -189,7 +356,7 @@ impl DB {
                     description: row.get(2)?,
                 })
             })
-            .map_err(|_| ("No Collection".to_string()))?;
+            .map_err(|_| "No Collection".to_string())?;
         Ok(collection)
     }
 
@@ -279,6 +446,32 @@ impl DB {
         Ok(())
     }
 
+    // Moves a command into a collection, None takes it out of its collection
+    pub fn set_command_collection(
+        &self,
+        id: i32,
+        collection_id: Option<i32>,
+    ) -> Result<(), rusqlite::Error> {
+        let mut stmt = self
+            .conn
+            .prepare("UPDATE commands SET collection_id = ?1 WHERE id = ?2")?;
+        stmt.execute(params![collection_id, id])?;
+        Ok(())
+    }
+
+    // Adds a copy of a command, with its label and description, to a collection
+    pub fn copy_command_to_collection(
+        &self,
+        id: i32,
+        collection_id: i32,
+    ) -> Result<(), rusqlite::Error> {
+        let mut stmt = self.conn.prepare(
+            "INSERT INTO commands (command, label, description, curl_json, collection_id) SELECT command, label, description, curl_json, ?1 FROM commands WHERE id = ?2",
+        )?;
+        stmt.execute(params![collection_id, id])?;
+        Ok(())
+    }
+
     pub fn delete_command(&self, id: i32) -> Result<(), rusqlite::Error> {
         let mut stmt = self.conn.prepare("DELETE FROM commands WHERE id = ?")?;
         stmt.execute([id])?;
@@ -365,6 +558,82 @@ impl DB {
         Ok(())
     }
 
+    // Saves a secret as a key, returning the id of the key. It always gets its own
+    // row so deleting the environment never removes a key the user saved
+    pub fn add_secret(&self, key: &str, label: &str) -> Result<i32> {
+        let mut stmt = self
+            .conn
+            .prepare("INSERT INTO keys (key, label) VALUES (?1, ?2)")?;
+        Ok(stmt.insert(params![key, label])? as i32)
+    }
+
+    pub fn get_key_by_id(&self, id: i32) -> Result<SavedKey> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, key, label FROM keys WHERE id = ?")?;
+        stmt.query_row(params![id], |row| {
+            Ok(SavedKey {
+                id: row.get(0)?,
+                key: row.get(1)?,
+                label: row.get(2)?,
+            })
+        })
+    }
+
+    pub fn add_environment(&self, name: &str, variables: &[EnvVariable]) -> Result<i32> {
+        let json = serde_json::to_string(variables).unwrap_or_default();
+        let mut stmt = self
+            .conn
+            .prepare("INSERT INTO environments (name, variables) VALUES (?1, ?2)")?;
+        Ok(stmt.insert(params![name, json])? as i32)
+    }
+
+    pub fn get_environments(&self) -> Result<Vec<Environment>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, name, variables, selected FROM environments")?;
+        let rows = stmt.query_map(params![], |row| {
+            let variables: Option<String> = row.get(2)?;
+            Ok(Environment {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                variables: variables
+                    .and_then(|json| serde_json::from_str(&json).ok())
+                    .unwrap_or_default(),
+                selected: row.get::<_, Option<bool>>(3)?.unwrap_or_default(),
+            })
+        })?;
+        Ok(rows.filter_map(|row| row.ok()).collect())
+    }
+
+    pub fn get_selected_environment(&self) -> Option<Environment> {
+        self.get_environments()
+            .ok()?
+            .into_iter()
+            .find(|env| env.selected)
+    }
+
+    // Only one environment is selected at a time, None unselects it
+    pub fn select_environment(&self, id: Option<i32>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE environments SET selected = (id = ?1)",
+            params![id.unwrap_or(-1)],
+        )?;
+        Ok(())
+    }
+
+    // The secrets of the environment are deleted from the saved keys too
+    pub fn delete_environment(&self, id: i32) -> Result<()> {
+        if let Some(env) = self.get_environments()?.into_iter().find(|e| e.id == id) {
+            for key in env.variables.iter().filter_map(|v| v.key_id) {
+                self.delete_key(key)?;
+            }
+        }
+        self.conn
+            .execute("DELETE FROM environments WHERE id = ?", params![id])?;
+        Ok(())
+    }
+
     pub fn set_key_label(&self, key: i32, label: &str) -> Result<()> {
         let mut stmt = self
             .conn
@@ -388,6 +657,59 @@ impl DB {
         }
         Ok(keys)
     }
+
+    // Logs a request, only the most recent `limit` entries are kept
+    pub fn add_history(&self, entry: &HistoryEntry, limit: usize) -> Result<()> {
+        let mut stmt = self.conn.prepare(
+            "INSERT INTO history (timestamp, method, url, curl_json, status, duration_ms, size, body, headers) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        )?;
+        stmt.execute(params![
+            entry.timestamp,
+            entry.method,
+            entry.url,
+            entry.curl_json,
+            entry.status,
+            entry.duration_ms,
+            entry.size,
+            entry.body,
+            entry.headers
+        ])?;
+        let mut stmt = self.conn.prepare(
+            "DELETE FROM history WHERE id NOT IN (SELECT id FROM history ORDER BY id DESC LIMIT ?1)",
+        )?;
+        stmt.execute(params![limit as i64])?;
+        Ok(())
+    }
+
+    // Newest first, optionally only entries whose method, url or status contain `filter`
+    pub fn get_history(&self, filter: Option<&str>) -> Result<Vec<HistoryEntry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, timestamp, method, url, curl_json, status, duration_ms, size, body, headers FROM history WHERE ?1 IS NULL OR method LIKE ?1 ESCAPE '\\' OR url LIKE ?1 ESCAPE '\\' OR CAST(status AS TEXT) LIKE ?1 ESCAPE '\\' ORDER BY id DESC",
+        )?;
+        // % and _ in the filter are matched literally
+        let pattern = filter.map(|f| {
+            let escaped = f
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_");
+            format!("%{}%", escaped)
+        });
+        let rows = stmt.query_map(params![pattern], HistoryEntry::from_row)?;
+        rows.collect()
+    }
+
+    pub fn get_history_entry(&self, id: i32) -> Result<HistoryEntry> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, timestamp, method, url, curl_json, status, duration_ms, size, body, headers FROM history WHERE id = ?",
+        )?;
+        stmt.query_row(params![id], HistoryEntry::from_row)
+    }
+
+    pub fn delete_history_entry(&self, id: i32) -> Result<()> {
+        let mut stmt = self.conn.prepare("DELETE FROM history WHERE id = ?")?;
+        stmt.execute([id])?;
+        Ok(())
+    }
 }
 
 impl Display for SavedCommand {
@@ -486,6 +808,115 @@ impl SavedKey {
     }
 }
 
+impl HistoryEntry {
+    pub fn new(curl_json: &str, method: &str, url: &str) -> Self {
+        Self {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or_default(),
+            method: method.to_string(),
+            url: url.to_string(),
+            curl_json: curl_json.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn set_response(
+        &mut self,
+        status: Option<u32>,
+        duration: Duration,
+        size: usize,
+        headers: &str,
+        body: &str,
+    ) {
+        self.status = status;
+        self.headers = headers.to_string();
+        self.duration_ms = duration.as_millis() as i64;
+        self.size = size as i64;
+        let mut end = body.len().min(HISTORY_BODY_LIMIT);
+        while !body.is_char_boundary(end) {
+            end -= 1;
+        }
+        self.body = body[..end].to_string();
+    }
+
+    fn from_row(row: &rusqlite::Row) -> Result<Self> {
+        Ok(HistoryEntry {
+            id: row.get(0)?,
+            timestamp: row.get(1)?,
+            method: row.get(2)?,
+            url: row.get(3)?,
+            curl_json: row.get(4)?,
+            status: row.get(5)?,
+            duration_ms: row.get(6)?,
+            size: row.get(7)?,
+            body: row.get(8)?,
+            headers: row.get::<_, Option<String>>(9)?.unwrap_or_default(),
+        })
+    }
+
+    pub fn get_id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn get_curl_json(&self) -> &str {
+        &self.curl_json
+    }
+
+    // How long ago the request was sent, e.g. "5m ago"
+    pub fn get_age(&self) -> String {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or_default();
+        match (now - self.timestamp).max(0) {
+            secs if secs < 60 => format!("{}s ago", secs),
+            secs if secs < 60 * 60 => format!("{}m ago", secs / 60),
+            secs if secs < 60 * 60 * 24 => format!("{}h ago", secs / (60 * 60)),
+            secs => format!("{}d ago", secs / (60 * 60 * 24)),
+        }
+    }
+}
+
+impl Display for HistoryEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let status = match self.status {
+            Some(status) => status.to_string(),
+            None => String::from("ERR"),
+        };
+        write!(
+            f,
+            "{} {} {} | {}ms | {}B | {}",
+            status,
+            self.method,
+            self.url,
+            self.duration_ms,
+            self.size,
+            self.get_age()
+        )
+    }
+}
+
+impl Environment {
+    pub fn get_id(&self) -> i32 {
+        self.id
+    }
+}
+
+impl Display for Environment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} ({} variable{})",
+            if self.selected { "[x]" } else { "[ ]" },
+            self.name,
+            self.variables.len(),
+            if self.variables.len() == 1 { "" } else { "s" },
+        )
+    }
+}
+
 impl SavedCollection {
     pub fn get_name(&self) -> &str {
         &self.name
@@ -0,0 +1,460 @@
+use super::db::{HistoryEntry, SavedCommand};
+use super::import::{form_urlencoded, saved_command};
+use crate::request::curl::{AuthKind, Curl, Method};
+use crate::request::summary::ResponseSummary;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const HAR_VERSION: &str = "1.2";
+
+// Request headers left out on import: libcurl sets these itself, the cookies are
+// imported from the cookie list and pseudo headers (':authority') are HTTP/2 framing
+const SKIPPED_HEADERS: [&str; 4] = ["host", "content-length", "cookie", "accept-encoding"];
+
+/// An HTTP Archive, as exported by browser devtools
+/// http://www.softwareishard.com/blog/har-12-spec/
+#[derive(Serialize, Debug, Default, Deserialize)]
+pub struct Har {
+    pub log: Log,
+}
+
+#[derive(Serialize, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Log {
+    pub version: String,
+    pub creator: Creator,
+    pub entries: Vec<Entry>,
+}
+
+#[derive(Serialize, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Creator {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Serialize, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Entry {
+    pub started_date_time: String,
+    // total time of the request in milliseconds
+    pub time: f64,
+    pub request: Request,
+    pub response: Response,
+    pub cache: Map<String, Value>,
+    pub timings: Timings,
+}
+
+#[derive(Serialize, Debug, Default, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct NameValue {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Serialize, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Request {
+    pub method: String,
+    pub url: String,
+    pub http_version: String,
+    pub cookies: Vec<NameValue>,
+    pub headers: Vec<NameValue>,
+    pub query_string: Vec<NameValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_data: Option<PostData>,
+    pub headers_size: i64,
+    pub body_size: i64,
+}
+
+#[derive(Serialize, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PostData {
+    pub mime_type: String,
+    pub text: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub params: Vec<NameValue>,
+}
+
+#[derive(Serialize, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Response {
+    pub status: u32,
+    pub status_text: String,
+    pub http_version: String,
+    pub cookies: Vec<NameValue>,
+    pub headers: Vec<NameValue>,
+    pub content: Content,
+    #[serde(rename = "redirectURL")]
+    pub redirect_url: String,
+    pub headers_size: i64,
+    pub body_size: i64,
+}
+
+#[derive(Serialize, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Content {
+    pub size: i64,
+    pub mime_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+}
+
+#[derive(Serialize, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Timings {
+    pub send: f64,
+    pub wait: f64,
+    pub receive: f64,
+}
+
+impl From<Har> for Vec<SavedCommand> {
+    fn from(har: Har) -> Vec<SavedCommand> {
+        har.log
+            .entries
+            .iter()
+            .map(|e| e.request.to_command())
+            .collect()
+    }
+}
+
+impl Har {
+    pub fn new(entries: Vec<Entry>) -> Self {
+        Self {
+            log: Log {
+                version: String::from(HAR_VERSION),
+                creator: Creator {
+                    name: String::from("CuTE"),
+                    version: String::from(env!("CARGO_PKG_VERSION")),
+                },
+                entries,
+            },
+        }
+    }
+
+    // Saved commands have no response, only their requests are exported
+    pub fn from_commands(commands: &[SavedCommand]) -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        Self::new(
+            commands
+                .iter()
+                .map(|cmd| Entry {
+                    started_date_time: iso_8601(now.as_secs() as i64),
+                    request: Request::from_json(cmd.get_curl_json()),
+                    response: Response::default(),
+                    ..Default::default()
+                })
+                .collect(),
+        )
+    }
+
+    pub fn from_history(history: &[HistoryEntry]) -> Self {
+        Self::new(history.iter().map(Entry::from_history).collect())
+    }
+
+    pub fn write(&self, path: &str) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, json).map_err(|e| e.to_string())
+    }
+}
+
+impl Entry {
+    fn from_history(entry: &HistoryEntry) -> Self {
+        let summary = ResponseSummary::new(
+            entry.status,
+            &entry.headers,
+            Duration::from_millis(entry.duration_ms as u64),
+            entry.size as usize,
+            None,
+        );
+        let headers = parse_headers(entry.headers.lines().skip(1));
+        let redirect_url = headers
+            .iter()
+            .find(|h| h.name.eq_ignore_ascii_case("location"))
+            .map(|h| h.value.clone())
+            .unwrap_or_default();
+        Entry {
+            started_date_time: iso_8601(entry.timestamp),
+            time: entry.duration_ms as f64,
+            request: Request::from_json(entry.get_curl_json()),
+            response: Response {
+                status: entry.status.unwrap_or_default(),
+                status_text: summary.reason.clone().unwrap_or_default(),
+                http_version: summary.version.clone().unwrap_or_default(),
+                cookies: Vec::new(),
+                headers,
+                content: Content {
+                    size: entry.size,
+                    mime_type: summary.content_type.clone().unwrap_or_default(),
+                    text: Some(entry.body.clone()),
+                    encoding: None,
+                },
+                redirect_url,
+                headers_size: -1,
+                body_size: entry.size,
+            },
+            cache: Map::new(),
+            timings: Timings {
+                send: 0.0,
+                wait: entry.duration_ms as f64,
+                receive: 0.0,
+            },
+        }
+    }
+}
+
+impl Request {
+    // Takes the serialized curl command of a saved command or history entry
+    fn from_json(json: &str) -> Self {
+        let curl: Curl = serde_json::from_str(json).unwrap_or_default();
+        let mut headers = parse_headers(curl.get_request_headers().iter().map(String::as_str));
+        // the bearer token is part of the request headers already. Digest auth
+        // answers the server's challenge, there is no header to write down for it
+        if let AuthKind::Basic(login) = curl.get_auth() {
+            headers.push(NameValue {
+                name: String::from("Authorization"),
+                value: format!("Basic {}", base64(login.as_bytes())),
+            });
+        }
+        let query_string = curl
+            .get_url()
+            .split_once('?')
+            .map(|(_, query)| {
+                query
+                    .split('&')
+                    .filter(|pair| !pair.is_empty())
+                    .map(|pair| name_value(pair, '='))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let post_data = curl.get_request_body().map(|text| PostData {
+            mime_type: headers
+                .iter()
+                .find(|h| h.name.eq_ignore_ascii_case("content-type"))
+                .map(|h| h.value.clone())
+                .unwrap_or_default(),
+            text,
+            params: Vec::new(),
+        });
+        Request {
+            method: curl.get_method().to_string(),
+            url: curl.get_url().to_string(),
+            http_version: String::from("HTTP/1.1"),
+            cookies: curl
+                .get_cookies()
+                .iter()
+                .map(|cookie| name_value(cookie, '='))
+                .collect(),
+            headers,
+            query_string,
+            body_size: post_data.as_ref().map(|p| p.text.len() as i64).unwrap_or(0),
+            post_data,
+            headers_size: -1,
+        }
+    }
+
+    fn to_command(&self) -> SavedCommand {
+        let mut curl = Curl::new_serializing();
+        curl.set_url(&self.url);
+        curl.set_method(Method::from_str(&self.method.to_uppercase()).unwrap_or_default());
+        self.headers
+            .iter()
+            .filter(|h| !h.name.starts_with(':'))
+            .filter(|h| !SKIPPED_HEADERS.contains(&h.name.to_lowercase().as_str()))
+            .for_each(|h| curl.add_headers(&format!("{}: {}", h.name, h.value)));
+        self.cookies
+            .iter()
+            .for_each(|c| curl.add_cookie(&format!("{}={}", c.name, c.value)));
+        if let Some(ref post) = self.post_data {
+            let body = match post.text.is_empty() {
+                true => form_urlencoded(
+                    &post
+                        .params
+                        .iter()
+                        .map(|p| (p.name.clone(), p.value.clone()))
+                        .collect::<Vec<(String, String)>>(),
+                ),
+                false => post.text.clone(),
+            };
+            if !body.is_empty() {
+                curl.set_request_body(&body);
+            }
+        }
+        let label = format!("{} {}", self.method.to_uppercase(), self.url);
+        saved_command(curl, label, None)
+    }
+}
+
+fn name_value(pair: &str, separator: char) -> NameValue {
+    let (name, value) = pair.split_once(separator).unwrap_or((pair, ""));
+    NameValue {
+        name: name.trim().to_string(),
+        value: value.trim().to_string(),
+    }
+}
+
+// Standard base64 with padding, as basic auth sends the user and password
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut text = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let bits = chunk.iter().enumerate().fold(0u32, |bits, (i, byte)| {
+            bits | (*byte as u32) << (16 - 8 * i)
+        });
+        for i in 0..4 {
+            match i <= chunk.len() {
+                true => text.push(ALPHABET[(bits >> (18 - 6 * i)) as usize & 63] as char),
+                false => text.push('='),
+            }
+        }
+    }
+    text
+}
+
+fn parse_headers<'a>(lines: impl Iterator<Item = &'a str>) -> Vec<NameValue> {
+    lines
+        .filter(|line| line.contains(':'))
+        .map(|line| name_value(line, ':'))
+        .collect()
+}
+
+// Formats a unix timestamp as an ISO 8601 date in UTC, e.g. 2024-03-01T12:00:00.000Z
+fn iso_8601(timestamp: i64) -> String {
+    let (days, secs) = (timestamp.div_euclid(86400), timestamp.rem_euclid(86400));
+    // civil_from_days, http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.000Z",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request::curl::AuthKind;
+
+    const HAR: &str = r#"{"log": {"version": "1.2", "creator": {"name": "Firefox", "version": "125"},
+        "entries": [{
+            "startedDateTime": "2024-03-01T12:00:00.000Z", "time": 42,
+            "request": {"method": "POST", "url": "https://example.com/api/users?page=2",
+                "httpVersion": "HTTP/2",
+                "headers": [{"name": ":authority", "value": "example.com"},
+                    {"name": "Content-Type", "value": "application/json"},
+                    {"name": "Cookie", "value": "session=abc"},
+                    {"name": "X-Token", "value": "secret"}],
+                "cookies": [{"name": "session", "value": "abc"}],
+                "queryString": [{"name": "page", "value": "2"}],
+                "postData": {"mimeType": "application/json", "text": "{\"name\": \"Jane\"}"},
+                "headersSize": -1, "bodySize": 16},
+            "response": {"status": 201, "statusText": "Created", "httpVersion": "HTTP/2",
+                "headers": [], "cookies": [], "content": {"size": 2, "mimeType": "application/json", "text": "{}"},
+                "redirectURL": "", "headersSize": -1, "bodySize": 2},
+            "cache": {}, "timings": {"send": 1, "wait": 40, "receive": 1}
+        }, {
+            "request": {"method": "get", "url": "https://example.com/form",
+                "postData": {"mimeType": "application/x-www-form-urlencoded", "text": "",
+                    "params": [{"name": "a", "value": "1"}, {"name": "b", "value": "2"}]}}
+        }]}}"#;
+
+    #[test]
+    fn test_import_har() {
+        let har: Har = serde_json::from_str(HAR).unwrap();
+        let commands: Vec<SavedCommand> = har.into();
+        assert_eq!(commands.len(), 2);
+        assert_eq!(
+            commands[0].label.as_deref(),
+            Some("POST https://example.com/api/users?page=2")
+        );
+        let curl: Curl = serde_json::from_str(commands[0].get_curl_json()).unwrap();
+        assert_eq!(curl.get_method(), &Method::Post);
+        assert_eq!(
+            curl.get_request_headers(),
+            vec!["Content-Type: application/json", "X-Token: secret"]
+        );
+        // bearer auth is exported as the header that is sent
+        let mut bearer: Curl = serde_json::from_str(commands[0].get_curl_json()).unwrap();
+        bearer.set_auth(AuthKind::Bearer(String::from("Bearer t0ken")));
+        assert_eq!(
+            bearer.get_request_headers()[0],
+            "Authorization: Bearer t0ken"
+        );
+        assert_eq!(curl.get_cookies(), vec!["session=abc"]);
+        assert_eq!(
+            curl.get_request_body().as_deref(),
+            Some("{\"name\": \"Jane\"}")
+        );
+        let curl: Curl = serde_json::from_str(commands[1].get_curl_json()).unwrap();
+        assert_eq!(curl.get_method(), &Method::Get);
+        assert_eq!(curl.get_request_body().as_deref(), Some("a=1&b=2"));
+    }
+
+    #[test]
+    fn test_export_har() {
+        let har: Har = serde_json::from_str(HAR).unwrap();
+        let commands: Vec<SavedCommand> = har.into();
+        let exported = Har::from_commands(&commands);
+        let request = &exported.log.entries[0].request;
+        assert_eq!(exported.log.version, "1.2");
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.query_string, vec![name_value("page=2", '=')]);
+        assert_eq!(
+            request.post_data.as_ref().map(|p| p.mime_type.as_str()),
+            Some("application/json")
+        );
+
+        // basic auth is exported as the header curl sends, digest auth has none
+        let mut curl: Curl = serde_json::from_str(commands[0].get_curl_json()).unwrap();
+        curl.set_auth(AuthKind::Basic(String::from("user:pa55")));
+        let request = Request::from_json(&serde_json::to_string(&curl).unwrap());
+        assert!(request
+            .headers
+            .contains(&name_value("Authorization: Basic dXNlcjpwYTU1", ':')));
+        curl.set_auth(AuthKind::Digest(String::from("user:pa55")));
+        let request = Request::from_json(&serde_json::to_string(&curl).unwrap());
+        assert!(!request.headers.iter().any(|h| h.name == "Authorization"));
+        assert_eq!(base64(b"ab"), "YWI=");
+        assert_eq!(base64(b"a"), "YQ==");
+
+        let mut entry =
+            HistoryEntry::new(commands[0].get_curl_json(), "POST", "https://example.com");
+        entry.timestamp = 1709294400;
+        entry.set_response(
+            Some(301),
+            Duration::from_millis(42),
+            2,
+            "HTTP/1.1 301 Moved Permanently\r\nLocation: /new\r\nContent-Type: application/json",
+            "{}",
+        );
+        let exported = Har::from_history(&[entry]);
+        let json = serde_json::to_value(&exported).unwrap();
+        let entry = &json["log"]["entries"][0];
+        assert_eq!(entry["startedDateTime"], "2024-03-01T12:00:00.000Z");
+        assert_eq!(entry["time"], 42.0);
+        assert_eq!(entry["response"]["status"], 301);
+        assert_eq!(entry["response"]["statusText"], "Moved Permanently");
+        assert_eq!(entry["response"]["redirectURL"], "/new");
+        assert_eq!(entry["response"]["content"]["mimeType"], "application/json");
+        assert_eq!(entry["response"]["content"]["text"], "{}");
+        assert_eq!(iso_8601(0), "1970-01-01T00:00:00.000Z");
+    }
+}
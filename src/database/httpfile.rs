@@ -0,0 +1,462 @@
+use super::db::{ImportedCollection, SavedCommand};
+use super::import::saved_command;
+use crate::request::curl::{AuthKind, Curl, Method};
+use crate::request::variables::substitute;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+// Methods that can appear on a request line, the ones Curl has no Method for are skipped
+const HTTP_METHODS: [&str; 10] = [
+    "GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS", "TRACE", "CONNECT", "GRAPHQL",
+];
+
+/// A `.http` / `.rest` file, as used by the VS Code REST Client and JetBrains HTTP Client
+/// https://github.com/Huachao/vscode-restclient#usage
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct HttpFile {
+    // `@name = value` file variables
+    pub variables: Vec<(String, String)>,
+    pub requests: Vec<HttpRequest>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct HttpRequest {
+    // from `# @name`, or the text after `###`
+    pub name: Option<String>,
+    // plain comment lines above the request line
+    pub comments: Vec<String>,
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+}
+
+impl HttpFile {
+    pub fn parse(text: &str) -> Self {
+        let mut file = HttpFile::default();
+        let mut block = Vec::new();
+        let mut title = None;
+        for line in text.lines() {
+            match line.strip_prefix("###") {
+                Some(rest) => {
+                    file.parse_block(&block, title.take());
+                    block.clear();
+                    title = Some(rest.trim().to_string()).filter(|t| !t.is_empty());
+                }
+                None => block.push(line),
+            }
+        }
+        file.parse_block(&block, title);
+        file
+    }
+
+    // A block holds one request (or none, e.g. only variables) between `###` separators
+    fn parse_block(&mut self, lines: &[&str], title: Option<String>) {
+        let mut request = HttpRequest {
+            name: title,
+            ..Default::default()
+        };
+        let mut lines = lines.iter().map(|line| line.trim_end()).peekable();
+        // variables, comments and metadata before the request line
+        for line in lines.by_ref() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            if let Some(variable) = trimmed.strip_prefix('@') {
+                if let Some((name, value)) = variable.split_once('=') {
+                    // earlier variables can be used in the value
+                    let value = substitute(value.trim(), &self.variables);
+                    self.variables.push((name.trim().to_string(), value));
+                }
+                continue;
+            }
+            if let Some(comment) = comment_text(trimmed) {
+                match comment.strip_prefix('@') {
+                    Some(meta) => {
+                        if let Some(name) = meta.strip_prefix("name") {
+                            let name = name.trim_start_matches([' ', '=']).trim();
+                            request.name = Some(name.to_string()).filter(|n| !n.is_empty());
+                        }
+                    }
+                    None if !comment.is_empty() => request.comments.push(comment.to_string()),
+                    None => {}
+                }
+                continue;
+            }
+            let (method, url) = request_line(trimmed);
+            request.method = method;
+            request.url = url;
+            break;
+        }
+        if request.url.is_empty() {
+            return;
+        }
+        // a long query string can continue on the next lines, starting with ? or &
+        while let Some(next) = lines.peek() {
+            let next = next.trim();
+            if !next.starts_with('?') && !next.starts_with('&') {
+                break;
+            }
+            request.url.push_str(next);
+            lines.next();
+        }
+        request.url = strip_version(&request.url);
+        // headers, up to the first empty line
+        for line in lines.by_ref() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                break;
+            }
+            if comment_text(trimmed).is_some() {
+                continue;
+            }
+            if let Some((name, value)) = trimmed.split_once(':') {
+                request
+                    .headers
+                    .push((name.trim().to_string(), value.trim().to_string()));
+            }
+        }
+        // the rest is the body, without the response handlers and redirects at the end
+        let mut body = Vec::new();
+        let mut in_script = false;
+        for line in lines {
+            if in_script {
+                in_script = !line.contains("%}");
+                continue;
+            }
+            if line.starts_with("> {%") {
+                in_script = !line.contains("%}");
+                continue;
+            }
+            if line.starts_with("> ") || line.starts_with(">> ") || line.starts_with("<> ") {
+                continue;
+            }
+            body.push(line);
+        }
+        let body = body.join("\n");
+        let body = body.trim_matches('\n');
+        if !body.trim().is_empty() {
+            request.body = Some(body.to_string());
+        }
+        self.requests.push(request);
+    }
+
+    // The requests as one collection named after the file, with the requests
+    // that can't be saved as commands listed by name
+    pub fn import(&self, name: &str) -> (ImportedCollection, Vec<String>) {
+        let mut skipped = Vec::new();
+        let mut commands = Vec::new();
+        for request in self.requests.iter() {
+            match request.to_command() {
+                Ok(command) => commands.push(command),
+                Err(reason) => skipped.push(format!("{}: {}", request.label(), reason)),
+            }
+        }
+        let collection = ImportedCollection {
+            name: name.to_string(),
+            description: String::from("Imported from a .http file"),
+            variables: self.variables.clone(),
+            commands,
+        };
+        (collection, skipped)
+    }
+
+    // Saved commands as a .http file, so they can be kept in git next to the code
+    pub fn from_commands(commands: &[SavedCommand], variables: &[(String, String)]) -> Self {
+        HttpFile {
+            variables: variables.to_vec(),
+            requests: commands.iter().map(HttpRequest::from_command).collect(),
+        }
+    }
+
+    pub fn write(&self, path: &str) -> Result<(), String> {
+        std::fs::write(path, self.to_string()).map_err(|e| e.to_string())
+    }
+}
+
+impl Display for HttpFile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (name, value) in self.variables.iter() {
+            writeln!(f, "@{} = {}", name, value)?;
+        }
+        for (i, request) in self.requests.iter().enumerate() {
+            if i > 0 || !self.variables.is_empty() {
+                writeln!(f)?;
+            }
+            write!(f, "{}", request)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for HttpRequest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.name {
+            Some(ref name) => writeln!(f, "### {}", name)?,
+            None => writeln!(f, "###")?,
+        }
+        for comment in self.comments.iter() {
+            writeln!(f, "# {}", comment)?;
+        }
+        // REST Client names are used in references, they can't have spaces
+        if let Some(name) = self
+            .name
+            .as_ref()
+            .filter(|n| !n.contains(char::is_whitespace))
+        {
+            writeln!(f, "# @name {}", name)?;
+        }
+        writeln!(f, "{} {}", self.method, self.url)?;
+        for (name, value) in self.headers.iter() {
+            writeln!(f, "{}: {}", name, value)?;
+        }
+        if let Some(ref body) = self.body {
+            writeln!(f)?;
+            writeln!(f, "{}", body)?;
+        }
+        Ok(())
+    }
+}
+
+impl HttpRequest {
+    fn label(&self) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| format!("{} {}", self.method, self.url))
+    }
+
+    fn to_command(&self) -> Result<SavedCommand, String> {
+        let method = Method::from_str(&self.method)
+            .map_err(|_| format!("{} requests are not supported", self.method))?;
+        let mut curl = Curl::new_serializing();
+        curl.set_method(method);
+        // `GET /path` with a Host header
+        let host = self
+            .headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("host"))
+            .map(|(_, value)| value.clone());
+        match host {
+            Some(ref host) if self.url.starts_with('/') => {
+                curl.set_url(&format!("http://{}{}", host, self.url))
+            }
+            _ => curl.set_url(&self.url),
+        }
+        for (name, value) in self.headers.iter() {
+            if name.eq_ignore_ascii_case("host") && host.is_some() {
+                continue;
+            }
+            match header_auth(name, value) {
+                Some(auth) => curl.set_auth(auth),
+                None => curl.add_headers(&format!("{}: {}", name, value)),
+            }
+        }
+        if let Some(ref body) = self.body {
+            if let Some(file) = body.trim().strip_prefix("< ") {
+                return Err(format!("the body is read from the file {}", file.trim()));
+            }
+            curl.set_request_body(body);
+        }
+        let description = match self.comments.is_empty() {
+            true => None,
+            false => Some(self.comments.join("\n")),
+        };
+        Ok(saved_command(curl, self.label(), description))
+    }
+
+    fn from_command(cmd: &SavedCommand) -> Self {
+        let curl: Curl = serde_json::from_str(cmd.get_curl_json()).unwrap_or_default();
+        // the bearer token is part of the request headers already
+        let mut headers: Vec<(String, String)> = curl
+            .get_request_headers()
+            .iter()
+            .filter_map(|header| header.split_once(':'))
+            .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+            .collect();
+        // both clients take the user and password of basic and digest auth as they are
+        match curl.get_auth() {
+            AuthKind::Basic(login) => headers.push(login_header("Basic", login)),
+            AuthKind::Digest(login) => headers.push(login_header("Digest", login)),
+            _ => {}
+        }
+        let cookies = curl
+            .get_cookies()
+            .into_iter()
+            .filter(|cookie| !cookie.is_empty())
+            .collect::<Vec<String>>();
+        if !cookies.is_empty() {
+            headers.push((String::from("Cookie"), cookies.join("; ")));
+        }
+        HttpRequest {
+            name: cmd.label.clone(),
+            comments: cmd
+                .description
+                .as_deref()
+                .unwrap_or_default()
+                .lines()
+                .map(String::from)
+                .collect(),
+            method: curl.get_method().to_string(),
+            url: curl.get_url().to_string(),
+            headers,
+            body: curl.get_request_body().filter(|body| !body.is_empty()),
+        }
+    }
+}
+
+// `# comment` and `// comment` lines
+fn comment_text(line: &str) -> Option<&str> {
+    line.strip_prefix('#')
+        .or(line.strip_prefix("//"))
+        .map(str::trim)
+}
+
+// `METHOD URL [HTTP/1.1]`, or only the URL for a GET request
+fn request_line(line: &str) -> (String, String) {
+    let (method, rest) = match line.split_once(char::is_whitespace) {
+        Some((method, rest)) if HTTP_METHODS.contains(&method.to_uppercase().as_str()) => {
+            (method.to_uppercase(), rest.trim())
+        }
+        _ => (String::from("GET"), line),
+    };
+    (method, rest.to_string())
+}
+
+fn strip_version(url: &str) -> String {
+    match url.rsplit_once(char::is_whitespace) {
+        Some((url, version)) if version.starts_with("HTTP/") => url.trim().to_string(),
+        _ => url.to_string(),
+    }
+}
+
+// `Authorization: Basic user password` (or user:password) is encoded by the clients,
+// a value that's already encoded stays a header
+fn header_auth(name: &str, value: &str) -> Option<AuthKind> {
+    if !name.eq_ignore_ascii_case("authorization") {
+        return None;
+    }
+    let (scheme, credentials) = value.split_once(' ')?;
+    let credentials = credentials.trim();
+    let login = match credentials.split_once(char::is_whitespace) {
+        Some((user, password)) => format!("{}:{}", user, password.trim()),
+        None if credentials.contains(':') => credentials.to_string(),
+        None => return None,
+    };
+    match scheme.to_lowercase().as_str() {
+        "basic" => Some(AuthKind::Basic(login)),
+        "digest" => Some(AuthKind::Digest(login)),
+        _ => None,
+    }
+}
+
+fn login_header(scheme: &str, login: &str) -> (String, String) {
+    let (user, password) = login.split_once(':').unwrap_or((login, ""));
+    (
+        String::from("Authorization"),
+        format!("{} {} {}", scheme, user, password),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HTTP_FILE: &str = "@host = api.example.com
+@baseUrl = https://{{host}}
+
+### List users
+# Every user of the shop
+GET {{baseUrl}}/users
+    ?page=1
+    &limit=10 HTTP/1.1
+Accept: application/json
+# Accept-Language: fr
+
+###
+# @name createUser
+POST {{baseUrl}}/users
+Content-Type: application/json
+Authorization: Basic admin s3cret
+
+{
+    \"name\": \"CuTE\"
+}
+
+> {%
+    client.global.set(\"id\", response.body.id);
+%}
+
+### Upload
+POST {{baseUrl}}/upload
+
+< ./avatar.png
+
+###
+OPTIONS {{baseUrl}}/users
+";
+
+    #[test]
+    fn test_parse_http_file() {
+        let file = HttpFile::parse(HTTP_FILE);
+        assert_eq!(
+            file.variables,
+            vec![
+                (String::from("host"), String::from("api.example.com")),
+                (
+                    String::from("baseUrl"),
+                    String::from("https://api.example.com")
+                ),
+            ]
+        );
+        assert_eq!(file.requests.len(), 4);
+        let list = &file.requests[0];
+        assert_eq!(list.name.as_deref(), Some("List users"));
+        assert_eq!(list.comments, vec![String::from("Every user of the shop")]);
+        assert_eq!(list.url, "{{baseUrl}}/users?page=1&limit=10");
+        assert_eq!(
+            list.headers,
+            vec![(String::from("Accept"), String::from("application/json"))]
+        );
+        assert_eq!(list.body, None);
+        let create = &file.requests[1];
+        assert_eq!(create.name.as_deref(), Some("createUser"));
+        assert_eq!(create.method, "POST");
+        assert_eq!(create.body.as_deref(), Some("{\n    \"name\": \"CuTE\"\n}"));
+
+        let (collection, skipped) = file.import("shop");
+        assert_eq!(collection.name, "shop");
+        assert_eq!(collection.commands.len(), 2);
+        assert_eq!(
+            skipped,
+            vec![
+                String::from("Upload: the body is read from the file ./avatar.png"),
+                String::from("OPTIONS {{baseUrl}}/users: OPTIONS requests are not supported"),
+            ]
+        );
+        let curl: Curl = serde_json::from_str(collection.commands[1].get_curl_json()).unwrap();
+        assert_eq!(
+            curl.get_auth(),
+            &AuthKind::Basic(String::from("admin:s3cret"))
+        );
+        assert_eq!(
+            curl.get_request_headers(),
+            vec!["Content-Type: application/json"]
+        );
+    }
+
+    #[test]
+    fn test_http_file_round_trip() {
+        let file = HttpFile::parse(HTTP_FILE);
+        let (collection, _) = file.import("shop");
+        let exported = HttpFile::from_commands(&collection.commands, &collection.variables);
+        let text = exported.to_string();
+        assert!(text.starts_with("@host = api.example.com\n@baseUrl = https://api.example.com\n"));
+        assert!(text.contains(
+            "### List users\n# Every user of the shop\nGET {{baseUrl}}/users?page=1&limit=10\nAccept: application/json\n"
+        ));
+        assert!(text.contains("### createUser\n# @name createUser\nPOST {{baseUrl}}/users\n"));
+        assert!(text.contains("Authorization: Basic admin s3cret\n"));
+        assert_eq!(HttpFile::parse(&text), exported);
+    }
+}
@@ -0,0 +1,168 @@
+use super::db::{ImportedCollection, SavedCommand};
+use crate::request::curl::Curl;
+use serde_json::Value;
+
+// A folder of a collection being imported, with what the requests in it
+// inherit (auth, headers, variables..) in the terms of the format
+pub struct Folder<T> {
+    pub name: String,
+    pub description: String,
+    pub inherited: T,
+}
+
+impl<T> Folder<T> {
+    // Folders are imported as collections named `Collection / Folder`
+    pub fn sub_folder(&self, name: &str, description: String, inherited: T) -> Folder<T> {
+        Folder {
+            name: format!("{} / {}", self.name, name),
+            description,
+            inherited,
+        }
+    }
+}
+
+// The collections read from another client's export, with a note on
+// everything that couldn't be imported
+#[derive(Debug, Default)]
+pub struct Import {
+    pub collections: Vec<ImportedCollection>,
+    pub skipped: Vec<String>,
+}
+
+impl Import {
+    pub fn skip(&mut self, name: &str, reason: &str) {
+        self.skipped.push(format!("{}: {}", name, reason));
+    }
+
+    // Folders with only sub folders in them don't become a collection
+    pub fn add_collection<T>(
+        &mut self,
+        folder: &Folder<T>,
+        variables: Vec<(String, String)>,
+        commands: Vec<SavedCommand>,
+    ) {
+        if !commands.is_empty() {
+            self.collections.push(ImportedCollection {
+                name: folder.name.clone(),
+                description: folder.description.clone(),
+                variables,
+                commands,
+            });
+        }
+    }
+
+    pub fn finish(self) -> (Vec<ImportedCollection>, Vec<String>) {
+        (self.collections, self.skipped)
+    }
+}
+
+pub fn saved_command(mut curl: Curl, name: String, description: Option<String>) -> SavedCommand {
+    let cmd = curl.get_command_string();
+    let curl_json = serde_json::to_string(&curl).unwrap_or_default();
+    SavedCommand::new(&cmd, Some(name), description, &curl_json, None)
+}
+
+// The clients add the Content-Type of a body themselves, unless a header sets it
+pub fn add_content_type(headers: &mut Vec<(String, String)>, content_type: Option<&str>) {
+    if let Some(content_type) = content_type {
+        if !headers
+            .iter()
+            .any(|(key, _)| key.eq_ignore_ascii_case("content-type"))
+        {
+            headers.push((String::from("Content-Type"), content_type.to_string()));
+        }
+    }
+}
+
+pub fn form_urlencoded(pairs: &[(String, String)]) -> String {
+    pairs
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<String>>()
+        .join("&")
+}
+
+// For api keys sent as a query parameter
+pub fn append_query(url: &str, key: &str, value: &str) -> String {
+    let separator = if url.contains('?') { '&' } else { '?' };
+    format!("{}{}{}={}", url, separator, key, value)
+}
+
+// Fills in the whole `:name` segments of the URL's path, so the `id`
+// variable leaves `/:idx` and `?at=:id` alone
+pub fn replace_path_variable(url: &str, name: &str, value: &str) -> String {
+    let segment = format!("/:{}", name);
+    let (path, rest) = url.split_at(url.find(['?', '#']).unwrap_or(url.len()));
+    let mut replaced = String::new();
+    let mut remaining = path;
+    while let Some(start) = remaining.find(&segment) {
+        let after = &remaining[start + segment.len()..];
+        replaced.push_str(&remaining[..=start]);
+        if after.is_empty() || after.starts_with('/') {
+            replaced.push_str(value);
+        } else {
+            replaced.push_str(&segment[1..]);
+        }
+        remaining = after;
+    }
+    replaced.push_str(remaining);
+    replaced.push_str(rest);
+    replaced
+}
+
+// A JSON value as the text of a variable, header or parameter
+pub fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Array(items) => items
+            .iter()
+            .map(value_text)
+            .collect::<Vec<String>>()
+            .join(","),
+        Value::Null => String::new(),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replace_path_variable() {
+        assert_eq!(
+            replace_path_variable("{{baseUrl}}/users/:id/posts/:idx?at=:id", "id", "1"),
+            "{{baseUrl}}/users/1/posts/:idx?at=:id"
+        );
+        assert_eq!(
+            replace_path_variable("http://host:8080/:id", "id", "1"),
+            "http://host:8080/1"
+        );
+        assert_eq!(replace_path_variable("/a/:ids", "id", "1"), "/a/:ids");
+    }
+
+    #[test]
+    fn test_import_helpers() {
+        let mut headers = vec![(String::from("content-type"), String::from("text/csv"))];
+        add_content_type(&mut headers, Some("application/json"));
+        assert_eq!(headers.len(), 1);
+        assert_eq!(append_query("/a?b=1", "key", "v"), "/a?b=1&key=v");
+        assert_eq!(append_query("/a", "key", "v"), "/a?key=v");
+        assert_eq!(value_text(&serde_json::json!(["a", 1])), "a,1");
+        let root = Folder {
+            name: String::from("Shop"),
+            description: String::new(),
+            inherited: (),
+        };
+        let mut import = Import::default();
+        import.add_collection(&root, Vec::new(), Vec::new());
+        import.skip(
+            &root.sub_folder("Orders", String::new(), ()).name,
+            "not run",
+        );
+        assert_eq!(
+            import.finish(),
+            (Vec::new(), vec![String::from("Shop / Orders: not run")])
+        );
+    }
+}
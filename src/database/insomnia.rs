@@ -0,0 +1,365 @@
+use super::db::{ImportedCollection, SavedCommand};
+use super::import::{
+    add_content_type, append_query, form_urlencoded, saved_command, Folder, Import,
+};
+use crate::request::curl::{AuthKind, Curl, Method};
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+// Insomnia refers to environment variables as `{{ _.name }}`, CuTE as `{{name}}`
+const VARIABLE_PATTERN: &str = r"\{\{\s*_\.([^{}\s]+)\s*\}\}";
+
+/// An Insomnia v4 export (Application menu > Export Data).
+/// Workspaces and request groups become collections, the
+/// base and first sub environment become their variables.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct InsomniaExport {
+    #[serde(rename = "__export_format")]
+    pub export_format: u32,
+    pub resources: Vec<Resource>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Resource {
+    #[serde(rename = "_id")]
+    pub id: String,
+    // workspace, request_group, request, environment, cookie_jar...
+    #[serde(rename = "_type")]
+    pub kind: String,
+    pub parent_id: Option<String>,
+    pub name: String,
+    pub description: String,
+    pub meta_sort_key: f64,
+    pub url: String,
+    pub method: String,
+    pub headers: Vec<Pair>,
+    pub parameters: Vec<Pair>,
+    pub body: Body,
+    pub authentication: Value,
+    // variables of an environment
+    pub data: Map<String, Value>,
+    // variables of a request group
+    pub environment: Map<String, Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Pair {
+    pub name: String,
+    pub value: String,
+    pub disabled: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Body {
+    pub mime_type: String,
+    pub text: String,
+    pub params: Vec<Pair>,
+}
+
+impl InsomniaExport {
+    pub fn is_insomnia_json(text: &str) -> bool {
+        serde_json::from_str::<Value>(text).is_ok_and(|export| export["_type"] == "export")
+    }
+
+    fn children<'a>(
+        &'a self,
+        parent: &'a str,
+        kind: &'a str,
+    ) -> impl Iterator<Item = &'a Resource> {
+        self.resources
+            .iter()
+            .filter(move |r| r.kind == kind && r.parent_id.as_deref() == Some(parent))
+    }
+
+    // The base environment of a workspace, overridden by its first sub environment
+    fn environment(&self, workspace: &Resource) -> Vec<(String, String)> {
+        let mut variables = Vec::new();
+        if let Some(base) = self.children(&workspace.id, "environment").next() {
+            flatten("", &base.data, &mut variables);
+            let mut sub_environments: Vec<&Resource> =
+                self.children(&base.id, "environment").collect();
+            sub_environments.sort_by(|a, b| a.meta_sort_key.total_cmp(&b.meta_sort_key));
+            if let Some(sub) = sub_environments.first() {
+                flatten("", &sub.data, &mut variables);
+            }
+        }
+        variables
+    }
+
+    // Each workspace and request group becomes a collection named
+    // `Workspace / Group`, with a note on everything that couldn't be imported
+    pub fn import(&self) -> (Vec<ImportedCollection>, Vec<String>) {
+        let mut import = Import::default();
+        for workspace in self.resources.iter().filter(|r| r.kind == "workspace") {
+            let folder = Folder {
+                name: workspace.name.clone(),
+                description: workspace.collection_description(),
+                inherited: self.environment(workspace),
+            };
+            self.add_folder(&mut import, workspace, &folder);
+        }
+        import.finish()
+    }
+
+    // The requests directly in `parent`, then its request groups, which
+    // inherit the variables of the groups they are in
+    fn add_folder(
+        &self,
+        import: &mut Import,
+        parent: &Resource,
+        folder: &Folder<Vec<(String, String)>>,
+    ) {
+        let mut variables = folder.inherited.clone();
+        flatten("", &parent.environment, &mut variables);
+        let mut requests: Vec<&Resource> = self.children(&parent.id, "request").collect();
+        requests.sort_by(|a, b| a.meta_sort_key.total_cmp(&b.meta_sort_key));
+        let commands = requests.iter().map(|r| r.to_command(import)).collect();
+        import.add_collection(folder, variables.clone(), commands);
+        let mut groups: Vec<&Resource> = self.children(&parent.id, "request_group").collect();
+        groups.sort_by(|a, b| a.meta_sort_key.total_cmp(&b.meta_sort_key));
+        for group in groups {
+            let sub_folder = folder.sub_folder(
+                &group.name,
+                group.collection_description(),
+                variables.clone(),
+            );
+            self.add_folder(import, group, &sub_folder);
+        }
+    }
+}
+
+impl Resource {
+    fn collection_description(&self) -> String {
+        match self.description.trim() {
+            "" => String::from("Imported from Insomnia"),
+            description => description.to_string(),
+        }
+    }
+
+    fn to_command(&self, import: &mut Import) -> SavedCommand {
+        let mut curl = Curl::new_serializing();
+        let mut url = enabled(&self.parameters)
+            .iter()
+            .fold(self.url.clone(), |url, (name, value)| {
+                append_query(&url, name, value)
+            });
+        let mut headers = enabled(&self.headers);
+        self.add_auth(import, &mut curl, &mut url, &mut headers);
+        curl.set_url(&convert_variables(&url));
+        curl.set_method(Method::from_str(&self.method.to_uppercase()).unwrap_or_default());
+        let body = match self.body.text.is_empty() {
+            true => form_urlencoded(&enabled(&self.body.params)),
+            false => self.body.text.clone(),
+        };
+        // multipart bodies need files, they are left for the user to fill in
+        if self.body.mime_type.starts_with("multipart/") {
+            if !body.is_empty() {
+                import.skip(&self.name, "multipart bodies are not imported");
+            }
+        } else if !body.is_empty() {
+            let mime_type = Some(self.body.mime_type.as_str()).filter(|t| !t.is_empty());
+            add_content_type(&mut headers, mime_type);
+            curl.set_request_body(&convert_variables(&body));
+        }
+        headers.iter().for_each(|(name, value)| {
+            curl.add_headers(&convert_variables(&format!("{}: {}", name, value)))
+        });
+        let description = Some(self.description.trim().to_string()).filter(|d| !d.is_empty());
+        saved_command(curl, self.name.clone(), description)
+    }
+
+    // Basic, digest, bearer, NTLM and API key auth, the other kinds need a login flow
+    fn add_auth(
+        &self,
+        import: &mut Import,
+        curl: &mut Curl,
+        url: &mut String,
+        headers: &mut Vec<(String, String)>,
+    ) {
+        let auth = &self.authentication;
+        if auth["disabled"].as_bool().unwrap_or_default() {
+            return;
+        }
+        let field = |name: &str| convert_variables(auth[name].as_str().unwrap_or_default());
+        let login = format!("{}:{}", field("username"), field("password"));
+        match auth["type"].as_str().unwrap_or_default() {
+            "basic" => curl.set_auth(AuthKind::Basic(login)),
+            "digest" => curl.set_auth(AuthKind::Digest(login)),
+            "ntlm" => curl.set_auth(AuthKind::Ntlm),
+            "bearer" => match field("prefix").as_str() {
+                "" | "Bearer" => curl.set_auth(AuthKind::Bearer(field("token"))),
+                prefix => headers.push((
+                    String::from("Authorization"),
+                    format!("{} {}", prefix, field("token")),
+                )),
+            },
+            "apikey" => match auth["addTo"].as_str().unwrap_or_default() {
+                "queryParams" => *url = append_query(url, &field("key"), &field("value")),
+                "cookie" => curl.add_cookie(&format!("{}={}", field("key"), field("value"))),
+                _ => headers.push((field("key"), field("value"))),
+            },
+            "" | "none" => {}
+            kind => import.skip(&self.name, &format!("{} auth is not supported", kind)),
+        }
+    }
+}
+
+fn enabled(pairs: &[Pair]) -> Vec<(String, String)> {
+    pairs
+        .iter()
+        .filter(|p| !p.disabled && !p.name.is_empty())
+        .map(|p| (p.name.clone(), p.value.clone()))
+        .collect()
+}
+
+fn convert_variables(text: &str) -> String {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN
+        .get_or_init(|| Regex::new(VARIABLE_PATTERN).expect("valid variable regex"))
+        .replace_all(text, "{{$1}}")
+        .into_owned()
+}
+
+// Nested environment objects are referred to with dots, e.g. `{{ _.api.url }}`
+fn flatten(prefix: &str, data: &Map<String, Value>, variables: &mut Vec<(String, String)>) {
+    for (name, value) in data {
+        let name = match prefix {
+            "" => name.clone(),
+            prefix => format!("{}.{}", prefix, name),
+        };
+        variables.retain(|(existing, _)| existing != &name);
+        match value {
+            Value::Object(nested) => flatten(&name, nested, variables),
+            // environment values can refer to other variables too
+            Value::String(text) => variables.push((name, convert_variables(text))),
+            value => variables.push((name, value.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = r#"{
+        "_type": "export",
+        "__export_format": 4,
+        "resources": [
+            {"_id": "wrk_1", "_type": "workspace", "parentId": null, "name": "Shop", "description": ""},
+            {"_id": "env_base", "_type": "environment", "parentId": "wrk_1", "name": "Base Environment",
+                "data": {"baseUrl": "http://localhost:3000", "api": {"version": 2}}},
+            {"_id": "env_prod", "_type": "environment", "parentId": "env_base", "name": "Production",
+                "metaSortKey": 2, "data": {"baseUrl": "https://shop.example.com"}},
+            {"_id": "env_dev", "_type": "environment", "parentId": "env_base", "name": "Development",
+                "metaSortKey": 1, "data": {"token": "dev-token"}},
+            {"_id": "req_1", "_type": "request", "parentId": "wrk_1", "name": "Health", "method": "GET",
+                "url": "{{ _.baseUrl }}/health", "parameters": [{"name": "verbose", "value": "1"},
+                {"name": "debug", "value": "1", "disabled": true}], "headers": [], "body": {},
+                "authentication": {}},
+            {"_id": "fld_1", "_type": "request_group", "parentId": "wrk_1", "name": "Orders",
+                "description": "Order endpoints", "environment": {"orderId": "42"}},
+            {"_id": "req_2", "_type": "request", "parentId": "fld_1", "name": "Create order", "method": "POST",
+                "url": "{{ _.baseUrl }}/v{{ _.api.version }}/orders", "description": "Places an order",
+                "headers": [{"name": "X-Trace", "value": "on"}],
+                "body": {"mimeType": "application/json", "text": "{\"id\": \"{{ _.orderId }}\"}"},
+                "authentication": {"type": "bearer", "token": "{{ _.token }}"}},
+            {"_id": "req_3", "_type": "request", "parentId": "fld_1", "name": "Login", "method": "POST",
+                "url": "{{ _.baseUrl }}/login", "metaSortKey": 5,
+                "body": {"mimeType": "application/x-www-form-urlencoded",
+                    "params": [{"name": "user", "value": "jane"}, {"name": "pass", "value": "secret"}]},
+                "authentication": {"type": "basic", "username": "jane", "password": "pw"}},
+            {"_id": "req_4", "_type": "request", "parentId": "wrk_1", "name": "Search", "method": "GET",
+                "url": "{{ _.baseUrl }}/search?q=shoes", "metaSortKey": 1,
+                "authentication": {"type": "apikey", "key": "api_key", "value": "{{ _.token }}",
+                    "addTo": "queryParams"}},
+            {"_id": "req_5", "_type": "request", "parentId": "wrk_1", "name": "Profile", "method": "GET",
+                "url": "{{ _.baseUrl }}/me", "metaSortKey": 2,
+                "authentication": {"type": "apikey", "key": "sid", "value": "abc", "addTo": "cookie"}},
+            {"_id": "req_6", "_type": "request", "parentId": "wrk_1", "name": "Upload", "method": "POST",
+                "url": "{{ _.baseUrl }}/upload", "metaSortKey": 3,
+                "body": {"mimeType": "multipart/form-data", "params": [{"name": "file", "value": ""}]},
+                "authentication": {"type": "oauth2", "grantType": "client_credentials"}}
+        ]
+    }"#;
+
+    #[test]
+    fn test_insomnia_collections() {
+        assert!(InsomniaExport::is_insomnia_json(EXPORT));
+        let export: InsomniaExport = serde_json::from_str(EXPORT).unwrap();
+        let (collections, skipped) = export.import();
+        assert_eq!(collections.len(), 2);
+        assert_eq!(
+            skipped,
+            vec![
+                String::from("Upload: oauth2 auth is not supported"),
+                String::from("Upload: multipart bodies are not imported"),
+            ]
+        );
+        let shop = &collections[0];
+        assert_eq!(shop.name, "Shop");
+        assert_eq!(shop.description, "Imported from Insomnia");
+        assert_eq!(
+            shop.variables,
+            vec![
+                (String::from("api.version"), String::from("2")),
+                (
+                    String::from("baseUrl"),
+                    String::from("http://localhost:3000")
+                ),
+                (String::from("token"), String::from("dev-token")),
+            ]
+        );
+        let health: Curl = serde_json::from_str(shop.commands[0].get_curl_json()).unwrap();
+        assert_eq!(health.get_url(), "{{baseUrl}}/health?verbose=1");
+        let search: Curl = serde_json::from_str(shop.commands[1].get_curl_json()).unwrap();
+        assert_eq!(
+            search.get_url(),
+            "{{baseUrl}}/search?q=shoes&api_key={{token}}"
+        );
+        let profile: Curl = serde_json::from_str(shop.commands[2].get_curl_json()).unwrap();
+        assert_eq!(profile.get_cookies(), vec!["sid=abc"]);
+
+        let orders = &collections[1];
+        assert_eq!(orders.name, "Shop / Orders");
+        assert_eq!(orders.description, "Order endpoints");
+        assert_eq!(
+            orders.variables.last(),
+            Some(&(String::from("orderId"), String::from("42")))
+        );
+        let create = &orders.commands[0];
+        assert_eq!(create.label.as_deref(), Some("Create order"));
+        assert_eq!(create.description.as_deref(), Some("Places an order"));
+        let curl: Curl = serde_json::from_str(create.get_curl_json()).unwrap();
+        assert_eq!(curl.get_url(), "{{baseUrl}}/v{{api.version}}/orders");
+        assert_eq!(curl.get_method().to_string(), "POST");
+        assert_eq!(
+            curl.get_request_headers(),
+            vec![
+                String::from("Authorization: Bearer {{token}}"),
+                String::from("X-Trace: on"),
+                String::from("Content-Type: application/json"),
+            ]
+        );
+        assert_eq!(
+            curl.get_request_body().as_deref(),
+            Some("{\"id\": \"{{orderId}}\"}")
+        );
+        let login: Curl = serde_json::from_str(orders.commands[1].get_curl_json()).unwrap();
+        assert_eq!(
+            login.get_request_body().as_deref(),
+            Some("user=jane&pass=secret")
+        );
+        assert!(login
+            .opts
+            .contains(&crate::display::AppOptions::Auth(AuthKind::Basic(
+                String::from("jane:pw")
+            ))));
+    }
+}
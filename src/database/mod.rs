@@ -1,2 +1,8 @@
+pub mod bruno;
 pub mod db;
+pub mod har;
+pub mod httpfile;
+pub mod import;
+pub mod insomnia;
+pub mod openapi;
 pub mod postman;
@@ -0,0 +1,563 @@
+use super::db::SavedCommand;
+use super::import::{add_content_type, append_query, saved_command, value_text};
+use crate::request::curl::{Curl, Method};
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+// Operations of a path item that are imported, OPTIONS and TRACE
+// operations are left out as there is no Method to send them with
+const METHODS: [&str; 6] = ["get", "post", "put", "patch", "delete", "head"];
+
+// How far $refs and nested schemas are followed when generating an example body,
+// recursive schemas would never end otherwise
+const MAX_DEPTH: usize = 8;
+
+// Used when the spec doesn't say where the API is served
+const DEFAULT_BASE_URL: &str = "http://localhost";
+
+/// An OpenAPI 3 or Swagger 2 description of an API, in JSON or YAML.
+/// Each operation becomes a saved command, with the server URL
+/// kept in the `baseUrl` variable of the collection.
+pub struct OpenApi {
+    spec: Value,
+}
+
+impl OpenApi {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let spec: Value = match serde_json::from_str(text) {
+            Ok(spec) => spec,
+            Err(_) => serde_yaml::from_str(text).map_err(|e| e.to_string())?,
+        };
+        match OpenApi::is_openapi(&spec) {
+            true => Ok(Self { spec }),
+            false => Err(String::from(
+                "Not an OpenAPI or Swagger document: the 'openapi' or 'swagger' field is missing",
+            )),
+        }
+    }
+
+    pub fn is_openapi(spec: &Value) -> bool {
+        spec.get("openapi").is_some() || spec.get("swagger").is_some()
+    }
+
+    // Tells OpenAPI specs apart from Postman collections, which are JSON too
+    pub fn is_openapi_json(text: &str) -> bool {
+        serde_json::from_str::<Value>(text).is_ok_and(|spec| OpenApi::is_openapi(&spec))
+    }
+
+    pub fn name(&self) -> String {
+        self.spec["info"]["title"]
+            .as_str()
+            .unwrap_or("OpenAPI")
+            .to_string()
+    }
+
+    pub fn description(&self) -> String {
+        self.spec["info"]["description"]
+            .as_str()
+            .unwrap_or("Imported from OpenAPI")
+            .trim()
+            .to_string()
+    }
+
+    // The first server of an OpenAPI 3 spec, or the host, basePath and scheme of Swagger 2
+    pub fn base_url(&self) -> String {
+        let url = match self.spec.get("swagger") {
+            Some(_) => match self.spec["host"].as_str() {
+                Some(host) => format!(
+                    "{}://{}{}",
+                    self.spec["schemes"][0].as_str().unwrap_or("https"),
+                    host,
+                    self.spec["basePath"].as_str().unwrap_or_default()
+                ),
+                None => DEFAULT_BASE_URL.to_string(),
+            },
+            None => {
+                let server = &self.spec["servers"][0];
+                let mut url = server["url"]
+                    .as_str()
+                    .unwrap_or(DEFAULT_BASE_URL)
+                    .to_string();
+                if let Some(variables) = server["variables"].as_object() {
+                    for (name, variable) in variables {
+                        let default = value_text(&variable["default"]);
+                        url = url.replace(&format!("{{{}}}", name), &default);
+                    }
+                }
+                url
+            }
+        };
+        url.trim_end_matches('/').to_string()
+    }
+
+    pub fn variables(&self) -> Vec<(String, String)> {
+        vec![(String::from("baseUrl"), self.base_url())]
+    }
+
+    // Follows local $refs, e.g. '#/components/schemas/Pet'
+    fn resolve<'a>(&'a self, mut value: &'a Value) -> &'a Value {
+        for _ in 0..MAX_DEPTH {
+            let Some(reference) = value["$ref"].as_str() else {
+                break;
+            };
+            match reference
+                .strip_prefix('#')
+                .and_then(|pointer| self.spec.pointer(pointer))
+            {
+                Some(target) => value = target,
+                None => break,
+            }
+        }
+        value
+    }
+
+    // Parameters of the path item overridden by those of the operation
+    fn parameters<'a>(&'a self, path_item: &'a Value, operation: &'a Value) -> Vec<&'a Value> {
+        let mut parameters: Vec<&Value> = Vec::new();
+        let declared = [&path_item["parameters"], &operation["parameters"]];
+        for param in declared
+            .iter()
+            .filter_map(|params| params.as_array())
+            .flatten()
+            .map(|param| self.resolve(param))
+        {
+            parameters.retain(|p| p["name"] != param["name"] || p["in"] != param["in"]);
+            parameters.push(param);
+        }
+        parameters
+    }
+
+    // An example value for a parameter, required parameters without one become a `{{name}}` placeholder
+    fn parameter_value(&self, param: &Value) -> Option<String> {
+        let schema = self.resolve(&param["schema"]);
+        let example = [
+            &param["example"],
+            &param["x-example"],
+            &schema["example"],
+            &param["default"],
+            &schema["default"],
+            &param["enum"][0],
+            &schema["enum"][0],
+        ]
+        .into_iter()
+        .find(|value| !value.is_null())
+        .cloned()
+        .or_else(|| {
+            param["examples"]
+                .as_object()
+                .and_then(|examples| examples.values().next())
+                .map(|example| self.resolve(example)["value"].clone())
+                .filter(|value| !value.is_null())
+        });
+        match example {
+            Some(example) => Some(value_text(&example)),
+            None if param["required"].as_bool().unwrap_or_default() => Some(format!(
+                "{{{{{}}}}}",
+                param["name"].as_str().unwrap_or_default()
+            )),
+            None => None,
+        }
+    }
+
+    // Generates an example from a schema, preferring the examples it declares
+    fn schema_example(&self, schema: &Value, depth: usize) -> Value {
+        if depth > MAX_DEPTH {
+            return Value::Null;
+        }
+        let schema = self.resolve(schema);
+        if let Some(example) = [
+            &schema["example"],
+            &schema["examples"][0],
+            &schema["default"],
+        ]
+        .into_iter()
+        .find(|value| !value.is_null())
+        {
+            return example.clone();
+        }
+        if let Some(value) = schema["enum"].as_array().and_then(|values| values.first()) {
+            return value.clone();
+        }
+        if let Some(all) = schema["allOf"].as_array() {
+            let mut merged = Map::new();
+            for part in all {
+                if let Value::Object(members) = self.schema_example(part, depth + 1) {
+                    merged.extend(members);
+                }
+            }
+            return Value::Object(merged);
+        }
+        if let Some(first) = ["oneOf", "anyOf"]
+            .iter()
+            .find_map(|key| schema[*key].as_array().and_then(|schemas| schemas.first()))
+        {
+            return self.schema_example(first, depth + 1);
+        }
+        // OpenAPI 3.1 allows a list of types, e.g. ["string", "null"]
+        let kind = match &schema["type"] {
+            Value::Array(kinds) => kinds
+                .iter()
+                .find_map(|k| k.as_str().filter(|k| *k != "null")),
+            kind => kind.as_str(),
+        };
+        match kind {
+            Some("object") | None if schema["properties"].is_object() => Value::Object(
+                schema["properties"]
+                    .as_object()
+                    .into_iter()
+                    .flatten()
+                    .map(|(name, property)| {
+                        (name.clone(), self.schema_example(property, depth + 1))
+                    })
+                    .collect(),
+            ),
+            Some("object") => Value::Object(Map::new()),
+            Some("array") => Value::Array(vec![self.schema_example(&schema["items"], depth + 1)]),
+            Some("string") => Value::from(match schema["format"].as_str() {
+                Some("date-time") => "2024-01-01T00:00:00Z",
+                Some("date") => "2024-01-01",
+                Some("email") => "user@example.com",
+                Some("uuid") => "3fa85f64-5717-4562-b3fc-2c963f66afa6",
+                Some("uri") | Some("url") => "https://example.com",
+                _ => "string",
+            }),
+            Some("integer") => Value::from(0),
+            Some("number") => Value::from(0.0),
+            Some("boolean") => Value::from(true),
+            _ => Value::Null,
+        }
+    }
+
+    // The media type and example of an OpenAPI 3 requestBody, JSON is preferred when there's a choice
+    fn request_body(&self, operation: &Value) -> Option<(String, Value)> {
+        let content = self.resolve(&operation["requestBody"])["content"].as_object()?;
+        let (media_type, media) = content
+            .iter()
+            .find(|(media_type, _)| media_type.contains("json"))
+            .or_else(|| content.iter().next())?;
+        let example = match media.get("example") {
+            Some(example) => example.clone(),
+            None => match media["examples"]
+                .as_object()
+                .and_then(|e| e.values().next())
+            {
+                Some(example) => self.resolve(example)["value"].clone(),
+                None => self.schema_example(&media["schema"], 0),
+            },
+        };
+        Some((media_type.clone(), example))
+    }
+
+    // Swagger 2 has the body as an 'in: body' parameter, or forms as 'in: formData' parameters
+    fn swagger_body(&self, operation: &Value, parameters: &[&Value]) -> Option<(String, Value)> {
+        let consumes = operation["consumes"][0]
+            .as_str()
+            .or(self.spec["consumes"][0].as_str());
+        if let Some(param) = parameters.iter().find(|p| p["in"] == "body") {
+            let media_type = consumes.unwrap_or("application/json");
+            return Some((
+                media_type.to_string(),
+                self.schema_example(&param["schema"], 0),
+            ));
+        }
+        let form: Map<String, Value> = parameters
+            .iter()
+            .filter(|p| p["in"] == "formData")
+            .filter_map(|p| {
+                let value = self.parameter_value(p)?;
+                Some((p["name"].as_str()?.to_string(), Value::from(value)))
+            })
+            .collect();
+        match form.is_empty() {
+            true => None,
+            false => Some((
+                consumes
+                    .unwrap_or("application/x-www-form-urlencoded")
+                    .to_string(),
+                Value::Object(form),
+            )),
+        }
+    }
+
+    fn to_command(
+        &self,
+        path: &str,
+        method: &str,
+        path_item: &Value,
+        operation: &Value,
+    ) -> Option<SavedCommand> {
+        let mut curl = Curl::new_serializing();
+        let parameters = self.parameters(path_item, operation);
+        // path parameters stay placeholders: /pets/{petId} -> /pets/{{petId}}
+        static PATH_PARAMETER: OnceLock<Regex> = OnceLock::new();
+        let path = PATH_PARAMETER
+            .get_or_init(|| Regex::new(r"\{([^{}]+)\}").expect("valid path parameter regex"))
+            .replace_all(path, "{{$1}}");
+        let url = parameters
+            .iter()
+            .filter(|p| p["in"] == "query")
+            .filter_map(|p| Some((p["name"].as_str()?, self.parameter_value(p)?)))
+            .fold(format!("{{{{baseUrl}}}}{}", path), |url, (name, value)| {
+                append_query(&url, name, &encode_query(&value))
+            });
+        curl.set_url(&url);
+        curl.set_method(Method::from_str(&method.to_uppercase()).ok()?);
+        let mut headers: Vec<(String, String)> = parameters
+            .iter()
+            .filter(|p| p["in"] == "header")
+            .filter_map(|p| Some((p["name"].as_str()?.to_string(), self.parameter_value(p)?)))
+            .collect();
+        let body = match self.spec.get("swagger") {
+            Some(_) => self.swagger_body(operation, &parameters),
+            None => self.request_body(operation),
+        };
+        // multipart bodies need files, they are left for the user to fill in
+        let body = body.filter(|(t, _)| !t.starts_with("multipart/"));
+        add_content_type(&mut headers, body.as_ref().map(|(t, _)| t.as_str()));
+        headers
+            .iter()
+            .for_each(|(name, value)| curl.add_headers(&format!("{}: {}", name, value)));
+        if let Some((media_type, example)) = body {
+            curl.set_request_body(&body_text(&media_type, &example));
+        }
+        let label = match operation["operationId"].as_str() {
+            Some(id) => id.to_string(),
+            None => format!("{} {}", method.to_uppercase(), path),
+        };
+        let description = operation["summary"]
+            .as_str()
+            .or(operation["description"].as_str())
+            .map(|text| text.trim().to_string());
+        Some(saved_command(curl, label, description))
+    }
+}
+
+impl From<OpenApi> for Vec<SavedCommand> {
+    fn from(spec: OpenApi) -> Vec<SavedCommand> {
+        let Some(paths) = spec.spec["paths"].as_object() else {
+            return Vec::new();
+        };
+        paths
+            .iter()
+            .flat_map(|(path, item)| {
+                let item = spec.resolve(item);
+                METHODS.iter().filter_map(|method| {
+                    let operation = item.get(*method)?;
+                    spec.to_command(path, method, item, operation)
+                })
+            })
+            .collect()
+    }
+}
+
+// Percent encodes an example query value, placeholders are left for the variables to resolve
+fn encode_query(value: &str) -> String {
+    if value.starts_with("{{") && value.ends_with("}}") {
+        return value.to_string();
+    }
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b',' => {
+                (byte as char).to_string()
+            }
+            byte => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+fn body_text(media_type: &str, example: &Value) -> String {
+    match example {
+        Value::String(text) => text.clone(),
+        Value::Object(fields) if media_type.contains("x-www-form-urlencoded") => fields
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, encode_query(&value_text(value))))
+            .collect::<Vec<String>>()
+            .join("&"),
+        example if media_type.contains("json") => {
+            serde_json::to_string_pretty(example).unwrap_or_default()
+        }
+        example => example.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request::curl::Curl;
+
+    fn commands(text: &str) -> Vec<(String, Curl, SavedCommand)> {
+        let commands: Vec<SavedCommand> = OpenApi::parse(text).unwrap().into();
+        commands
+            .into_iter()
+            .map(|cmd| {
+                let curl: Curl = serde_json::from_str(cmd.get_curl_json()).unwrap();
+                (cmd.label.clone().unwrap(), curl, cmd)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_openapi_3_yaml() {
+        let spec = r#"
+openapi: 3.0.3
+info:
+  title: Petstore
+  description: A sample API
+servers:
+  - url: https://{region}.example.com/v1/
+    variables:
+      region:
+        default: eu
+paths:
+  /pets/{petId}:
+    parameters:
+      - name: petId
+        in: path
+        required: true
+        schema:
+          type: integer
+    get:
+      operationId: showPetById
+      summary: Info for a specific pet
+      parameters:
+        - name: fields
+          in: query
+          example: name tag
+        - name: X-Request-Id
+          in: header
+          required: true
+          schema:
+            type: string
+      responses:
+        200:
+          description: Expected response to a valid request
+  /pets:
+    post:
+      summary: Create a pet
+      requestBody:
+        content:
+          application/json:
+            schema:
+              $ref: '#/components/schemas/Pet'
+      responses:
+        '201':
+          description: Null response
+    options:
+      responses:
+        '200':
+          description: Allowed methods
+components:
+  schemas:
+    Pet:
+      type: object
+      required: [id, name]
+      properties:
+        id:
+          type: integer
+          format: int64
+        name:
+          type: string
+          example: doggie
+        tags:
+          type: array
+          items:
+            $ref: '#/components/schemas/Tag'
+    Tag:
+      allOf:
+        - properties:
+            name:
+              type: string
+        - properties:
+            created:
+              type: string
+              format: date-time
+"#;
+        let openapi = OpenApi::parse(spec).unwrap();
+        assert_eq!(openapi.name(), "Petstore");
+        assert_eq!(openapi.base_url(), "https://eu.example.com/v1");
+        let commands = commands(spec);
+        // the OPTIONS operation is skipped
+        assert_eq!(commands.len(), 2);
+        let (label, curl, cmd) = &commands[0];
+        assert_eq!(label, "POST /pets");
+        assert_eq!(cmd.description.as_deref(), Some("Create a pet"));
+        assert_eq!(curl.get_url(), "{{baseUrl}}/pets");
+        assert_eq!(curl.get_method().to_string(), "POST");
+        let body: Value = serde_json::from_str(&curl.get_request_body().unwrap()).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({"id": 0, "name": "doggie",
+                "tags": [{"name": "string", "created": "2024-01-01T00:00:00Z"}]})
+        );
+        assert!(curl
+            .get_request_headers()
+            .contains(&String::from("Content-Type: application/json")));
+        let (label, curl, cmd) = &commands[1];
+        assert_eq!(label, "showPetById");
+        assert_eq!(cmd.description.as_deref(), Some("Info for a specific pet"));
+        assert_eq!(
+            curl.get_url(),
+            "{{baseUrl}}/pets/{{petId}}?fields=name%20tag"
+        );
+        assert_eq!(
+            curl.get_request_headers(),
+            vec![String::from("X-Request-Id: {{X-Request-Id}}")]
+        );
+        assert!(curl.get_request_body().is_none());
+    }
+
+    #[test]
+    fn test_swagger_2_json() {
+        let spec = r##"{
+            "swagger": "2.0",
+            "info": {"title": "Users", "version": "1.0"},
+            "host": "api.example.com",
+            "basePath": "/v2",
+            "schemes": ["http"],
+            "paths": {
+                "/users": {
+                    "put": {
+                        "operationId": "updateUser",
+                        "parameters": [
+                            {"name": "dryRun", "in": "query", "type": "boolean", "default": false},
+                            {"name": "user", "in": "body", "schema": {"$ref": "#/definitions/User"}}
+                        ]
+                    },
+                    "post": {
+                        "consumes": ["application/x-www-form-urlencoded"],
+                        "parameters": [
+                            {"name": "name", "in": "formData", "type": "string", "x-example": "Jane Doe"},
+                            {"name": "age", "in": "formData", "type": "integer"}
+                        ]
+                    }
+                }
+            },
+            "definitions": {
+                "User": {"type": "object", "properties": {"email": {"type": "string", "format": "email"}}}
+            }
+        }"##;
+        assert!(OpenApi::is_openapi_json(spec));
+        assert!(!OpenApi::is_openapi_json(r#"{"info": {}, "item": []}"#));
+        assert_eq!(
+            OpenApi::parse(spec).unwrap().variables(),
+            vec![(
+                String::from("baseUrl"),
+                String::from("http://api.example.com/v2")
+            )]
+        );
+        let commands = commands(spec);
+        let (label, curl, _) = &commands[0];
+        assert_eq!(label, "POST /users");
+        assert_eq!(curl.get_request_body().as_deref(), Some("name=Jane%20Doe"));
+        let (label, curl, _) = &commands[1];
+        assert_eq!(label, "updateUser");
+        assert_eq!(curl.get_url(), "{{baseUrl}}/users?dryRun=false");
+        assert_eq!(
+            curl.get_request_body().as_deref(),
+            Some("{\n  \"email\": \"user@example.com\"\n}")
+        );
+    }
+}
@@ -1,190 +1,753 @@
-use super::db::SavedCommand;
-use crate::request::curl::{Curl, Method};
+use super::db::{ImportedCollection, SavedCommand};
+use super::import::{
+    add_content_type, append_query, form_urlencoded, replace_path_variable, saved_command,
+    value_text, Folder, Import,
+};
+use crate::request::curl::{bearer_token, AuthKind, Curl, Method};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{json, Value};
 use std::{collections::HashMap, str::FromStr};
 
+// Noted for the collection, folders and requests that have scripts
+const SCRIPTS_NOT_RUN: &str = "pre-request and test scripts are not run";
+
+pub const POSTMAN_SCHEMA: &str =
+    "https://schema.getpostman.com/json/collection/v2.1.0/collection.json";
+
 #[derive(Serialize, Debug, Deserialize)]
 pub struct PostmanCollection {
     pub info: Info,
     pub item: Vec<HashMap<String, Value>>,
+    // collection variables, as {"key": .., "value": ..}
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variable: Vec<HashMap<String, Value>>,
+    // auth of every request that doesn't set its own
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<Value>,
+    // pre-request and test scripts
+    #[serde(default, skip_serializing)]
+    pub event: Vec<Value>,
+}
+
+impl PostmanCollection {
+    // A Postman v2.1 collection of saved commands, e.g. to hand a collection to someone using Postman
+    pub fn from_commands(
+        name: &str,
+        description: &str,
+        commands: &[SavedCommand],
+        variables: &[(String, String)],
+    ) -> Self {
+        PostmanCollection {
+            info: Info {
+                name: name.to_string(),
+                schema: POSTMAN_SCHEMA.to_string(),
+                description: Value::from(description),
+            },
+            item: commands.iter().map(postman_item).collect(),
+            variable: variables
+                .iter()
+                .map(|(key, value)| {
+                    HashMap::from([
+                        (String::from("key"), Value::from(key.as_str())),
+                        (String::from("value"), Value::from(value.as_str())),
+                    ])
+                })
+                .collect(),
+            auth: None,
+            event: Vec::new(),
+        }
+    }
+
+    pub fn write(&self, path: &str) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, json).map_err(|e| e.to_string())
+    }
+}
+
+fn key_value(key: &str, value: &str) -> Value {
+    json!({"key": key, "value": value, "type": "text"})
+}
+
+fn postman_item(cmd: &SavedCommand) -> HashMap<String, Value> {
+    let curl: Curl = serde_json::from_str(cmd.get_curl_json()).unwrap_or_default();
+    let mut headers: Vec<Value> = curl
+        .headers
+        .iter()
+        .flatten()
+        .filter_map(|header| header.split_once(':'))
+        .map(|(name, value)| key_value(name.trim(), value.trim()))
+        .collect();
+    // Postman has no cookie list of its own, they are sent as a header
+    let cookies = curl
+        .get_cookies()
+        .iter()
+        .filter(|cookie| !cookie.is_empty())
+        .map(|cookie| match cookie.split_once('=') {
+            Some(_) => cookie.clone(),
+            None => cookie.replacen(": ", "=", 1),
+        })
+        .collect::<Vec<String>>();
+    if !cookies.is_empty() {
+        headers.push(key_value("Cookie", &cookies.join("; ")));
+    }
+    let content_type = curl
+        .headers
+        .iter()
+        .flatten()
+        .filter_map(|header| header.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-type"))
+        .map(|(_, value)| value.trim().to_lowercase())
+        .unwrap_or_default();
+    let mut request = json!({
+        "method": curl.get_method().to_string(),
+        "header": headers,
+        "url": postman_url(curl.get_url()),
+    });
+    if let Some(body) = curl.get_request_body().filter(|body| !body.is_empty()) {
+        request["body"] = postman_body(&body, &content_type);
+    }
+    if let Some(auth) = postman_auth(curl.get_auth()) {
+        request["auth"] = auth;
+    }
+    if let Some(ref description) = cmd.description {
+        request["description"] = Value::from(description.as_str());
+    }
+    HashMap::from([
+        (
+            String::from("name"),
+            Value::from(cmd.label.clone().unwrap_or(curl.get_url().to_string())),
+        ),
+        (String::from("request"), request),
+        (String::from("response"), json!([])),
+    ])
+}
+
+// Postman keeps the URL split into its parts as well as the raw string
+fn postman_url(raw: &str) -> Value {
+    let mut url = json!({"raw": raw});
+    let (protocol, rest) = match raw.split_once("://") {
+        Some((protocol, rest)) => (Some(protocol), rest),
+        None => (None, raw),
+    };
+    let rest = rest.split_once('#').map_or(rest, |(rest, _)| rest);
+    let (rest, query) = match rest.split_once('?') {
+        Some((rest, query)) => (rest, Some(query)),
+        None => (rest, None),
+    };
+    let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) if !port.is_empty() && port.chars().all(|c| c.is_ascii_digit()) => {
+            (host, Some(port))
+        }
+        _ => (authority, None),
+    };
+    if let Some(protocol) = protocol {
+        url["protocol"] = Value::from(protocol);
+    }
+    // a variable such as {{baseUrl}} stays in one piece
+    url["host"] = match host.starts_with("{{") {
+        true => json!([host]),
+        false => json!(host.split('.').collect::<Vec<&str>>()),
+    };
+    if let Some(port) = port {
+        url["port"] = Value::from(port);
+    }
+    if !path.is_empty() {
+        url["path"] = json!(path.split('/').collect::<Vec<&str>>());
+    }
+    if let Some(query) = query {
+        url["query"] = query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                json!({"key": key, "value": value})
+            })
+            .collect();
+    }
+    url
+}
+
+// Form bodies are exported as key/value pairs, anything else as raw text
+fn postman_body(body: &str, content_type: &str) -> Value {
+    if content_type.contains("x-www-form-urlencoded") {
+        let pairs: Vec<Value> = body
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                key_value(key, value)
+            })
+            .collect();
+        return json!({"mode": "urlencoded", "urlencoded": pairs});
+    }
+    let language = match content_type {
+        ct if ct.contains("json") => "json",
+        ct if ct.contains("xml") => "xml",
+        ct if ct.contains("html") => "html",
+        ct if ct.contains("javascript") => "javascript",
+        _ if serde_json::from_str::<Value>(body).is_ok_and(|v| v.is_object() || v.is_array()) => {
+            "json"
+        }
+        _ => "text",
+    };
+    json!({"mode": "raw", "raw": body, "options": {"raw": {"language": language}}})
+}
+
+fn postman_auth(auth: &AuthKind) -> Option<Value> {
+    let login = |kind: &str, login: &str| {
+        let (username, password) = login.split_once(':').unwrap_or((login, ""));
+        json!({"type": kind, kind: [
+            {"key": "username", "value": username, "type": "string"},
+            {"key": "password", "value": password, "type": "string"},
+        ]})
+    };
+    match auth {
+        AuthKind::Basic(info) => Some(login("basic", info)),
+        AuthKind::Digest(info) => Some(login("digest", info)),
+        AuthKind::Bearer(token) => Some(json!({"type": "bearer", "bearer": [
+            {"key": "token", "value": bearer_token(token), "type": "string"},
+        ]})),
+        AuthKind::Ntlm => Some(json!({"type": "ntlm"})),
+        AuthKind::AwsSigv4 => Some(json!({"type": "awsv4"})),
+        AuthKind::Spnego | AuthKind::None => None,
+    }
+}
+
+impl PostmanCollection {
+    // The requests at the top of the collection, then a collection for each folder
+    // ("Collection / Folder"), with a note on everything that couldn't be imported
+    pub fn import(&self) -> (Vec<ImportedCollection>, Vec<String>) {
+        let mut import = Import::default();
+        if !self.event.is_empty() {
+            import.skip(&self.info.name, SCRIPTS_NOT_RUN);
+        }
+        let variables: Vec<(String, String)> = self
+            .variable
+            .iter()
+            .filter(|v| {
+                !v.get("disabled")
+                    .and_then(Value::as_bool)
+                    .unwrap_or_default()
+            })
+            .filter_map(|v| {
+                let key = v.get("key")?.as_str()?.to_string();
+                Some((key, value_text(v.get("value").unwrap_or(&Value::Null))))
+            })
+            .collect();
+        let folder = Folder {
+            name: self.info.name.clone(),
+            description: description_text(&self.info.description),
+            inherited: self.auth.clone(),
+        };
+        let items: Vec<Value> = self
+            .item
+            .iter()
+            .map(|item| Value::Object(item.clone().into_iter().collect()))
+            .collect();
+        add_folder(&mut import, &folder, &items, &variables);
+        import.finish()
+    }
 }
 
 impl From<PostmanCollection> for Vec<SavedCommand> {
     fn from(collection: PostmanCollection) -> Vec<SavedCommand> {
-        let mut saved_commands = Vec::new();
-        collection.item.iter().for_each(|item| {
-            let mut curl_cmd = Curl::new_serializing();
-            let mut cmd_name: Option<String> = None;
-            let mut description: Option<String> = None;
-            if let Some(name) = item.get("name") {
-                if let Some(name) = name.as_str() {
-                    cmd_name = Some(name.to_string());
-                }
+        collection
+            .import()
+            .0
+            .into_iter()
+            .flat_map(|collection| collection.commands)
+            .collect()
+    }
+}
+
+// The folder's requests, then its sub folders. A folder's auth is
+// inherited by the requests and folders in it without auth of their own
+fn add_folder(
+    import: &mut Import,
+    folder: &Folder<Option<Value>>,
+    items: &[Value],
+    variables: &[(String, String)],
+) {
+    let commands: Vec<SavedCommand> = items
+        .iter()
+        .filter(|item| item.get("item").is_none())
+        .map(|item| read_request(import, item, folder))
+        .collect();
+    import.add_collection(folder, variables.to_vec(), commands);
+    for item in items.iter().filter(|item| item.get("item").is_some()) {
+        let sub_folder = folder.sub_folder(
+            item["name"].as_str().unwrap_or("Folder"),
+            description_text(&item["description"]),
+            item.get("auth").cloned().or(folder.inherited.clone()),
+        );
+        if has_scripts(item) {
+            import.skip(&sub_folder.name, SCRIPTS_NOT_RUN);
+        }
+        let items = item["item"].as_array().cloned().unwrap_or_default();
+        add_folder(import, &sub_folder, &items, variables);
+    }
+}
+
+fn has_scripts(item: &Value) -> bool {
+    item["event"].as_array().is_some_and(|e| !e.is_empty())
+}
+
+fn read_request(import: &mut Import, item: &Value, folder: &Folder<Option<Value>>) -> SavedCommand {
+    let mut curl = Curl::new_serializing();
+    let request = &item["request"];
+    let url = match request {
+        // a request can be just its URL
+        Value::String(url) => url.clone(),
+        request => postman_raw_url(&request["url"]),
+    };
+    let name = item["name"].as_str().unwrap_or(&url).to_string();
+    if has_scripts(item) {
+        import.skip(&name, SCRIPTS_NOT_RUN);
+    }
+    let method = request["method"].as_str().unwrap_or("GET").to_uppercase();
+    let method = Method::from_str(&method).unwrap_or_else(|_| {
+        import.skip(
+            &name,
+            &format!("{} is not supported, imported as GET", method),
+        );
+        Method::Get
+    });
+    let mut headers: Vec<(String, String)> = enabled_pairs(&request["header"]);
+    let mut query: Vec<(String, String)> = Vec::new();
+    let auth = match request.get("auth") {
+        Some(auth) if auth["type"] != "inherit" => Some(auth),
+        _ => folder.inherited.as_ref(),
+    };
+    if let Some(auth) = auth {
+        match postman_auth_kind(auth) {
+            Ok(Some(kind)) => curl.set_auth(kind),
+            Ok(None) => {}
+            Err(AuthParam::Header(key, value)) => headers.push((key, value)),
+            Err(AuthParam::Query(key, value)) => query.push((key, value)),
+            Err(AuthParam::Unsupported(kind)) => {
+                import.skip(&name, &format!("{} auth is not supported", kind))
             }
-            if let Some(request) = item.get("request") {
-                if let Some(request) = request.as_str() {
-                    // this means its a get request
-                    curl_cmd.set_url(request);
-                    curl_cmd.set_get_method();
-                } else if let Some(request) = request.as_object() {
-                    if let Some(desc) = request.get("description") {
-                        if let Some(desc) = desc.as_str() {
-                            description = Some(desc.to_string());
-                        }
-                    }
-                    if let Some(url) = request.get("url") {
-                        if let Some(str_url) = url.as_str() {
-                            curl_cmd.set_url(str_url);
-                        } else if let Some(url) = url.as_object() {
-                            if let Some(raw) = url.get("raw") {
-                                if let Some(raw_str) = raw.as_str() {
-                                    curl_cmd.set_url(raw_str);
-                                }
-                            }
-                        }
-                    }
-                    if let Some(method) = request.get("method") {
-                        if let Some(method) = method.as_str() {
-                            curl_cmd.set_method(Method::from_str(method).unwrap_or_default());
-                        }
-                    }
-                    if let Some(headers) = request.get("header") {
-                        if let Some(headers) = headers.as_array() {
-                            headers.iter().for_each(|hdr| {
-                                if let Some(hdr) = hdr.as_object() {
-                                    if let Some(key) = hdr.get("key") {
-                                        if let Some(key) = key.as_str() {
-                                            if let Some(value) = hdr.get("value") {
-                                                if let Some(value) = value.as_str() {
-                                                    curl_cmd.add_headers(&format!(
-                                                        "{}: {}",
-                                                        key, value
-                                                    ));
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            });
-                        }
-                    }
-                    if let Some(body) = request.get("body") {
-                        if let Some(body) = body.as_object() {
-                            if let Some(mode) = body.get("mode") {
-                                if let Some(mode) = mode.as_str() {
-                                    match mode {
-                                        "formdata" => {
-                                            if let Some(data) = body.get("formdata") {
-                                                if let Some(data) = data.as_array() {
-                                                    let mut form_data = Vec::new();
-                                                    data.iter().for_each(|d| {
-                                                        if let Some(d) = d.as_object() {
-                                                            if let Some(key) = d.get("key") {
-                                                                if let Some(key) = key.as_str() {
-                                                                    if let Some(value) =
-                                                                        d.get("value")
-                                                                    {
-                                                                        if let Some(value) =
-                                                                            value.as_str()
-                                                                        {
-                                                                            form_data
-                                                                                .push((key, value));
-                                                                        }
-                                                                    }
-                                                                }
-                                                            }
-                                                        }
-                                                    });
-                                                    curl_cmd.set_request_body(
-                                                        &serde_json::to_string(&form_data)
-                                                            .unwrap_or_default(),
-                                                    );
-                                                }
-                                            }
-                                        }
-                                        "urlencoded" => {
-                                            if let Some(data) = body.get("urlencoded") {
-                                                if let Some(data) = data.as_array() {
-                                                    data.iter().for_each(|d| {
-                                                        if let Some(d) = d.as_object() {
-                                                            if let Some(key) = d.get("key") {
-                                                                if let Some(key) = key.as_str() {
-                                                                    if let Some(value) =
-                                                                        d.get("value")
-                                                                    {
-                                                                        if let Some(value) =
-                                                                            value.as_str()
-                                                                        {
-                                                                            curl_cmd.url_encode(
-                                                                                &format!(
-                                                                                    "{}={}",
-                                                                                    key, value
-                                                                                ),
-                                                                            );
-                                                                        }
-                                                                    }
-                                                                }
-                                                            }
-                                                        }
-                                                    });
-                                                }
-                                            }
-                                        }
-                                        "raw" => {
-                                            if let Some(data) = body.get("raw") {
-                                                if let Some(data) = data.as_str() {
-                                                    curl_cmd.set_request_body(data);
-                                                }
-                                            }
-                                        }
-                                        _ => {}
-                                    }
-                                }
-                            }
-                        }
+        }
+    }
+    let url = query
+        .iter()
+        .fold(url, |url, (key, value)| append_query(&url, key, value));
+    curl.set_url(&url);
+    curl.set_method(method);
+    let body = &request["body"];
+    let content_type = match body["mode"].as_str().unwrap_or_default() {
+        "raw" => {
+            if let Some(raw) = body["raw"].as_str().filter(|raw| !raw.is_empty()) {
+                curl.set_request_body(raw);
+            }
+            match body["options"]["raw"]["language"].as_str() {
+                Some("json") => Some("application/json"),
+                Some("xml") => Some("application/xml"),
+                _ => None,
+            }
+        }
+        "urlencoded" => {
+            curl.set_request_body(&form_urlencoded(&enabled_pairs(&body["urlencoded"])));
+            Some("application/x-www-form-urlencoded")
+        }
+        "formdata" => {
+            let fields = body["formdata"].as_array().cloned().unwrap_or_default();
+            if fields.iter().any(|field| field["type"] == "file") {
+                import.skip(&name, "file fields of the form are not imported");
+            }
+            let form_data: Vec<(String, String)> = enabled_pairs(&Value::Array(
+                fields.into_iter().filter(|f| f["type"] != "file").collect(),
+            ));
+            curl.set_request_body(&serde_json::to_string(&form_data).unwrap_or_default());
+            None
+        }
+        "graphql" => {
+            let graphql = json!({
+                "query": body["graphql"]["query"],
+                "variables": serde_json::from_str::<Value>(
+                    body["graphql"]["variables"].as_str().unwrap_or_default()
+                ).unwrap_or_else(|_| json!({})),
+            });
+            curl.set_request_body(&graphql.to_string());
+            Some("application/json")
+        }
+        "file" => {
+            import.skip(&name, "file bodies are not imported");
+            None
+        }
+        _ => None,
+    };
+    add_content_type(&mut headers, content_type);
+    headers
+        .iter()
+        .for_each(|(key, value)| curl.add_headers(&format!("{}: {}", key, value)));
+    enabled_pairs(&request["cookie"])
+        .iter()
+        .for_each(|(key, value)| curl.add_cookie(&format!("{}: {}", key, value)));
+    let description = Some(description_text(&request["description"]))
+        .filter(|d| !d.is_empty())
+        .or_else(|| Some(description_text(&item["description"])).filter(|d| !d.is_empty()));
+    saved_command(curl, name, description)
+}
+
+// The raw URL, or for url objects without one the URL put together from its parts
+fn postman_raw_url(url: &Value) -> String {
+    let mut raw = match url {
+        Value::String(url) => return url.clone(),
+        url => match url["raw"].as_str() {
+            Some(raw) => raw.to_string(),
+            None => {
+                let mut raw = String::new();
+                if let Some(protocol) = url["protocol"].as_str() {
+                    raw.push_str(&format!("{}://", protocol));
+                }
+                raw.push_str(&match &url["host"] {
+                    Value::Array(parts) => {
+                        parts.iter().map(value_text).collect::<Vec<_>>().join(".")
                     }
-                    if let Some(cookie) = request.get("cookie") {
-                        if let Some(cookie) = cookie.as_array() {
-                            cookie.iter().for_each(|ck| {
-                                if let Some(ck) = ck.as_object() {
-                                    if let Some(key) = ck.get("key") {
-                                        if let Some(key) = key.as_str() {
-                                            if let Some(value) = ck.get("value") {
-                                                if let Some(value) = value.as_str() {
-                                                    curl_cmd
-                                                        .add_cookie(&format!("{}: {}", key, value));
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            });
-                        }
+                    host => value_text(host),
+                });
+                if let Some(port) = url["port"].as_str() {
+                    raw.push_str(&format!(":{}", port));
+                }
+                match &url["path"] {
+                    Value::Array(parts) => parts
+                        .iter()
+                        .for_each(|part| raw.push_str(&format!("/{}", value_text(part)))),
+                    Value::String(path) => {
+                        raw.push_str(&format!("/{}", path.trim_start_matches('/')))
                     }
+                    _ => {}
                 }
+                let query = enabled_pairs(&url["query"])
+                    .iter()
+                    .map(|(key, value)| format!("{}={}", key, value))
+                    .collect::<Vec<String>>();
+                if !query.is_empty() {
+                    raw.push_str(&format!("?{}", query.join("&")));
+                }
+                raw
             }
-            if cmd_name.is_none() {
-                cmd_name = Some(String::from(curl_cmd.get_url()));
-            }
-            let cmd = curl_cmd.get_command_string();
-            let curl_json: String = serde_json::to_string(&curl_cmd).unwrap_or_default();
-            saved_commands.push(SavedCommand::new(
-                &cmd,
-                cmd_name,
-                description,
-                &curl_json,
-                None,
-            ));
-        });
+        },
+    };
+    // path variables, e.g. /users/:id
+    for (key, value) in enabled_pairs(&url["variable"]) {
+        if !value.is_empty() {
+            raw = replace_path_variable(&raw, &key, &value);
+        }
+    }
+    raw
+}
 
-        saved_commands
+// The enabled {"key": .., "value": ..} pairs of headers, query parameters and forms
+fn enabled_pairs(pairs: &Value) -> Vec<(String, String)> {
+    pairs
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|pair| !pair["disabled"].as_bool().unwrap_or_default())
+        .filter_map(|pair| {
+            let key = pair["key"].as_str()?;
+            Some((key.to_string(), value_text(&pair["value"])))
+        })
+        .collect()
+}
+
+// Descriptions are a string, or an object with the text in 'content'
+fn description_text(description: &Value) -> String {
+    match description {
+        Value::Object(description) => description
+            .get("content")
+            .map(value_text)
+            .unwrap_or_default(),
+        description => value_text(description),
+    }
+}
+
+// Auth that has no AuthKind of its own
+enum AuthParam {
+    Header(String, String),
+    Query(String, String),
+    Unsupported(String),
+}
+
+// A parameter of an auth block, a list of {"key", "value"} in v2.1 or an object in v2.0
+fn auth_param(auth: &Value, kind: &str, key: &str) -> String {
+    match &auth[kind] {
+        Value::Array(params) => params
+            .iter()
+            .find(|param| param["key"] == key)
+            .map(|param| value_text(&param["value"]))
+            .unwrap_or_default(),
+        params => value_text(&params[key]),
+    }
+}
+
+fn postman_auth_kind(auth: &Value) -> Result<Option<AuthKind>, AuthParam> {
+    let kind = auth["type"].as_str().unwrap_or("noauth");
+    let param = |key: &str| auth_param(auth, kind, key);
+    let login = || format!("{}:{}", param("username"), param("password"));
+    match kind {
+        "noauth" => Ok(None),
+        "basic" => Ok(Some(AuthKind::Basic(login()))),
+        "digest" => Ok(Some(AuthKind::Digest(login()))),
+        "bearer" => Ok(Some(AuthKind::Bearer(param("token")))),
+        "ntlm" => Ok(Some(AuthKind::Ntlm)),
+        "apikey" if param("in") == "query" => Err(AuthParam::Query(param("key"), param("value"))),
+        "apikey" => Err(AuthParam::Header(param("key"), param("value"))),
+        kind => Err(AuthParam::Unsupported(kind.to_string())),
+    }
+}
+
+// A Postman environment export (`*.postman_environment.json`)
+#[derive(Serialize, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct PostmanEnvironment {
+    pub name: String,
+    pub values: Vec<EnvironmentValue>,
+    #[serde(rename = "_postman_variable_scope")]
+    pub scope: String,
+}
+
+#[derive(Serialize, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct EnvironmentValue {
+    pub key: String,
+    pub value: Value,
+    // "default" or "secret"
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub enabled: Option<bool>,
+}
+
+impl PostmanEnvironment {
+    // Environments have a list of values instead of items
+    pub fn is_environment_json(text: &str) -> bool {
+        serde_json::from_str::<Value>(text).is_ok_and(|value| {
+            value["_postman_variable_scope"] == "environment"
+                || (value["values"].is_array()
+                    && value["name"].is_string()
+                    && value.get("item").is_none())
+        })
+    }
+
+    // The enabled variables as (name, value, is_secret)
+    pub fn variables(&self) -> Vec<(String, String, bool)> {
+        self.values
+            .iter()
+            .filter(|value| value.enabled.unwrap_or(true) && !value.key.is_empty())
+            .map(|value| {
+                (
+                    value.key.clone(),
+                    value_text(&value.value),
+                    value.kind == "secret",
+                )
+            })
+            .collect()
     }
 }
 
 #[derive(Serialize, Debug, Deserialize)]
 pub struct Info {
     pub name: String,
+    #[serde(default)]
     schema: String,
-    pub description: String,
+    // a string, or {"content": .., "type": "text/markdown"}
+    #[serde(default)]
+    pub description: Value,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request::curl::AuthKind;
+
+    #[test]
+    fn test_postman_environment() {
+        let text = r#"{"id": "1", "name": "Staging", "_postman_variable_scope": "environment",
+            "values": [
+                {"key": "baseUrl", "value": "https://staging.example.com", "type": "default", "enabled": true},
+                {"key": "token", "value": "s3cret", "type": "secret", "enabled": true},
+                {"key": "retries", "value": 3},
+                {"key": "old", "value": "x", "enabled": false}
+            ]}"#;
+        assert!(PostmanEnvironment::is_environment_json(text));
+        assert!(!PostmanEnvironment::is_environment_json(
+            r#"{"info": {"name": "x"}, "item": []}"#
+        ));
+        let env: PostmanEnvironment = serde_json::from_str(text).unwrap();
+        assert_eq!(env.name, "Staging");
+        assert_eq!(
+            env.variables(),
+            vec![
+                (
+                    String::from("baseUrl"),
+                    String::from("https://staging.example.com"),
+                    false
+                ),
+                (String::from("token"), String::from("s3cret"), true),
+                (String::from("retries"), String::from("3"), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_import_postman_folders() {
+        let collection = r#"{
+            "info": {"name": "Shop", "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json",
+                "description": {"content": "The shop API", "type": "text/markdown"}},
+            "variable": [{"key": "baseUrl", "value": "https://shop.example.com"}, {"key": "retries", "value": 3}],
+            "auth": {"type": "bearer", "bearer": [{"key": "token", "value": "{{token}}", "type": "string"}]},
+            "item": [
+                {"name": "Health", "request": {"method": "GET", "url": {"protocol": "https",
+                    "host": ["shop", "example", "com"], "port": "8443", "path": ["health"],
+                    "query": [{"key": "full", "value": "1"}, {"key": "debug", "value": "1", "disabled": true}]}}},
+                {"name": "Orders", "description": "Order endpoints", "item": [
+                    {"name": "Get order", "request": {"method": "GET",
+                        "url": {"raw": "{{baseUrl}}/orders/:id/items/:idx", "variable": [{"key": "id", "value": "42"}, {"key": "idx", "value": "7"}]},
+                        "header": [{"key": "X-Trace", "value": "on"}, {"key": "X-Off", "value": "1", "disabled": true}],
+                        "description": {"content": "Fetches an order"}}},
+                    {"name": "Admin", "auth": {"type": "basic", "basic": {"username": "admin", "password": "pw"}}, "item": [
+                        {"name": "Refund", "event": [{"listen": "test", "script": {"exec": ["pm.test()"]}}],
+                            "request": {"method": "POST", "url": "{{baseUrl}}/refunds",
+                            "auth": {"type": "apikey", "apikey": [{"key": "key", "value": "X-Api-Key"},
+                                {"key": "value", "value": "secret"}]},
+                            "body": {"mode": "graphql", "graphql": {"query": "{ refunds { id } }", "variables": ""}}}},
+                        {"name": "Audit", "request": {"method": "GET", "url": "{{baseUrl}}/audit",
+                            "auth": {"type": "oauth2", "oauth2": []}}},
+                        {"name": "Report", "request": {"method": "GET", "url": "{{baseUrl}}/report"}}
+                    ]}
+                ]}
+            ]
+        }"#;
+        let collection: PostmanCollection = serde_json::from_str(collection).unwrap();
+        let (collections, skipped) = collection.import();
+        let names: Vec<&str> = collections.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["Shop", "Shop / Orders", "Shop / Orders / Admin"]
+        );
+        assert_eq!(collections[0].description, "The shop API");
+        assert_eq!(
+            collections[2].variables,
+            vec![
+                (
+                    String::from("baseUrl"),
+                    String::from("https://shop.example.com")
+                ),
+                (String::from("retries"), String::from("3")),
+            ]
+        );
+        let curl = |collection: usize, command: usize| -> Curl {
+            serde_json::from_str(collections[collection].commands[command].get_curl_json()).unwrap()
+        };
+        let health = curl(0, 0);
+        assert_eq!(
+            health.get_url(),
+            "https://shop.example.com:8443/health?full=1"
+        );
+        assert_eq!(
+            health.get_auth(),
+            &AuthKind::Bearer(String::from("{{token}}"))
+        );
+        let order = curl(1, 0);
+        assert_eq!(order.get_url(), "{{baseUrl}}/orders/42/items/7");
+        assert_eq!(
+            order.get_request_headers(),
+            vec![
+                String::from("Authorization: Bearer {{token}}"),
+                String::from("X-Trace: on")
+            ]
+        );
+        assert_eq!(
+            collections[1].commands[0].description.as_deref(),
+            Some("Fetches an order")
+        );
+        let refund = curl(2, 0);
+        assert_eq!(refund.get_auth(), &AuthKind::None);
+        assert_eq!(
+            refund.get_request_headers(),
+            vec![
+                String::from("X-Api-Key: secret"),
+                String::from("Content-Type: application/json")
+            ]
+        );
+        assert_eq!(
+            refund.get_request_body().as_deref(),
+            Some(r#"{"query":"{ refunds { id } }","variables":{}}"#)
+        );
+        // the folder's auth is inherited
+        assert_eq!(
+            curl(2, 2).get_auth(),
+            &AuthKind::Basic(String::from("admin:pw"))
+        );
+        assert_eq!(
+            skipped,
+            vec![
+                String::from("Refund: pre-request and test scripts are not run"),
+                String::from("Audit: oauth2 auth is not supported"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_export_postman_collection() {
+        let mut curl = Curl::new_serializing();
+        curl.set_url("{{baseUrl}}:8080/users/7?verbose=1&q=a");
+        curl.set_method(Method::Post);
+        curl.add_headers("Content-Type: application/x-www-form-urlencoded");
+        curl.set_request_body("name=jane&age=30");
+        curl.add_cookie("session=abc");
+        curl.set_auth(AuthKind::Bearer(String::from("t0ken")));
+        let json = serde_json::to_string(&curl).unwrap();
+        let cmd = SavedCommand::new(
+            "curl",
+            Some(String::from("Update user")),
+            Some(String::from("Updates a user")),
+            &json,
+            None,
+        );
+        let variables = vec![(String::from("baseUrl"), String::from("http://localhost"))];
+        let collection = PostmanCollection::from_commands("Users", "", &[cmd], &variables);
+        let exported = serde_json::to_value(&collection).unwrap();
+        assert_eq!(exported["info"]["schema"], POSTMAN_SCHEMA);
+        assert_eq!(
+            exported["variable"],
+            json!([{"key": "baseUrl", "value": "http://localhost"}])
+        );
+        let item = &exported["item"][0];
+        assert_eq!(item["name"], "Update user");
+        let request = &item["request"];
+        assert_eq!(request["description"], "Updates a user");
+        assert_eq!(
+            request["url"],
+            json!({"raw": "{{baseUrl}}:8080/users/7?verbose=1&q=a", "host": ["{{baseUrl}}"],
+                "port": "8080", "path": ["users", "7"],
+                "query": [{"key": "verbose", "value": "1"}, {"key": "q", "value": "a"}]})
+        );
+        assert_eq!(request["body"]["mode"], "urlencoded");
+        assert_eq!(request["body"]["urlencoded"][1]["value"], "30");
+        assert_eq!(request["auth"]["bearer"][0]["value"], "t0ken");
+        assert_eq!(
+            request["header"][1],
+            json!({"key": "Cookie", "value": "session=abc", "type": "text"})
+        );
+        assert_eq!(
+            postman_url("https://api.example.com/v1#top")["host"],
+            json!(["api", "example", "com"])
+        );
+        assert_eq!(
+            postman_body("{\"a\": 1}", "")["options"]["raw"]["language"],
+            "json"
+        );
+
+        // and back again
+        let imported: PostmanCollection =
+            serde_json::from_str(&serde_json::to_string(&collection).unwrap()).unwrap();
+        let commands: Vec<SavedCommand> = imported.into();
+        let curl: Curl = serde_json::from_str(commands[0].get_curl_json()).unwrap();
+        assert_eq!(curl.get_method().to_string(), "POST");
+        assert_eq!(curl.get_url(), "{{baseUrl}}:8080/users/7?verbose=1&q=a");
+    }
 }
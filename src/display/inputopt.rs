@@ -1,5 +1,9 @@
 use crate::{
-    request::curl::{AuthKind, Method},
+    request::{
+        codegen::Language,
+        curl::{AuthKind, Method},
+        report::ReportFormat,
+    },
     screens::Screen,
 };
 use std::fmt::Display;
@@ -37,6 +41,30 @@ pub enum InputOpt {
     RequestError(String),
     AlertMessage(String),
     Method(Method),
+    RunReport(ReportFormat),
+    // benchmark a saved command (or the current command if None)
+    Benchmark(Option<i32>),
+    HistoryFilter,
+    // jq style filter over the response body
+    BodyFilter,
+    // write what the body viewer shows to a file
+    SaveBody,
+    // find text in the body viewer
+    BodySearch,
+    // decode the response with another character encoding
+    Charset,
+    // write a collection, or every saved command, to a Postman collection or HAR file
+    ExportCollection(Option<i32>),
+    // write the history entries matching the filter to a HAR file
+    ExportHistory(Option<String>),
+    // name of a new collection, the saved commands are moved (or copied if true) into it
+    NewCollection(Vec<i32>, bool),
+    // write a code snippet of a saved command (or the current command if None)
+    CodeFile(Option<i32>, Language),
+    // path of a saved example response to compare the history entry with
+    DiffExample(i32),
+    // the environments to run a saved command against and compare
+    DiffEnvironments(i32),
 }
 
 impl InputOpt {
@@ -69,16 +97,46 @@ impl InputOpt {
             InputOpt::AlertMessage(_) => Screen::RequestMenu(None),
             InputOpt::ImportCollection => Screen::SavedCollections(None),
             InputOpt::RenameCollection(_) => Screen::SavedCollections(None),
-            InputOpt::Execute => Screen::Response(String::new()),
+            InputOpt::Execute => Screen::Response(None),
+            InputOpt::Charset => Screen::Response(None),
+            InputOpt::ExportCollection(Some(_)) => Screen::SavedCollections(None),
+            InputOpt::ExportCollection(None) => Screen::SavedCommands {
+                id: None,
+                opt: None,
+            },
+            InputOpt::ExportHistory(filter) => Screen::History {
+                filter: filter.clone(),
+                opt: None,
+            },
+            InputOpt::NewCollection(ids, _) if ids.is_empty() => Screen::SavedCollections(None),
+            InputOpt::NewCollection(..) => Screen::SavedCommands {
+                id: None,
+                opt: None,
+            },
+            InputOpt::CodeFile(id, _) => Screen::CodeGen { id: *id, opt: None },
+            InputOpt::DiffExample(_) => Screen::History {
+                filter: None,
+                opt: None,
+            },
+            InputOpt::DiffEnvironments(id) => Screen::CmdMenu { id: *id, opt: None },
             InputOpt::CollectionDescription(_) => Screen::SavedCollections(None),
             InputOpt::URL => Screen::RequestMenu(None),
             InputOpt::UploadFile => Screen::RequestMenu(None),
             InputOpt::Headers => Screen::Headers,
-            InputOpt::Output => Screen::Response(String::new()),
+            InputOpt::Output => Screen::Response(None),
             InputOpt::Verbose => Screen::RequestMenu(None),
             InputOpt::RequestBody => Screen::RequestMenu(None),
             InputOpt::Auth(_) => Screen::RequestMenu(None),
             InputOpt::CookieValue(_) => Screen::RequestMenu(None),
+            InputOpt::RunReport(_) => Screen::RunResults(None),
+            InputOpt::Benchmark(_) => Screen::Benchmark,
+            InputOpt::HistoryFilter => Screen::History {
+                filter: None,
+                opt: None,
+            },
+            InputOpt::BodyFilter | InputOpt::SaveBody | InputOpt::BodySearch => {
+                Screen::ViewBody(None)
+            }
         }
     }
     pub fn is_error(&self) -> bool {
@@ -120,6 +178,44 @@ impl Display for InputOpt {
             InputOpt::CmdLabel(_) => write!(f, "| Command Label"),
             InputOpt::CmdDescription(_) => write!(f, "| Command Description"),
             InputOpt::CollectionDescription(_) => write!(f, "| Collection Description"),
+            InputOpt::RunReport(format) => write!(f, "| {} Report Path", format),
+            InputOpt::HistoryFilter => write!(f, "| Filter by method, URL or status"),
+            InputOpt::BodyFilter => write!(f, "| Filter e.g. .data[].id or .[] | select(.ok)"),
+            InputOpt::SaveBody => write!(f, "| Save to file"),
+            InputOpt::BodySearch => write!(f, "| Search, wrap in /slashes/ for a regex"),
+            InputOpt::ExportCollection(_) => {
+                write!(
+                    f,
+                    "| Export to a Postman collection (.json), HAR (.har) or .http file"
+                )
+            }
+            InputOpt::ExportHistory(_) => write!(f, "| Export to HAR file"),
+            InputOpt::NewCollection(..) => write!(f, "| New Collection Name"),
+            InputOpt::DiffExample(_) => {
+                write!(
+                    f,
+                    "| Path of the example response (body, or saved with curl -i)"
+                )
+            }
+            InputOpt::DiffEnvironments(_) => {
+                write!(f, "| Two environments to compare e.g. staging, prod")
+            }
+            InputOpt::CodeFile(_, language) => write!(
+                f,
+                "| Write {} code to a file (e.g. request.{}), empty to copy it to the clipboard",
+                language,
+                language.extension()
+            ),
+            InputOpt::Charset => write!(
+                f,
+                "| Character encoding e.g. windows-1252 or shift_jis, empty to detect it"
+            ),
+            InputOpt::Benchmark(_) => {
+                write!(
+                    f,
+                    "| Benchmark: <count|duration> [concurrency] e.g. 500 20 or 30s 20"
+                )
+            }
         }
     }
 }
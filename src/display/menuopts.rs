@@ -2,7 +2,11 @@
 * String literals for Menus/Options
  */
 pub const SAVED_COMMANDS_PARAGRAPH: &str =
-    "\nPress q to exit\nPress Enter to Send Request\nPress 'ESC' or 'h' to go back\n";
+    "\nPress q to exit\nPress Enter for Menu, Space to mark and 'm' to move to a collection\nPress 'e' to export (the marked) to Postman, HAR or .http\nPress 'ESC' or 'h' to go back\n";
+pub const ENVIRONMENTS_PARAGRAPH: &str =
+    "\nPress q to exit\nPress Enter to select or unselect\nPress 'd' to delete\nPress 'ESC' or 'h' to go back\n";
+pub const HISTORY_PARAGRAPH: &str =
+    "\nPress q to exit\nPress Enter for Menu\nPress '/' to filter, 'e' to export to HAR\nPress 'ESC' or 'h' to go back\n";
 pub const CURL: &str = "curl";
 pub const WGET: &str = "wget";
 pub const CUSTOM: &str = "custom";
@@ -23,10 +27,27 @@ pub const SAVED_COMMANDS_TITLE: &str = "My Saved cURL Commands";
 pub const DEFAULT_MENU_TITLE: &str = "** CuTE **";
 pub const AUTH_MENU_TITLE: &str = "** CuTE ** Authentication Menu 󰌋";
 pub const VIEW_BODY_TITLE: &str = "** CuTE ** View Response Body";
+pub const VIEW_HEADERS_TITLE: &str = "** CuTE ** View Response Headers";
+pub const VIEW_BODY_PARAGRAPH: &str =
+    "\nPress q to exit\nPress 'j'/'k' to scroll, 'z'/'Enter' to fold, 'Z' to fold all\nPress '/' to search, 'n'/'N' for the next/previous match, 'f' to filter\nPress 'y'/'c' to copy the block/view, 's' to save\nPress 'r' for the raw body, 't' to change the format\nPress 'ESC' or 'h' to go back\n";
 pub const INPUT_MENU_TITLE: &str = "** Press i to enter Insert mode **";
 pub const ERROR_MENU_TITLE: &str = "* CuTE ** Error! *";
 pub const SUCCESS_MENU_TITLE: &str = "* CuTE ** Success! *";
 pub const POSTMAN_COLLECTION_TITLE: &str = "* CuTE ** Postman Collections";
+pub const RUN_RESULTS_TITLE: &str = "* CuTE ** Collection Run Results";
+pub const HISTORY_TITLE: &str = "* CuTE ** Request History";
+pub const ENVIRONMENTS_TITLE: &str = "* CuTE ** Environments";
+pub const MOVE_COMMANDS_TITLE: &str = "* CuTE ** Pick a Collection";
+pub const NEW_COLLECTION: &str = "Create a new collection 󰉗 ";
+pub const CODEGEN_TITLE: &str = "* CuTE ** Generate Code";
+pub const CODEGEN_PARAGRAPH: &str =
+    "\nPress q to exit\nPress Enter to copy or save the snippet\nPress 'ESC' or 'h' to go back\n";
+pub const DIFF_TITLE: &str = "* CuTE ** Response Diff";
+pub const DIFF_PARAGRAPH: &str =
+    "\nPress q to exit\nPress 'j'/'k' to scroll\nPress 'ESC' or 'h' to go back\n";
+pub const BENCHMARK_TITLE: &str = "* CuTE ** Benchmark Results";
+pub const BENCHMARK_PARAGRAPH: &str =
+    "\nPress q to exit\nPress 'c' to cancel the running benchmark\nPress 'ESC' or 'h' to go back\n";
 pub const SUCCESS_MESSAGE: &str = "Request saved successfully";
 // This padds the choices in the menu. This is the least hideous way to do this.(I think)
 pub const OPTION_PADDING_MAX: &str = "\n\n\n\n";
@@ -99,12 +120,29 @@ pub const SAVE_AUTH_ERROR: &str =
 pub const VALID_COMMAND_ERROR: &str =
     "Error: Invalid command.\n You must add either a URL or Unix Socket to execute a command";
 
-pub const CMD_MENU_OPTIONS: [&str; 6] = [
+pub const CMD_MENU_OPTIONS: [&str; 13] = [
     "Execute   ",
     "Add a label 󰈮 ",
     "Add a description 󰈮 ",
     "Delete   ",
     "Copy CLI command to clipboard  󰅎 ",
+    "Copy wget command to clipboard  󰅎 ",
+    "Benchmark 󰓅 ",
+    "Generate code  ",
+    "Move to a collection 󰉒 ",
+    "Copy to a collection 󰆏 ",
+    "Remove from its collection 󰆴 ",
+    "Compare runs across environments  ",
+    "Cancel   ",
+];
+pub const HISTORY_MENU_OPTIONS: [&str; 8] = [
+    "Replay request  ",
+    "Compare with another response  ",
+    "Compare with a saved example  ",
+    "Promote to saved command 󰆓 ",
+    "Copy CLI command to clipboard 󰅎 ",
+    "Copy wget command to clipboard 󰅎 ",
+    "Delete   ",
     "Cancel   ",
 ];
 pub const KEY_MENU_OPTIONS: [&str; 4] = [
@@ -113,24 +151,30 @@ pub const KEY_MENU_OPTIONS: [&str; 4] = [
     "Copy to Clipboard  󰅎 ",
     "Cancel   ",
 ];
-pub const COLLECTION_MENU_OPTIONS: [&str; 3] = [
-    "Import New Postman Collection 󰖟 ",
+pub const COLLECTION_MENU_OPTIONS: [&str; 5] = [
+    "Import a Postman, Insomnia, Bruno, HAR, OpenAPI or .http file 󰖟 ",
     "View Collections 󱂛 ",
+    "Environments 󰒓 ",
+    "Create an empty collection 󰉗 ",
     "Cancel   ",
 ];
+pub const ENVIRONMENT_MENU_OPTIONS: [&str; 2] = ["Delete this environment", "Cancel"];
 pub const ALERT_MENU_OPTIONS_KEY: [&str; 3] =
     ["Delete", "Copy Curl command to Clipboard", "Cancel"];
-pub const MAIN_MENU_OPTIONS: [&str; 4] = [
+pub const MAIN_MENU_OPTIONS: [&str; 5] = [
     "Build and send an HTTP request 󰖟 ",
     "View saved requests  ",
     "View or Import Postman Collections",
     "View Saved API keys 󱂛  ",
+    "View request history 󰋚 ",
 ];
-pub const COLLECTION_ALERT_MENU_OPTS: [&str; 5] = [
+pub const COLLECTION_ALERT_MENU_OPTS: [&str; 7] = [
     "View Requests in this collection",
+    "Run all requests in this collection",
     "Add a description",
     "Rename this collection",
     "Delete this collection",
+    "Export this collection to Postman, HAR or .http",
     "Cancel",
 ];
 pub const REQUEST_MENU_OPTIONS: [&str; 12] = [
@@ -197,10 +241,21 @@ pub const MORE_FLAGS_MENU: [&str; 11] = [
     "Specify User-Agent  󰖟 ",
     "Enable TCP keepalive 󰗶 ",
 ];
-pub const RESPONSE_MENU_OPTIONS: [&str; 5] = [
+pub const RUN_RESULTS_MENU_OPTIONS: [&str; 3] = [
+    "Write JUnit XML report 󱇧 ",
+    "Write JSON report 󱇧 ",
+    "Return to collections 󰁍 ",
+];
+pub const RESPONSE_MENU_OPTIONS: [&str; 10] = [
     "Write to file? 󱇧 ",
     "View response headers 󰰀 ",
     "View response body 󰈮 ",
     "Copy CLI command to clipboard 󰅎 ",
+    "Copy wget command to clipboard 󰅎 ",
+    "Benchmark this request 󰓅 ",
+    "Compare with a previous response  ",
+    "Change character encoding 󰪷 ",
+    "Generate code  ",
     "Return to main menu  ",
 ];
+pub const BENCHMARK_MENU_OPTIONS: [&str; 2] = ["Back 󰁍 ", "Return to main menu  "];
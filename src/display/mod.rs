@@ -9,7 +9,8 @@ use self::menuopts::{
     DISPLAY_OPT_TOKEN_SAVED, DISPLAY_OPT_UNIX_SOCKET, DISPLAY_OPT_UNRESTRICTED_AUTH,
     DISPLAY_OPT_UPLOAD, DISPLAY_OPT_URL, DISPLAY_OPT_USERAGENT, DISPLAY_OPT_VERBOSE,
 };
-use crate::request::curl::AuthKind;
+use crate::request::curl::{bearer_header, cookie_pair, AuthKind};
+use crate::request::shell::Shell;
 use std::fmt::{Display, Formatter};
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -43,6 +44,9 @@ pub mod inputopt;
 // Menu Options
 pub mod menuopts;
 
+// Response Body Viewer
+pub mod viewer;
+
 /// Here are the options that require us to display a box letting
 /// the user know that they have selected that option.
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -78,51 +82,111 @@ pub enum AppOptions {
 }
 
 impl AppOptions {
-    pub fn get_curl_flag_value(&self) -> String {
+    // The curl arguments for the option, a flag and its value are separate arguments
+    pub fn get_curl_args(&self) -> Vec<String> {
         match self {
-            Self::Verbose => "-v".to_string(),
-            Self::Headers(ref str) => format!("-H {str}"),
-            Self::UploadFile(ref file) => format!("-T {file}"),
-            Self::Outfile(ref file) => format!("-o {file}"),
-            Self::NewCookie(ref cookie) => format!("--cookie {cookie}"),
-            Self::CookieJar(ref jar) => format!("--cookie-jar {jar}"),
-            Self::CookiePath(ref path) => format!("--cookie {path}"),
-            Self::Referrer(ref referrer) => format!("-e {referrer}"),
-            Self::CaPath(ref path) => format!("--cacert {path}"),
-            Self::MaxRedirects(ref size) => format!("--max-redirs {size}"),
-            Self::UserAgent(ref ua) => format!("-A {ua}"),
-            Self::RequestBody(ref body) => format!("-d {body}"),
-            Self::NewCookieSession => "--junk-session-cookies".to_string(),
-            Self::ProxyTunnel => "--proxy-tunnel".to_string(),
-            Self::CertInfo => "--certinfo".to_string(),
-            Self::FollowRedirects => "-L".to_string(),
-            Self::UnixSocket(ref socket) => format!("--unix-socket {socket}"),
-            Self::MatchWildcard => "-g".to_string(),
+            Self::Verbose => vec!["-v".to_string()],
+            Self::Headers(ref str) => vec!["-H".to_string(), str.clone()],
+            Self::UploadFile(ref file) => vec!["-T".to_string(), file.clone()],
+            Self::Outfile(ref file) => vec!["-o".to_string(), file.clone()],
+            Self::NewCookie(ref cookie) => vec!["--cookie".to_string(), cookie.clone()],
+            Self::CookieJar(ref jar) => vec!["--cookie-jar".to_string(), jar.clone()],
+            Self::CookiePath(ref path) => vec!["--cookie".to_string(), path.clone()],
+            Self::Referrer(ref referrer) => vec!["-e".to_string(), referrer.clone()],
+            Self::CaPath(ref path) => vec!["--cacert".to_string(), path.clone()],
+            Self::MaxRedirects(ref size) => vec!["--max-redirs".to_string(), size.to_string()],
+            Self::UserAgent(ref ua) => vec!["-A".to_string(), ua.clone()],
+            Self::RequestBody(ref body) => vec!["-d".to_string(), body.clone()],
+            Self::NewCookieSession => vec!["--junk-session-cookies".to_string()],
+            Self::ProxyTunnel => vec!["--proxy-tunnel".to_string()],
+            Self::CertInfo => vec!["--certinfo".to_string()],
+            Self::FollowRedirects => vec!["-L".to_string()],
+            Self::UnixSocket(ref socket) => vec!["--unix-socket".to_string(), socket.clone()],
+            Self::MatchWildcard => vec!["-g".to_string()],
             Self::Auth(ref kind) => match kind {
-                AuthKind::Basic(ref login) => {
-                    format!("-u {login}")
-                }
+                AuthKind::Basic(ref login) => vec!["-u".to_string(), login.clone()],
                 AuthKind::Digest(ref login) => {
-                    format!("--digest -u {login}")
+                    vec!["--digest".to_string(), "-u".to_string(), login.clone()]
+                }
+                AuthKind::Ntlm => vec!["--ntlm".to_string()],
+                AuthKind::Bearer(ref token) => {
+                    vec!["-H".to_string(), bearer_header(token)]
                 }
-                AuthKind::Ntlm => "--ntlm".to_string(),
-                AuthKind::Bearer(ref token) => format!("-H 'Authorization: Bearer {token}'"),
-                AuthKind::AwsSigv4 => "--aws-sigv4".to_string(),
-                AuthKind::Spnego => "--spnego".to_string(),
-                AuthKind::None => "".to_string(),
+                AuthKind::AwsSigv4 => vec!["--aws-sigv4".to_string()],
+                AuthKind::Spnego => vec!["--spnego".to_string()],
+                AuthKind::None => Vec::new(),
             },
             Self::ContentHeaders(ref kind) => match kind {
-                HeaderKind::Accept(val) => format!("-H \"Accept: {}\"", val),
-                HeaderKind::ContentType(val) => format!("-H \"Content-Type: {}\"", val),
-                HeaderKind::Allow(val) => format!("-H \"Allow: {}\"", val),
-                HeaderKind::Connection(val) => format!("-H \"Connection: {}\"", val),
-                HeaderKind::ContentLength(val) => format!("-H \"Content-Length: {}\"", val),
-                HeaderKind::None => "".to_string(),
+                HeaderKind::None => Vec::new(),
+                kind => vec!["-H".to_string(), kind.to_string()],
             },
-            Self::UnrestrictedAuth => "--anyauth".to_string(),
-            _ => "".to_string(),
+            Self::UnrestrictedAuth => vec!["--anyauth".to_string()],
+            _ => Vec::new(),
         }
     }
+    // The curl arguments of the option as they are written in a POSIX shell
+    pub fn get_curl_flag_value(&self) -> String {
+        self.get_curl_args()
+            .iter()
+            .map(|arg| Shell::Posix.quote(arg))
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+    // The wget arguments for the option, or why wget can't do what curl does.
+    // Options that wget does by default (following redirects, failing on
+    // HTTP errors, negotiating auth) have no arguments.
+    pub fn get_wget_flag_value(&self) -> Result<Vec<String>, String> {
+        let flag = match self {
+            Self::Verbose => "--verbose".to_string(),
+            Self::Headers(ref str) => format!("--header={str}"),
+            Self::UploadFile(ref file) => format!("--body-file={file}"),
+            Self::Outfile(ref file) => format!("--output-document={file}"),
+            Self::NewCookie(ref cookie) => format!("--header=Cookie: {}", cookie_pair(cookie)),
+            Self::CookieJar(ref jar) => {
+                return Ok(vec![
+                    format!("--save-cookies={jar}"),
+                    "--keep-session-cookies".to_string(),
+                ])
+            }
+            Self::CookiePath(ref path) => format!("--load-cookies={path}"),
+            Self::Referrer(ref referrer) => format!("--referer={referrer}"),
+            Self::CaPath(ref path) => format!("--ca-certificate={path}"),
+            Self::MaxRedirects(ref size) => format!("--max-redirect={size}"),
+            Self::UserAgent(ref ua) => format!("--user-agent={ua}"),
+            Self::RequestBody(ref body) => format!("--body-data={body}"),
+            Self::EnableHeaders => "--save-headers".to_string(),
+            Self::ProxyTunnel => return Err("proxy tunnel".to_string()),
+            Self::CertInfo => return Err("certificate info".to_string()),
+            Self::TcpKeepAlive => return Err("TCP keepalive".to_string()),
+            Self::UnixSocket(ref socket) => return Err(format!("unix socket {socket}")),
+            Self::MatchWildcard => return Err("wildcard matching".to_string()),
+            Self::NewCookieSession => return Err("new cookie session".to_string()),
+            Self::Auth(ref kind) => match kind {
+                AuthKind::Basic(ref login) | AuthKind::Digest(ref login) => {
+                    let (user, password) = login.split_once(':').unwrap_or((login, ""));
+                    let mut flags =
+                        vec![format!("--user={user}"), format!("--password={password}")];
+                    // curl sends basic auth without waiting for a challenge
+                    if matches!(kind, AuthKind::Basic(_)) {
+                        flags.push("--auth-no-challenge".to_string());
+                    }
+                    return Ok(flags);
+                }
+                AuthKind::Bearer(ref token) => format!("--header={}", bearer_header(token)),
+                AuthKind::Ntlm | AuthKind::AwsSigv4 | AuthKind::Spnego => {
+                    return Err(format!("{kind} authentication"))
+                }
+                AuthKind::None => return Ok(Vec::new()),
+            },
+            Self::ContentHeaders(ref kind) => match kind {
+                HeaderKind::None => return Ok(Vec::new()),
+                kind => format!("--header={kind}"),
+            },
+            // the options of CuTE itself and what wget does by default
+            _ => return Ok(Vec::new()),
+        };
+        Ok(vec![flag])
+    }
     pub fn should_toggle(&self) -> bool {
         matches!(
             self,
@@ -0,0 +1,762 @@
+use crate::request::format::Formatter;
+use crate::request::jq::run_filter;
+use regex::Regex;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Syntax {
+    Json,
+    Xml,
+    Html,
+    Yaml,
+    // status line and response headers
+    Headers,
+    #[default]
+    Plain,
+}
+
+impl Syntax {
+    // Picks the syntax from the response Content-Type, falling back to
+    // sniffing the body when the server didn't send one
+    pub fn detect(content_type: Option<&str>, body: &str) -> Self {
+        let content_type = content_type.unwrap_or_default().to_lowercase();
+        let mime = content_type.split(';').next().unwrap_or_default().trim();
+        match mime {
+            m if m.ends_with("json") => return Syntax::Json,
+            m if m.ends_with("html") => return Syntax::Html,
+            m if m.ends_with("xml") => return Syntax::Xml,
+            m if m.ends_with("yaml") || m.ends_with("yml") => return Syntax::Yaml,
+            "" | "text/plain" | "application/octet-stream" => {}
+            _ => return Syntax::Plain,
+        }
+        let start = body.trim_start();
+        let lower = start.get(..15).unwrap_or(start).to_lowercase();
+        if start.starts_with('{') || start.starts_with('[') {
+            Syntax::Json
+        } else if lower.starts_with("<!doctype html") || lower.starts_with("<html") {
+            Syntax::Html
+        } else if start.starts_with('<') {
+            Syntax::Xml
+        } else if start.starts_with("---") {
+            Syntax::Yaml
+        } else {
+            Syntax::Plain
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Key,
+    Str,
+    Number,
+    Literal,
+    Punct,
+    Tag,
+    Attr,
+    Comment,
+    Text,
+}
+
+// Splits a line into highlighted tokens. Lines are highlighted on their own,
+// so strings or comments spanning several lines are only partly coloured
+pub fn highlight(line: &str, syntax: Syntax) -> Vec<(TokenKind, &str)> {
+    match syntax {
+        Syntax::Json => highlight_json(line),
+        Syntax::Xml | Syntax::Html => highlight_markup(line),
+        Syntax::Yaml => highlight_yaml(line),
+        Syntax::Headers => highlight_header(line),
+        Syntax::Plain => vec![(TokenKind::Text, line)],
+    }
+}
+
+fn scalar_kind(value: &str) -> TokenKind {
+    match value.trim() {
+        "true" | "false" | "null" | "~" => TokenKind::Literal,
+        v if v.parse::<f64>().is_ok() => TokenKind::Number,
+        v if v.starts_with('"') || v.starts_with('\'') => TokenKind::Str,
+        _ => TokenKind::Text,
+    }
+}
+
+// end of a quoted string starting at `start`, handling escapes
+fn string_end(line: &str, start: usize) -> usize {
+    let quote = line.as_bytes()[start];
+    let mut escaped = false;
+    for (i, b) in line.bytes().enumerate().skip(start + 1) {
+        match b {
+            b'\\' if !escaped => escaped = true,
+            b if b == quote && !escaped => return i + 1,
+            _ => escaped = false,
+        }
+    }
+    line.len()
+}
+
+fn highlight_json(line: &str) -> Vec<(TokenKind, &str)> {
+    let mut tokens = Vec::new();
+    let bytes = line.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        let kind = match bytes[i] {
+            b'"' => {
+                i = string_end(line, i);
+                // a string followed by a colon is an object key
+                match line[i..].trim_start().starts_with(':') {
+                    true => TokenKind::Key,
+                    false => TokenKind::Str,
+                }
+            }
+            b'{' | b'}' | b'[' | b']' | b':' | b',' => {
+                i += 1;
+                TokenKind::Punct
+            }
+            b if b.is_ascii_whitespace() => {
+                while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                    i += 1;
+                }
+                TokenKind::Text
+            }
+            _ => {
+                while i < bytes.len() && !b"{}[]:,\" \t".contains(&bytes[i]) {
+                    i += 1;
+                }
+                scalar_kind(&line[start..i])
+            }
+        };
+        tokens.push((kind, &line[start..i]));
+    }
+    tokens
+}
+
+fn highlight_markup(line: &str) -> Vec<(TokenKind, &str)> {
+    let mut tokens = Vec::new();
+    let mut rest = line;
+    while !rest.is_empty() {
+        if rest.starts_with("<!--") {
+            let end = rest.find("-->").map(|e| e + 3).unwrap_or(rest.len());
+            tokens.push((TokenKind::Comment, &rest[..end]));
+            rest = &rest[end..];
+        } else if rest.starts_with('<') {
+            let end = rest.find('>').map(|e| e + 1).unwrap_or(rest.len());
+            tokens.extend(highlight_tag(&rest[..end]));
+            rest = &rest[end..];
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            tokens.push((TokenKind::Text, &rest[..end]));
+            rest = &rest[end..];
+        }
+    }
+    tokens
+}
+
+// <name attr="value"> -> tag, attribute names and quoted values
+fn highlight_tag(tag: &str) -> Vec<(TokenKind, &str)> {
+    let skip = if tag.starts_with("</") { 2 } else { 1 };
+    let name_end = tag
+        .char_indices()
+        .skip(skip)
+        .find(|(_, c)| c.is_whitespace() || matches!(c, '>' | '/'))
+        .map(|(i, _)| i)
+        .unwrap_or(tag.len());
+    let mut tokens = vec![(TokenKind::Tag, &tag[..name_end])];
+    let bytes = tag.as_bytes();
+    let mut i = name_end;
+    while i < tag.len() {
+        let start = i;
+        let kind = match bytes[i] {
+            b'"' | b'\'' => {
+                i = string_end(tag, i);
+                TokenKind::Str
+            }
+            b'>' | b'/' | b'=' | b'?' => {
+                i += 1;
+                TokenKind::Tag
+            }
+            b if b.is_ascii_whitespace() => {
+                i += 1;
+                TokenKind::Text
+            }
+            _ => {
+                while i < tag.len() && !b"\"'>/= \t".contains(&bytes[i]) {
+                    i += 1;
+                }
+                TokenKind::Attr
+            }
+        };
+        tokens.push((kind, &tag[start..i]));
+    }
+    tokens
+}
+
+fn highlight_yaml(line: &str) -> Vec<(TokenKind, &str)> {
+    let content = line.trim_start();
+    let indent = &line[..line.len() - content.len()];
+    let mut tokens = vec![(TokenKind::Text, indent)];
+    if content.starts_with('#') {
+        tokens.push((TokenKind::Comment, content));
+        return tokens;
+    }
+    let (item, content) = match content.strip_prefix("- ") {
+        Some(rest) => (Some(&content[..2]), rest),
+        None => (None, content),
+    };
+    if let Some(dash) = item {
+        tokens.push((TokenKind::Punct, dash));
+    }
+    match content
+        .find(": ")
+        .or(content.strip_suffix(':').map(|k| k.len()))
+    {
+        Some(colon) if !content.starts_with(['"', '\'']) => {
+            tokens.push((TokenKind::Key, &content[..colon]));
+            tokens.push((TokenKind::Punct, &content[colon..colon + 1]));
+            let value = &content[colon + 1..];
+            tokens.push((scalar_kind(value), value));
+        }
+        _ => tokens.push((scalar_kind(content), content)),
+    }
+    tokens
+}
+
+fn highlight_header(line: &str) -> Vec<(TokenKind, &str)> {
+    if line.starts_with("HTTP/") {
+        return vec![(TokenKind::Tag, line)];
+    }
+    match line.find(':') {
+        Some(colon) => vec![
+            (TokenKind::Key, &line[..colon]),
+            (TokenKind::Punct, &line[colon..colon + 1]),
+            (TokenKind::Text, &line[colon + 1..]),
+        ],
+        None => vec![(TokenKind::Text, line)],
+    }
+}
+
+// Plain text searches are case insensitive unless the query has an
+// uppercase letter, a query wrapped in slashes (/like this/) is a regex
+fn search_regex(query: &str) -> Result<Regex, String> {
+    let pattern = match query.strip_prefix('/').and_then(|q| q.strip_suffix('/')) {
+        Some(pattern) if !pattern.is_empty() => pattern.to_string(),
+        _ if query.chars().any(char::is_uppercase) => regex::escape(query),
+        _ => format!("(?i){}", regex::escape(query)),
+    };
+    Regex::new(&pattern).map_err(|e| e.to_string())
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+// Lines closing a block, which stay part of the fold they close
+fn is_closer(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with('}') || line.starts_with(']') || line.starts_with("</")
+}
+
+// For every line, the last line of the block it opens (if any). Blocks are
+// found from indentation, which works for pretty printed JSON, XML and YAML
+fn fold_regions(lines: &[&str]) -> Vec<Option<usize>> {
+    let mut regions = vec![None; lines.len()];
+    for (i, line) in lines.iter().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let indent = indent_of(line);
+        let mut end = i;
+        for (j, next) in lines.iter().enumerate().skip(i + 1) {
+            if next.trim().is_empty() {
+                continue;
+            }
+            if indent_of(next) <= indent {
+                if indent_of(next) == indent && is_closer(next) && !is_closer(line) {
+                    end = j;
+                }
+                break;
+            }
+            end = j;
+        }
+        if end > i {
+            regions[i] = Some(end);
+        }
+    }
+    regions
+}
+
+// Byte offset of the start of every line, so lines can be sliced out of the
+// body without copying them
+fn line_starts(text: &str) -> Vec<usize> {
+    if text.is_empty() {
+        return Vec::new();
+    }
+    let mut starts = vec![0];
+    starts.extend(
+        text.bytes()
+            .enumerate()
+            .filter(|(i, b)| *b == b'\n' && i + 1 < text.len())
+            .map(|(i, _)| i + 1),
+    );
+    starts
+}
+
+fn line_at<'a>(text: &'a str, starts: &[usize], i: usize) -> &'a str {
+    let end = starts.get(i + 1).map(|e| e - 1).unwrap_or(text.len());
+    text[starts[i]..end].trim_end_matches(['\n', '\r'])
+}
+
+// The response body indexed by line, with folding, filter and search state
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BodyViewer {
+    pub syntax: Syntax,
+    body: String,
+    body_syntax: Syntax,
+    // parsed body, only once a filter needs it
+    json: Option<Value>,
+    // jq style filter applied to the body, and its output
+    pub filter: Option<String>,
+    filtered: Option<String>,
+    // last filter previewed while typing
+    preview: Option<String>,
+    starts: Vec<usize>,
+    regions: Vec<Option<usize>>,
+    folded: BTreeSet<usize>,
+    // line indices currently on screen, after folding
+    visible: Vec<usize>,
+    // shown in the title, e.g. after yanking
+    pub status: Option<String>,
+    pub search: Option<String>,
+    // (line, byte range) of every search match, in order
+    matches: Vec<(usize, Range<usize>)>,
+    // index into matches of the one last jumped to
+    current: Option<usize>,
+    // size of the whole body, when only the start of it was loaded
+    pub truncated: Option<usize>,
+    // formatter the body was rendered with, unless it is shown as received
+    pub format: Option<Formatter>,
+    pub raw: bool,
+    // set when the formatter was picked over the one the Content-Type asked for
+    pub overridden: bool,
+    // first row on screen, and how many rows fit
+    pub offset: usize,
+    pub page: usize,
+    // 'g' was pressed once, a second press goes to the top
+    pub pending_g: bool,
+}
+
+impl BodyViewer {
+    pub fn new(body: &str, syntax: Syntax) -> Self {
+        let mut viewer = Self {
+            body: body.to_string(),
+            body_syntax: syntax,
+            ..Default::default()
+        };
+        viewer.index_lines(syntax);
+        viewer
+    }
+
+    fn source(&self) -> &str {
+        self.filtered.as_deref().unwrap_or(&self.body)
+    }
+
+    pub fn line(&self, line: usize) -> &str {
+        line_at(self.source(), &self.starts, line)
+    }
+
+    fn index_lines(&mut self, syntax: Syntax) {
+        self.syntax = syntax;
+        self.starts = line_starts(self.source());
+        let lines: Vec<&str> = (0..self.starts.len()).map(|i| self.line(i)).collect();
+        self.regions = fold_regions(&lines);
+        self.folded.clear();
+        self.offset = 0;
+        self.update_visible();
+        if let Some(query) = self.search.take() {
+            let _ = self.set_search(&query);
+        }
+    }
+
+    // Shows the output of a jq style filter instead of the body, an empty
+    // filter shows the whole body again. On error the current view is kept
+    pub fn set_filter(&mut self, filter: &str) -> Result<(), String> {
+        let filter = filter.trim();
+        if filter.is_empty() {
+            self.filter = None;
+            self.filtered = None;
+            self.index_lines(self.body_syntax);
+            return Ok(());
+        }
+        if self.json.is_none() && self.body_syntax == Syntax::Json {
+            self.json = serde_json::from_str(&self.body).ok();
+        }
+        let json = self
+            .json
+            .as_ref()
+            .ok_or_else(|| String::from("Filters can only be applied to JSON responses"))?;
+        let output = run_filter(filter, json)?;
+        self.filter = Some(filter.to_string());
+        self.filtered = Some(output);
+        self.index_lines(Syntax::Json);
+        Ok(())
+    }
+
+    // Applies the filter as it is being typed, the error (if any) is shown
+    // in the title while the last good result stays on screen
+    pub fn preview_filter(&mut self, filter: &str) {
+        if self.preview.as_deref() == Some(filter) {
+            return;
+        }
+        self.preview = Some(filter.to_string());
+        self.status = self
+            .set_filter(filter)
+            .err()
+            .map(|e| format!("Error: {}", e));
+    }
+
+    // Finds every match of the query, an empty query clears the search
+    pub fn set_search(&mut self, query: &str) -> Result<(), String> {
+        self.matches.clear();
+        self.current = None;
+        self.search = None;
+        if query.is_empty() {
+            return Ok(());
+        }
+        let regex = search_regex(query)?;
+        let source = self.filtered.as_deref().unwrap_or(&self.body);
+        self.matches = (0..self.starts.len())
+            .flat_map(|i| {
+                regex
+                    .find_iter(line_at(source, &self.starts, i))
+                    .filter(|m| !m.is_empty())
+                    .map(move |m| (i, m.range()))
+            })
+            .collect();
+        self.search = Some(query.to_string());
+        Ok(())
+    }
+
+    // Searches as the query is typed, moving to the first match from `row`.
+    // Returns the row the cursor should move to
+    pub fn preview_search(&mut self, query: &str, row: usize) -> usize {
+        if self.search.as_deref().unwrap_or_default() == query {
+            return row;
+        }
+        self.status = self
+            .set_search(query)
+            .err()
+            .map(|e| format!("Error: {}", e));
+        self.jump_to_match(row, true)
+    }
+
+    pub fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    // 1 based position of the current match, for the title
+    pub fn current_match(&self) -> Option<usize> {
+        self.current.map(|i| i + 1)
+    }
+
+    // Byte ranges of the matches on a line, and which of them is current
+    pub fn line_matches(&self, line: usize) -> Vec<(Range<usize>, bool)> {
+        let start = self.matches.partition_point(|(l, _)| *l < line);
+        self.matches[start..]
+            .iter()
+            .take_while(|(l, _)| *l == line)
+            .enumerate()
+            .map(|(i, (_, range))| (range.clone(), self.current == Some(start + i)))
+            .collect()
+    }
+
+    // Moves to the next (or previous) match, wrapping around and unfolding
+    // anything hiding it. Returns the row the cursor should move to
+    pub fn jump_to_match(&mut self, row: usize, forward: bool) -> usize {
+        if self.matches.is_empty() {
+            return row;
+        }
+        let len = self.matches.len();
+        let line = self.visible.get(row).copied().unwrap_or_default();
+        let next = match (self.current, forward) {
+            (Some(i), true) => (i + 1) % len,
+            (Some(i), false) => (i + len - 1) % len,
+            (None, true) => self
+                .matches
+                .iter()
+                .position(|(l, _)| *l >= line)
+                .unwrap_or(0),
+            (None, false) => self
+                .matches
+                .iter()
+                .rposition(|(l, _)| *l <= line)
+                .unwrap_or(len - 1),
+        };
+        self.current = Some(next);
+        let target = self.matches[next].0;
+        let hidden: Vec<usize> = self
+            .folded
+            .iter()
+            .filter(|start| {
+                **start < target && self.regions[**start].is_some_and(|end| end >= target)
+            })
+            .copied()
+            .collect();
+        if !hidden.is_empty() {
+            hidden.iter().for_each(|start| {
+                self.folded.remove(start);
+            });
+            self.update_visible();
+        }
+        self.visible.binary_search(&target).unwrap_or(row)
+    }
+
+    // Everything currently shown, ignoring folds
+    pub fn text(&self) -> &str {
+        self.source()
+    }
+
+    // Moves the window so the cursor row is on screen
+    pub fn scroll_into_view(&mut self, row: usize, height: usize) {
+        self.page = height.max(1);
+        if row < self.offset {
+            self.offset = row;
+        } else if row >= self.offset + self.page {
+            self.offset = row + 1 - self.page;
+        }
+        self.offset = self.offset.min(self.len().saturating_sub(1));
+    }
+
+    fn update_visible(&mut self) {
+        self.visible.clear();
+        let mut i = 0;
+        while i < self.starts.len() {
+            self.visible.push(i);
+            i = match self.regions[i] {
+                Some(end) if self.folded.contains(&i) => end + 1,
+                _ => i + 1,
+            };
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.visible.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.visible.is_empty()
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.starts.len()
+    }
+
+    // (line number, text, folded) of the visible row
+    pub fn row(&self, row: usize) -> Option<(usize, &str, bool)> {
+        let line = *self.visible.get(row)?;
+        Some((line, self.line(line), self.folded.contains(&line)))
+    }
+
+    pub fn is_foldable(&self, row: usize) -> bool {
+        self.visible
+            .get(row)
+            .is_some_and(|line| self.regions[*line].is_some())
+    }
+
+    // Folds or unfolds the block opened at `row`, or the innermost block
+    // containing it. Returns the row the cursor should move to
+    pub fn toggle_fold(&mut self, row: usize) -> usize {
+        let Some(&line) = self.visible.get(row) else {
+            return row;
+        };
+        let target = match self.regions[line] {
+            Some(_) => Some(line),
+            None => (0..line)
+                .rev()
+                .find(|start| self.regions[*start].is_some_and(|end| end >= line)),
+        };
+        let Some(target) = target else {
+            return row;
+        };
+        if !self.folded.remove(&target) {
+            self.folded.insert(target);
+        }
+        self.update_visible();
+        self.visible.binary_search(&target).unwrap_or(row)
+    }
+
+    // Folds every top level block, or unfolds everything if anything is folded
+    pub fn toggle_all(&mut self) {
+        if self.folded.is_empty() {
+            let mut i = 0;
+            while i < self.starts.len() {
+                match self.regions[i] {
+                    // keep the outermost block of a document open
+                    Some(end) if i == 0 && end + 1 >= self.starts.len() => i += 1,
+                    Some(end) => {
+                        self.folded.insert(i);
+                        i = end + 1;
+                    }
+                    None => i += 1,
+                }
+            }
+        } else {
+            self.folded.clear();
+        }
+        self.update_visible();
+    }
+
+    // The block under the cursor as text, dedented. For JSON the key and
+    // trailing comma are dropped so the result is a valid document
+    pub fn subtree(&self, row: usize) -> Option<String> {
+        let line = *self.visible.get(row)?;
+        let end = self.regions[line].unwrap_or(line);
+        let indent = indent_of(self.line(line));
+        let mut text: Vec<&str> = (line..=end)
+            .map(|i| self.line(i))
+            .map(|l| l.get(indent.min(indent_of(l))..).unwrap_or_default())
+            .collect();
+        if self.syntax == Syntax::Json {
+            let first = text[0];
+            if first.starts_with('"') {
+                let key_end = string_end(first, 0);
+                if let Some(value) = first[key_end..].trim_start().strip_prefix(':') {
+                    text[0] = value.trim_start();
+                }
+            }
+            let last = text.len() - 1;
+            text[last] = text[last].trim_end().trim_end_matches(',');
+        }
+        Some(text.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BODY: &str = "{\n  \"id\": 1,\n  \"user\": {\n    \"name\": \"a\",\n    \"tags\": [\n      \"x\"\n    ]\n  },\n  \"ok\": true\n}";
+
+    #[test]
+    fn test_detect_syntax() {
+        assert_eq!(
+            Syntax::detect(Some("application/json; charset=utf-8"), ""),
+            Syntax::Json
+        );
+        assert_eq!(
+            Syntax::detect(Some("application/problem+json"), ""),
+            Syntax::Json
+        );
+        assert_eq!(Syntax::detect(Some("text/html"), ""), Syntax::Html);
+        assert_eq!(Syntax::detect(Some("application/xml"), ""), Syntax::Xml);
+        assert_eq!(Syntax::detect(Some("application/yaml"), ""), Syntax::Yaml);
+        assert_eq!(Syntax::detect(None, "  [1, 2]"), Syntax::Json);
+        assert_eq!(Syntax::detect(None, "<!DOCTYPE html>"), Syntax::Html);
+        assert_eq!(Syntax::detect(Some("text/csv"), "{"), Syntax::Plain);
+    }
+
+    #[test]
+    fn test_highlight_json() {
+        let tokens = highlight("  \"name\": \"a\\\"b\", 1.5, null", Syntax::Json);
+        let kinds: Vec<TokenKind> = tokens
+            .iter()
+            .filter(|(k, _)| !matches!(k, TokenKind::Text | TokenKind::Punct))
+            .map(|(k, _)| *k)
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::Key,
+                TokenKind::Str,
+                TokenKind::Number,
+                TokenKind::Literal
+            ]
+        );
+        let text: String = tokens.iter().map(|(_, t)| *t).collect();
+        assert_eq!(text, "  \"name\": \"a\\\"b\", 1.5, null");
+    }
+
+    #[test]
+    fn test_folding() {
+        let mut viewer = BodyViewer::new(BODY, Syntax::Json);
+        assert_eq!(viewer.len(), 10);
+        // fold "user": { ... }
+        assert_eq!(viewer.toggle_fold(2), 2);
+        assert_eq!(viewer.len(), 5);
+        assert_eq!(viewer.row(3), Some((8, "  \"ok\": true", false)));
+        assert_eq!(viewer.row(2).map(|r| r.2), Some(true));
+        viewer.toggle_fold(2);
+        assert_eq!(viewer.len(), 10);
+        // folding from inside a block folds the enclosing block
+        assert_eq!(viewer.toggle_fold(5), 4);
+        assert_eq!(viewer.len(), 8);
+        viewer.toggle_all();
+        assert_eq!(viewer.len(), 10);
+        viewer.toggle_all();
+        assert_eq!(viewer.len(), 5);
+    }
+
+    #[test]
+    fn test_filter() {
+        let mut viewer = BodyViewer::new(BODY, Syntax::Json);
+        viewer.set_filter(".user.tags").unwrap();
+        assert_eq!(viewer.text(), "[\n  \"x\"\n]");
+        assert_eq!(viewer.filter.as_deref(), Some(".user.tags"));
+        assert!(viewer.set_filter(".user[").is_err());
+        assert_eq!(viewer.len(), 3);
+        viewer.set_filter("").unwrap();
+        assert_eq!(viewer.text(), BODY);
+        assert!(BodyViewer::new("text", Syntax::Plain)
+            .set_filter(".")
+            .is_err());
+    }
+
+    #[test]
+    fn test_search() {
+        let mut viewer = BodyViewer::new(BODY, Syntax::Json);
+        viewer.set_search("\"").unwrap();
+        assert_eq!(viewer.match_count(), 14);
+        viewer.set_search("NAME").unwrap();
+        assert_eq!(viewer.match_count(), 0);
+        viewer.set_search("name").unwrap();
+        assert_eq!(viewer.line_matches(3), vec![(5..9, false)]);
+        viewer.set_search("/\\b(id|ok)\\b/").unwrap();
+        assert_eq!(viewer.match_count(), 2);
+        assert!(viewer.set_search("/(/").is_err());
+        // jumping into a folded block unfolds it
+        viewer.set_search("x").unwrap();
+        viewer.toggle_fold(2);
+        assert_eq!(viewer.jump_to_match(0, true), 5);
+        assert_eq!(viewer.len(), 10);
+        assert_eq!(viewer.current_match(), Some(1));
+        assert_eq!(viewer.line_matches(5), vec![(7..8, true)]);
+        // the search follows the filter
+        viewer.set_filter(".user.tags").unwrap();
+        assert_eq!(viewer.match_count(), 1);
+    }
+
+    #[test]
+    fn test_line_index_and_scrolling() {
+        let mut viewer = BodyViewer::new("a\r\nb\n\nc\n", Syntax::Plain);
+        assert_eq!(viewer.line_count(), 4);
+        assert_eq!(viewer.line(0), "a");
+        assert_eq!(viewer.line(2), "");
+        assert_eq!(viewer.line(3), "c");
+        viewer.scroll_into_view(3, 2);
+        assert_eq!((viewer.offset, viewer.page), (2, 2));
+        viewer.scroll_into_view(0, 2);
+        assert_eq!(viewer.offset, 0);
+        assert_eq!(BodyViewer::new("", Syntax::Plain).line_count(), 0);
+    }
+
+    #[test]
+    fn test_subtree() {
+        let viewer = BodyViewer::new(BODY, Syntax::Json);
+        let user = viewer.subtree(2).unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&user).unwrap(),
+            serde_json::json!({"name": "a", "tags": ["x"]})
+        );
+        assert_eq!(viewer.subtree(8).unwrap(), "true");
+    }
+}
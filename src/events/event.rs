@@ -48,8 +48,8 @@ impl EventHandler {
                             CrosstermEvent::Key(e) => sender.send(Event::Key(e)),
                             CrosstermEvent::Mouse(e) => sender.send(Event::Mouse(e)),
                             CrosstermEvent::Resize(w, h) => sender.send(Event::Resize(w, h)),
-                            CrosstermEvent::FocusGained => {sender.send(Event::Tick)}
-                            CrosstermEvent::FocusLost => {sender.send(Event::Tick)}
+                            CrosstermEvent::FocusGained => sender.send(Event::Tick),
+                            CrosstermEvent::FocusLost => sender.send(Event::Tick),
                             CrosstermEvent::Paste(_s) => sender.send(Event::Tick),
                         }
                         .expect("failed to send terminal event")
@@ -13,15 +13,18 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
         InputMode::Normal => {
             match key_event.kind {
                 KeyEventKind::Press => {
+                    if key_event.code != KeyCode::Char('g') {
+                        app.viewer.pending_g = false;
+                    }
                     match key_event.code {
                         KeyCode::Char('q') => {
                             app.quit();
                         }
                         // Exit application on `Ctrl-C`
-                        KeyCode::Char('c') | KeyCode::Char('C') => {
-                            if key_event.modifiers == KeyModifiers::CONTROL {
-                                app.quit();
-                            }
+                        KeyCode::Char('c') | KeyCode::Char('C')
+                            if key_event.modifiers == KeyModifiers::CONTROL =>
+                        {
+                            app.quit();
                         }
                         KeyCode::Esc => {
                             app.go_back_screen(); // Escape Should Bring You Back
@@ -43,6 +46,124 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
                                 app.goto_screen(&Screen::SavedKeys(Some(InputOpt::ApiKey)))
                             }
                         }
+                        KeyCode::Char('/') => {
+                            if let Screen::History { filter, .. } = &app.current_screen {
+                                app.goto_screen(&Screen::History {
+                                    filter: filter.clone(),
+                                    opt: Some(InputOpt::HistoryFilter),
+                                });
+                                app.input_mode = InputMode::Editing;
+                            } else if let Screen::ViewBody(_) = app.current_screen {
+                                app.open_viewer_prompt(InputOpt::BodySearch);
+                            }
+                        }
+                        KeyCode::Char('e')
+                            if matches!(app.current_screen, Screen::History { .. }) =>
+                        {
+                            if let Screen::History { filter, .. } = &app.current_screen {
+                                let filter = filter.clone();
+                                app.goto_screen(&Screen::History {
+                                    filter: filter.clone(),
+                                    opt: Some(InputOpt::ExportHistory(filter)),
+                                });
+                                app.input_mode = InputMode::Editing;
+                            }
+                        }
+                        KeyCode::Char('e')
+                            if matches!(app.current_screen, Screen::SavedCommands { .. }) =>
+                        {
+                            if let Screen::SavedCommands { id, .. } = app.current_screen {
+                                app.goto_screen(&Screen::SavedCommands {
+                                    id,
+                                    opt: Some(InputOpt::ExportCollection(id)),
+                                });
+                                app.input_mode = InputMode::Editing;
+                            }
+                        }
+                        KeyCode::Char(' ')
+                            if matches!(app.current_screen, Screen::SavedCommands { .. }) =>
+                        {
+                            app.toggle_mark();
+                        }
+                        KeyCode::Char('m')
+                            if matches!(app.current_screen, Screen::SavedCommands { .. }) =>
+                        {
+                            let ids = app.commands_to_move();
+                            if !ids.is_empty() {
+                                app.goto_screen(&Screen::MoveCommands {
+                                    ids,
+                                    copy: false,
+                                    opt: None,
+                                });
+                            }
+                        }
+                        KeyCode::Char('d')
+                            if matches!(app.current_screen, Screen::Environments(_)) =>
+                        {
+                            let environments = app.db.get_environments().unwrap_or_default();
+                            if let Some(env) = environments.get(app.cursor) {
+                                app.goto_screen(&Screen::EnvironmentMenu(env.get_id()));
+                            }
+                        }
+                        KeyCode::Char('n') if matches!(app.current_screen, Screen::ViewBody(_)) => {
+                            app.jump_to_match(true);
+                        }
+                        KeyCode::Char('N') if matches!(app.current_screen, Screen::ViewBody(_)) => {
+                            app.jump_to_match(false);
+                        }
+                        KeyCode::Char('z') | KeyCode::Char(' ')
+                            if matches!(app.current_screen, Screen::ViewBody(_)) =>
+                        {
+                            app.toggle_fold();
+                        }
+                        KeyCode::Char('Z') if matches!(app.current_screen, Screen::ViewBody(_)) => {
+                            app.toggle_all_folds();
+                        }
+                        KeyCode::PageDown if matches!(app.current_screen, Screen::ViewBody(_)) => {
+                            app.scroll_page(true);
+                        }
+                        KeyCode::PageUp if matches!(app.current_screen, Screen::ViewBody(_)) => {
+                            app.scroll_page(false);
+                        }
+                        KeyCode::Char('G') | KeyCode::End
+                            if matches!(app.current_screen, Screen::ViewBody(_)) =>
+                        {
+                            app.scroll_to_end(true);
+                        }
+                        KeyCode::Home if matches!(app.current_screen, Screen::ViewBody(_)) => {
+                            app.scroll_to_end(false);
+                        }
+                        // gg goes to the top, like vim
+                        KeyCode::Char('g') if matches!(app.current_screen, Screen::ViewBody(_)) => {
+                            if app.viewer.pending_g {
+                                app.scroll_to_end(false);
+                            }
+                            app.viewer.pending_g = !app.viewer.pending_g;
+                        }
+                        KeyCode::Char('L') if matches!(app.current_screen, Screen::ViewBody(_)) => {
+                            app.load_full_body();
+                        }
+                        KeyCode::Char('f') if matches!(app.current_screen, Screen::ViewBody(_)) => {
+                            app.open_viewer_prompt(InputOpt::BodyFilter);
+                        }
+                        KeyCode::Char('s') if matches!(app.current_screen, Screen::ViewBody(_)) => {
+                            app.open_viewer_prompt(InputOpt::SaveBody);
+                        }
+                        KeyCode::Char('c') if matches!(app.current_screen, Screen::ViewBody(_)) => {
+                            app.copy_view();
+                        }
+                        KeyCode::Char('c') if matches!(app.current_screen, Screen::Benchmark) => {
+                            app.cancel_benchmark();
+                        }
+                        KeyCode::Char('y') if matches!(app.current_screen, Screen::ViewBody(_)) => {
+                            app.yank_subtree();
+                        }
+                        KeyCode::Char('r') if matches!(app.current_screen, Screen::ViewBody(_)) => {
+                            app.toggle_raw_body();
+                        }
+                        KeyCode::Char('t') if matches!(app.current_screen, Screen::ViewBody(_)) => {
+                            app.cycle_body_format();
+                        }
                         KeyCode::Char('i') => match &app.current_screen {
                             screen if screen.is_input_screen() => {
                                 app.input_mode = InputMode::Editing;
@@ -91,9 +212,11 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
                     }
                 }
                 KeyCode::Backspace => {
-                    if app.input.handle(InputRequest::DeletePrevChar).is_some() {}
+                    app.input.handle(InputRequest::DeletePrevChar);
+                }
+                KeyCode::Delete => {
+                    app.input.handle(InputRequest::DeleteNextChar);
                 }
-                KeyCode::Delete => if app.input.handle(InputRequest::DeleteNextChar).is_some() {},
                 // if ctrl + left or ctrl + right is pressed, move one word at a time in either
                 // direction respectively
                 KeyCode::Left => {
@@ -0,0 +1,124 @@
+use crate::app::App;
+use crate::database::db::SavedCommand;
+use crate::request::report::{write_report, ReportFormat};
+use crate::request::runner::{CollectionRun, RunResult};
+use std::io::Write;
+
+/// What `cute run` was asked to execute.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RunTarget {
+    /// ID or label of a saved command
+    Command(String),
+    /// Name of a saved collection
+    Collection(String),
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct RunOptions {
+    /// Write the status line + response headers to stderr
+    pub include_headers: bool,
+    /// Write a report of the results to this path
+    pub report: Option<(String, ReportFormat)>,
+    /// Don't write the one line summary of each response to stderr
+    pub quiet: bool,
+    /// Name of the environment to resolve {{variables}} with, instead of the selected one
+    pub environment: Option<String>,
+}
+
+/// Runs the saved command(s) without starting the TUI, printing each response
+/// body to stdout and a summary of it to stderr. Returns the exit code for the process.
+pub fn run(app: &mut App, target: &RunTarget, opts: &RunOptions) -> i32 {
+    let commands = match find_commands(app, target) {
+        Ok(commands) => commands,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return 2;
+        }
+    };
+    if let Some(ref env) = opts.environment {
+        match app.db.get_environments() {
+            Ok(envs) => match envs.iter().find(|e| &e.name == env) {
+                Some(found) => app.environment = Some(found.get_id()),
+                None => {
+                    eprintln!("Error: No environment named: {env}");
+                    return 2;
+                }
+            },
+            Err(e) => {
+                eprintln!("Error: {e}");
+                return 2;
+            }
+        }
+    }
+    let name = match target {
+        RunTarget::Command(name) | RunTarget::Collection(name) => name,
+    };
+    let mut run = CollectionRun::new(name);
+    for cmd in commands.iter() {
+        let result = app.run_saved_command(cmd);
+        print_result(&result, opts);
+        run.results.push(result);
+    }
+    if let Some((ref path, format)) = opts.report {
+        if let Err(e) = write_report(path, &run, format) {
+            eprintln!("Error: failed to write report to {path}: {e}");
+            return 1.max(run.exit_code());
+        }
+    }
+    run.exit_code()
+}
+
+fn find_commands(app: &App, target: &RunTarget) -> Result<Vec<SavedCommand>, String> {
+    match target {
+        RunTarget::Command(cmd) => {
+            if let Ok(id) = cmd.parse::<i32>() {
+                if let Ok(command) = app.db.get_command_by_id(id) {
+                    return Ok(vec![command]);
+                }
+            }
+            app.db
+                .get_commands(None)
+                .map_err(|e| e.to_string())?
+                .into_iter()
+                .find(|c| c.label.as_deref() == Some(cmd.as_str()))
+                .map(|c| vec![c])
+                .ok_or(format!("No saved command with ID or label: {cmd}"))
+        }
+        RunTarget::Collection(name) => {
+            let collection = app
+                .db
+                .get_collections()
+                .map_err(|e| e.to_string())?
+                .into_iter()
+                .find(|c| c.get_name() == name)
+                .ok_or(format!("No collection named: {name}"))?;
+            let commands = app
+                .db
+                .get_commands(Some(collection.get_id()))
+                .map_err(|e| e.to_string())?;
+            if commands.is_empty() {
+                return Err(format!("Collection {name} has no saved requests"));
+            }
+            Ok(commands)
+        }
+    }
+}
+
+fn print_result(result: &RunResult, opts: &RunOptions) {
+    if opts.include_headers && !result.headers.is_empty() {
+        eprintln!("{}\n", result.headers);
+    }
+    if let Some(ref e) = result.error {
+        eprintln!("{}: {}", result.name, e);
+        return;
+    }
+    let mut stdout = std::io::stdout();
+    let _ = stdout.write_all(result.body.as_bytes());
+    if !result.body.ends_with('\n') {
+        let _ = stdout.write_all(b"\n");
+    }
+    let _ = stdout.flush();
+    if !opts.quiet {
+        eprintln!("{}: {}", result.name, result.summary.one_line());
+    }
+}
@@ -3,11 +3,15 @@ use std::{fmt::Display, path::PathBuf};
 
 use crate::display::menuopts::CUTE_LOGO;
 
-use database::db::DB;
+use database::db::{DB, DEFAULT_HISTORY_LIMIT};
 use dirs::config_dir;
+use request::shell::Shell;
 use serde::{Deserialize, Serialize};
 use tui::style::Style;
 
+// Megabytes of a response body kept in memory when the config doesn't say
+pub const DEFAULT_MAX_BODY_SIZE: usize = 10;
+
 // Application.
 pub mod app;
 
@@ -26,6 +30,9 @@ pub mod request;
 // Events & Event Handler
 pub mod events;
 
+// Running Saved Commands And Collections Without The TUI
+pub mod headless;
+
 pub mod tui_cute;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -33,6 +40,14 @@ pub struct Config {
     colors: Colors,
     logo: Option<Logo>,
     db_path: Option<PathBuf>,
+    // number of executed requests kept in the history, 0 disables it
+    history_limit: Option<usize>,
+    // MB of a response body kept in memory, the rest of larger ones goes to a temporary file
+    max_body_size: Option<usize>,
+    // shell that copied commands are quoted for: Posix, Fish, PowerShell or Cmd
+    command_shell: Option<Shell>,
+    // put each flag of a copied command on its own line
+    multiline_commands: Option<bool>,
 }
 
 impl Config {
@@ -81,6 +96,10 @@ impl Config {
             },
             logo: Some(Logo::Default),
             db_path: Some(DB::get_default_path()),
+            history_limit: Some(DEFAULT_HISTORY_LIMIT),
+            max_body_size: Some(DEFAULT_MAX_BODY_SIZE),
+            command_shell: Some(Shell::Posix),
+            multiline_commands: Some(false),
         }
     }
 
@@ -104,14 +123,30 @@ impl Config {
     pub fn get_db_path(&self) -> Option<PathBuf> {
         self.db_path.as_ref().cloned()
     }
+
+    pub fn get_history_limit(&self) -> usize {
+        self.history_limit.unwrap_or(DEFAULT_HISTORY_LIMIT)
+    }
+
+    pub fn get_command_shell(&self) -> Shell {
+        self.command_shell.unwrap_or_default()
+    }
+
+    pub fn get_multiline_commands(&self) -> bool {
+        self.multiline_commands.unwrap_or_default()
+    }
+
+    // in bytes, 0 in the config means no limit
+    pub fn get_max_body_size(&self) -> Option<usize> {
+        match self.max_body_size.unwrap_or(DEFAULT_MAX_BODY_SIZE) {
+            0 => None,
+            mb => Some(mb * 1024 * 1024),
+        }
+    }
 }
 impl Default for Config {
     fn default() -> Self {
-        if let Ok(config) = Self::load() {
-            config
-        } else {
-            Self::get_default_config()
-        }
+        Self::load().unwrap_or_else(|_| Self::get_default_config())
     }
 }
 
@@ -1,6 +1,6 @@
 #![warn(clippy::all)]
 #![allow(non_snake_case)]
-use clap::{builder::Command, Arg};
+use clap::{builder::Command, Arg, ArgAction, ArgMatches};
 use dirs::config_dir;
 use std::io;
 use std::sync::OnceLock;
@@ -11,11 +11,14 @@ use CuTE_tui::events::{
     event::{Event, EventHandler},
     handler::handle_key_events,
 };
+use CuTE_tui::headless::{self, RunOptions, RunTarget};
+use CuTE_tui::request::report::ReportFormat;
 use CuTE_tui::{tui_cute::Tui, Config};
 
 pub static CONFIG_PATH: OnceLock<String> = OnceLock::new();
 
 fn main() -> AppResult<()> {
+    let args = cli().get_matches();
     let mut app = App::new();
     CONFIG_PATH.get_or_init(|| {
         config_dir()
@@ -25,7 +28,10 @@ fn main() -> AppResult<()> {
             .to_string_lossy()
             .to_string()
     });
-    app.set_config(parse_cmdline().unwrap_or_default());
+    app.set_config(parse_cmdline(&args).unwrap_or_default());
+    if let Some(("run", run_args)) = args.subcommand() {
+        std::process::exit(run_headless(&mut app, run_args));
+    }
     let backend = CrosstermBackend::new(io::stdout());
     let terminal = Terminal::new(backend)?;
     let events = EventHandler::new(250);
@@ -42,14 +48,15 @@ fn main() -> AppResult<()> {
     Ok(())
 }
 
-fn parse_cmdline() -> Option<Config> {
-    let args = Command::new("CuTE")
+fn cli() -> Command {
+    Command::new("CuTE")
         .author("PThorpe92 <preston@unlockedlabs.org>")
         .version("0.0.1")
         .about("Simple TUI for sending and storing HTTP requests, API keys and Postman collections")
         .after_help("Arguments are '--dump-config {path}' to write the default config file to the specified path,
             \nand '--db-path' to define a custom path to the database\nDB path can also be defined in the config file at $CONFIG/CuTE/config.toml\n
-            or you can set the $CUTE_DB_PATH environment variable")
+            or you can set the $CUTE_DB_PATH environment variable\n
+            Use 'cute run <ID|LABEL>' or 'cute run --collection <NAME>' to execute saved requests without the TUI")
         .arg(
             Arg::new("db-path")
                 .help("Define a custom path to the database")
@@ -61,7 +68,85 @@ fn parse_cmdline() -> Option<Config> {
                 .help("Write the default config file to the current working directory")
                 .id("dump-config")
                 .long("dump-config")
-        ).get_matches();
+        )
+        .subcommand(
+            Command::new("run")
+                .about("Execute a saved request or collection without starting the TUI")
+                .arg(
+                    Arg::new("command")
+                        .help("ID or label of the saved request to run")
+                        .required_unless_present("collection")
+                        .conflicts_with("collection"),
+                )
+                .arg(
+                    Arg::new("collection")
+                        .help("Run every request in the collection with this name")
+                        .short('c')
+                        .long("collection"),
+                )
+                .arg(
+                    Arg::new("include")
+                        .help("Write the status line and response headers to stderr")
+                        .short('i')
+                        .long("include")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("quiet")
+                        .help("Don't write a one line summary of each response to stderr")
+                        .short('q')
+                        .long("quiet")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("env")
+                        .help("Resolve {{variables}} with the environment of this name")
+                        .short('e')
+                        .long("env"),
+                )
+                .arg(
+                    Arg::new("report")
+                        .help("Write a JUnit XML or JSON report of the results to this path")
+                        .long("report"),
+                )
+                .arg(
+                    Arg::new("report-format")
+                        .help("Format of the report: 'junit' or 'json' (defaults to the report file extension)")
+                        .long("report-format")
+                        .requires("report"),
+                ),
+        )
+}
+
+fn run_headless(app: &mut App, args: &ArgMatches) -> i32 {
+    let target = match args.get_one::<String>("collection") {
+        Some(name) => RunTarget::Collection(name.to_string()),
+        None => RunTarget::Command(
+            args.get_one::<String>("command")
+                .expect("Missing command argument")
+                .to_string(),
+        ),
+    };
+    let report = args.get_one::<String>("report").map(|path| {
+        let format = match args.get_one::<String>("report-format") {
+            Some(format) => format.parse().unwrap_or_else(|e| {
+                eprintln!("Error: {e}");
+                std::process::exit(2);
+            }),
+            None => ReportFormat::from_path(path),
+        };
+        (path.to_string(), format)
+    });
+    let opts = RunOptions {
+        include_headers: args.get_flag("include"),
+        report,
+        quiet: args.get_flag("quiet"),
+        environment: args.get_one::<String>("env").cloned(),
+    };
+    headless::run(app, &target, &opts)
+}
+
+fn parse_cmdline(args: &ArgMatches) -> Option<Config> {
     if args.contains_id("dump-config") {
         let mut config_path: String = args
             .get_one::<String>("dump-config")
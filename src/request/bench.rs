@@ -0,0 +1,440 @@
+use super::curl::{Collector, Curl};
+use curl::easy::Easy2;
+use curl::multi::{Easy2Handle, Multi};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+// Number of requests kept in flight when none is given
+pub const DEFAULT_BENCH_CONCURRENCY: usize = 10;
+// Number of bars in the latency histogram
+pub const BENCH_HISTOGRAM_BUCKETS: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BenchLimit {
+    // Stop after this many requests
+    Count(usize),
+    // Keep starting new requests until this much time has passed
+    Duration(Duration),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BenchConfig {
+    pub limit: BenchLimit,
+    pub concurrency: usize,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            limit: BenchLimit::Count(100),
+            concurrency: DEFAULT_BENCH_CONCURRENCY,
+        }
+    }
+}
+
+// Parses "<count|duration> [concurrency]", e.g. "500 20" sends 500 requests
+// 20 at a time, "30s 5" sends requests 5 at a time for 30 seconds
+impl FromStr for BenchConfig {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split_whitespace();
+        let limit = match parts.next() {
+            Some(limit) => parse_limit(limit)?,
+            None => return Err(String::from("Enter a request count or a duration")),
+        };
+        let concurrency = match parts.next() {
+            Some(c) => c
+                .parse::<usize>()
+                .ok()
+                .filter(|c| *c > 0)
+                .ok_or_else(|| format!("Invalid concurrency: {c}"))?,
+            None => DEFAULT_BENCH_CONCURRENCY,
+        };
+        if parts.next().is_some() {
+            return Err(String::from(
+                "Expected '<count|duration> [concurrency]', e.g. '500 20' or '30s 20'",
+            ));
+        }
+        Ok(Self { limit, concurrency })
+    }
+}
+
+fn parse_limit(limit: &str) -> Result<BenchLimit, String> {
+    let (num, unit) = match limit.strip_suffix('s') {
+        Some(secs) => (secs, Some(1)),
+        None => match limit.strip_suffix('m') {
+            Some(mins) => (mins, Some(60)),
+            None => (limit, None),
+        },
+    };
+    match (num.parse::<u64>(), unit) {
+        (Ok(n), Some(unit)) if n > 0 => Ok(BenchLimit::Duration(Duration::from_secs(n * unit))),
+        (Ok(n), None) if n > 0 => Ok(BenchLimit::Count(n as usize)),
+        _ => Err(format!("Invalid request count or duration: {limit}")),
+    }
+}
+
+impl Display for BenchConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.limit {
+            BenchLimit::Count(n) => write!(f, "{n} requests, {} concurrent", self.concurrency),
+            BenchLimit::Duration(d) => {
+                write!(f, "{}s, {} concurrent", d.as_secs(), self.concurrency)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BenchReport {
+    pub config: BenchConfig,
+    // latency of every request that received a response, sorted
+    pub latencies: Vec<Duration>,
+    // number of responses received for each status code
+    pub statuses: BTreeMap<u32, usize>,
+    // requests that failed before a response was received
+    pub errors: usize,
+    pub last_error: Option<String>,
+    // wall clock time of the whole run
+    pub elapsed: Duration,
+    // the run was stopped before reaching its limit
+    pub cancelled: bool,
+}
+
+impl BenchReport {
+    pub fn new(config: BenchConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    fn record(&mut self, latency: Duration, result: Result<u32, String>) {
+        match result {
+            Ok(status) => {
+                self.latencies.push(latency);
+                *self.statuses.entry(status).or_default() += 1;
+            }
+            Err(e) => {
+                self.errors += 1;
+                self.last_error = Some(e);
+            }
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.latencies.len() + self.errors
+    }
+
+    pub fn min(&self) -> Duration {
+        self.latencies.first().copied().unwrap_or_default()
+    }
+
+    pub fn max(&self) -> Duration {
+        self.latencies.last().copied().unwrap_or_default()
+    }
+
+    pub fn mean(&self) -> Duration {
+        if self.latencies.is_empty() {
+            return Duration::ZERO;
+        }
+        self.latencies.iter().sum::<Duration>() / self.latencies.len() as u32
+    }
+
+    // Nearest-rank percentile, p is 0-100
+    pub fn percentile(&self, p: f64) -> Duration {
+        if self.latencies.is_empty() {
+            return Duration::ZERO;
+        }
+        let rank = (p / 100.0 * self.latencies.len() as f64).ceil() as usize;
+        self.latencies[rank.clamp(1, self.latencies.len()) - 1]
+    }
+
+    pub fn requests_per_sec(&self) -> f64 {
+        if self.elapsed.is_zero() {
+            return 0.0;
+        }
+        self.total() as f64 / self.elapsed.as_secs_f64()
+    }
+
+    // Buckets the latencies between min and max into evenly sized bins,
+    // labelled with the upper bound of each bin in milliseconds
+    pub fn histogram(&self, buckets: usize) -> Vec<(String, u64)> {
+        if self.latencies.is_empty() || buckets == 0 {
+            return Vec::new();
+        }
+        let min = self.min().as_secs_f64() * 1000.0;
+        let max = self.max().as_secs_f64() * 1000.0;
+        let buckets = if max > min { buckets } else { 1 };
+        let width = (max - min) / buckets as f64;
+        let mut counts = vec![0u64; buckets];
+        for latency in self.latencies.iter() {
+            let ms = latency.as_secs_f64() * 1000.0;
+            let bucket = if width > 0.0 {
+                ((ms - min) / width) as usize
+            } else {
+                0
+            };
+            counts[bucket.min(buckets - 1)] += 1;
+        }
+        counts
+            .into_iter()
+            .enumerate()
+            .map(|(i, count)| {
+                let upper = min + width * (i + 1) as f64;
+                let label = if width < 1.0 {
+                    format!("{upper:.1}")
+                } else {
+                    format!("{upper:.0}")
+                };
+                (label, count)
+            })
+            .collect()
+    }
+}
+
+fn new_handle(json: &str) -> Result<Easy2<Collector>, String> {
+    let mut curl: Curl = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    curl.easy_from_opts();
+    curl.into_handle()
+}
+
+fn add_handle(
+    multi: &Multi,
+    easy: Easy2<Collector>,
+    token: usize,
+) -> Result<Easy2Handle<Collector>, String> {
+    let mut handle = multi.add2(easy).map_err(|e| e.to_string())?;
+    handle.set_token(token).map_err(|e| e.to_string())?;
+    Ok(handle)
+}
+
+// A benchmark running on its own thread, so the UI can show its progress
+// and cancel it
+#[derive(Debug)]
+pub struct BenchRun {
+    report: Arc<Mutex<BenchReport>>,
+    cancel: Arc<AtomicBool>,
+    thread: Option<JoinHandle<Result<(), String>>>,
+}
+
+impl BenchRun {
+    pub fn start(json: String, config: BenchConfig) -> Self {
+        let report = Arc::new(Mutex::new(BenchReport::new(config)));
+        let cancel = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let report = Arc::clone(&report);
+            let cancel = Arc::clone(&cancel);
+            std::thread::spawn(move || bench(&json, &config, &report, &cancel))
+        };
+        Self {
+            report,
+            cancel,
+            thread: Some(thread),
+        }
+    }
+
+    // The results so far
+    pub fn progress(&self) -> BenchReport {
+        let mut report = match self.report.lock() {
+            Ok(report) => report.clone(),
+            Err(e) => e.into_inner().clone(),
+        };
+        report.latencies.sort();
+        report
+    }
+
+    // Stops starting new requests, the ones in flight are dropped
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.thread.as_ref().is_none_or(JoinHandle::is_finished)
+    }
+
+    // Waits for the thread and returns the final report
+    pub fn finish(mut self) -> Result<BenchReport, String> {
+        if let Some(thread) = self.thread.take() {
+            thread
+                .join()
+                .map_err(|_| String::from("The benchmark thread panicked"))??;
+        }
+        Ok(self.progress())
+    }
+}
+
+// Sends the serialized command over and over through a curl multi handle,
+// keeping `concurrency` requests in flight until the limit is reached.
+// Each slot reuses its easy handle so connections are kept alive.
+fn bench(
+    json: &str,
+    config: &BenchConfig,
+    report: &Mutex<BenchReport>,
+    cancel: &AtomicBool,
+) -> Result<(), String> {
+    curl::init();
+    let multi = Multi::new();
+    let mut slots: Vec<Option<(Easy2Handle<Collector>, Instant)>> = Vec::new();
+    let start = Instant::now();
+    let should_start = |started: usize| match config.limit {
+        BenchLimit::Count(n) => started < n,
+        BenchLimit::Duration(d) => start.elapsed() < d,
+    };
+    let update = |f: &mut dyn FnMut(&mut BenchReport)| {
+        let mut report = report.lock().map_err(|e| e.to_string())?;
+        f(&mut report);
+        report.elapsed = start.elapsed();
+        Ok::<(), String>(())
+    };
+    let mut started = 0;
+    for token in 0..config.concurrency {
+        if !should_start(started) {
+            break;
+        }
+        let handle = add_handle(&multi, new_handle(json)?, token)?;
+        slots.push(Some((handle, Instant::now())));
+        started += 1;
+    }
+    while slots.iter().any(Option::is_some) {
+        if cancel.load(Ordering::Relaxed) {
+            return update(&mut |report| report.cancelled = true);
+        }
+        let running = multi.perform().map_err(|e| e.to_string())?;
+        let mut done = Vec::new();
+        multi.messages(|msg| {
+            if let (Ok(token), Some(result)) = (msg.token(), msg.result()) {
+                done.push((token, result));
+            }
+        });
+        for (token, result) in done {
+            if let Some((handle, sent)) = slots[token].take() {
+                let latency = sent.elapsed();
+                let mut easy = multi.remove2(handle).map_err(|e| e.to_string())?;
+                let status = result
+                    .and_then(|_| easy.response_code())
+                    .map_err(|e| e.to_string());
+                update(&mut |report| report.record(latency, status.clone()))?;
+                if should_start(started) {
+                    easy.get_mut().clear();
+                    slots[token] = Some((add_handle(&multi, easy, token)?, Instant::now()));
+                    started += 1;
+                }
+            }
+        }
+        if running > 0 {
+            multi
+                .wait(&mut [], Duration::from_millis(100))
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    update(&mut |_| {})
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bench_config_from_str() {
+        let config: BenchConfig = "500 20".parse().unwrap();
+        assert_eq!(config.limit, BenchLimit::Count(500));
+        assert_eq!(config.concurrency, 20);
+        let config: BenchConfig = "30s".parse().unwrap();
+        assert_eq!(config.limit, BenchLimit::Duration(Duration::from_secs(30)));
+        assert_eq!(config.concurrency, DEFAULT_BENCH_CONCURRENCY);
+        assert_eq!(
+            "2m 1".parse::<BenchConfig>().unwrap().limit,
+            BenchLimit::Duration(Duration::from_secs(120))
+        );
+        assert!("".parse::<BenchConfig>().is_err());
+        assert!("0".parse::<BenchConfig>().is_err());
+        assert!("10 0".parse::<BenchConfig>().is_err());
+        assert!("ten".parse::<BenchConfig>().is_err());
+    }
+
+    #[test]
+    fn test_bench_report_stats() {
+        let mut report = BenchReport::default();
+        for ms in 1..=100 {
+            report.record(
+                Duration::from_millis(ms),
+                Ok(if ms > 90 { 500 } else { 200 }),
+            );
+        }
+        report.record(Duration::from_millis(5), Err(String::from("timeout")));
+        report.elapsed = Duration::from_secs(1);
+        assert_eq!(report.total(), 101);
+        assert_eq!(report.errors, 1);
+        assert_eq!(report.min(), Duration::from_millis(1));
+        assert_eq!(report.max(), Duration::from_millis(100));
+        assert_eq!(report.mean(), Duration::from_micros(50_500));
+        assert_eq!(report.percentile(50.0), Duration::from_millis(50));
+        assert_eq!(report.percentile(90.0), Duration::from_millis(90));
+        assert_eq!(report.percentile(99.0), Duration::from_millis(99));
+        assert_eq!(report.statuses.get(&200), Some(&90));
+        assert_eq!(report.statuses.get(&500), Some(&10));
+        assert_eq!(report.requests_per_sec(), 101.0);
+        let histogram = report.histogram(10);
+        assert_eq!(histogram.len(), 10);
+        assert_eq!(histogram.iter().map(|(_, c)| c).sum::<u64>(), 100);
+    }
+
+    #[test]
+    fn test_run_benchmark() {
+        let mut server = mockito::Server::new();
+        let mock = server
+            .mock("GET", "/")
+            .with_status(200)
+            .with_body("ok")
+            .expect(25)
+            .create();
+        let mut curl = Curl::new();
+        curl.set_url(&server.url());
+        curl.set_get_method();
+        let json = serde_json::to_string(&curl).unwrap();
+        let config: BenchConfig = "25 5".parse().unwrap();
+        let run = BenchRun::start(json, config);
+        while !run.is_finished() {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        let report = run.finish().unwrap();
+        mock.assert();
+        assert_eq!(report.total(), 25);
+        assert_eq!(report.errors, 0);
+        assert_eq!(report.statuses.get(&200), Some(&25));
+        assert!(!report.cancelled);
+    }
+
+    #[test]
+    fn test_bench_run_cancel() {
+        // not taken from the pool, the requests cut off by the cancel would
+        // reach whichever test gets the server next
+        let mut server = mockito::Server::new_with_opts(mockito::ServerOpts::default());
+        server
+            .mock("GET", "/")
+            .with_status(200)
+            .with_body("ok")
+            .expect_at_least(1)
+            .create();
+        let mut curl = Curl::new();
+        curl.set_url(&server.url());
+        curl.set_get_method();
+        let json = serde_json::to_string(&curl).unwrap();
+        let run = BenchRun::start(json, "10m 2".parse().unwrap());
+        while run.progress().total() == 0 {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(!run.is_finished());
+        run.cancel();
+        let report = run.finish().unwrap();
+        assert!(report.cancelled);
+        assert!(report.total() > 0);
+        assert!(report.elapsed < Duration::from_secs(60));
+    }
+}
@@ -0,0 +1,112 @@
+use encoding_rs::{Encoding, UTF_8};
+use regex::Regex;
+use std::sync::OnceLock;
+
+// How far into a body to look for a <meta charset> or <?xml encoding?> declaration
+const SNIFF_LEN: usize = 1024;
+
+// The `charset` parameter of a Content-Type header
+fn content_type_charset(content_type: &str) -> Option<&'static Encoding> {
+    content_type
+        .split(';')
+        .skip(1)
+        .filter_map(|param| param.split_once('='))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("charset"))
+        .and_then(|(_, value)| {
+            Encoding::for_label(value.trim().trim_matches(['"', '\'']).as_bytes())
+        })
+}
+
+// A charset declared by the document itself, in an HTML <meta> tag or the XML declaration
+fn declared_charset(body: &[u8]) -> Option<&'static Encoding> {
+    static PATTERNS: OnceLock<[Regex; 2]> = OnceLock::new();
+    let head = String::from_utf8_lossy(&body[..body.len().min(SNIFF_LEN)]);
+    PATTERNS
+        .get_or_init(|| {
+            [
+                r#"(?i)<meta[^>]*charset\s*=\s*["']?([\w.:-]+)"#,
+                r#"(?i)<\?xml[^>]*encoding\s*=\s*["']([\w.:-]+)"#,
+            ]
+            .map(|pattern| Regex::new(pattern).expect("valid charset regex"))
+        })
+        .iter()
+        .filter_map(|pattern| pattern.captures(&head))
+        .find_map(|captures| Encoding::for_label(captures[1].as_bytes()))
+}
+
+// Works out the encoding of a response body: a byte order mark wins, then the
+// Content-Type charset, then a declaration in the document, defaulting to UTF-8
+pub fn detect_charset(content_type: Option<&str>, body: &[u8]) -> &'static Encoding {
+    if let Some((encoding, _)) = Encoding::for_bom(body) {
+        return encoding;
+    }
+    content_type
+        .and_then(content_type_charset)
+        .or_else(|| declared_charset(body))
+        .unwrap_or(UTF_8)
+}
+
+pub fn charset_for_label(label: &str) -> Result<&'static Encoding, String> {
+    Encoding::for_label(label.trim().as_bytes())
+        .ok_or_else(|| format!("Unknown character encoding: {}", label.trim()))
+}
+
+// Bytes that aren't valid in the encoding are replaced, like a lossy UTF-8 conversion
+pub fn decode(body: &[u8], encoding: &'static Encoding) -> String {
+    encoding.decode(body).0.into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_charset() {
+        let detect = |ct, body: &[u8]| detect_charset(ct, body).name();
+        assert_eq!(detect(None, b"plain"), "UTF-8");
+        assert_eq!(
+            detect(Some("text/html; charset=ISO-8859-1"), b""),
+            "windows-1252"
+        );
+        assert_eq!(
+            detect(Some("text/plain; charset=\"Shift_JIS\""), b""),
+            "Shift_JIS"
+        );
+        assert_eq!(
+            detect(
+                Some("text/html"),
+                b"<html><head><meta charset=\"windows-1251\">"
+            ),
+            "windows-1251"
+        );
+        assert_eq!(
+            detect(
+                None,
+                b"<meta http-equiv=\"Content-Type\" content=\"text/html; charset=euc-jp\">"
+            ),
+            "EUC-JP"
+        );
+        assert_eq!(
+            detect(None, b"<?xml version=\"1.0\" encoding=\"ISO-8859-2\"?><a/>"),
+            "ISO-8859-2"
+        );
+        // the byte order mark wins over the header
+        assert_eq!(
+            detect(Some("text/plain; charset=latin1"), b"\xef\xbb\xbfhi"),
+            "UTF-8"
+        );
+        assert!(charset_for_label("nope").is_err());
+    }
+
+    #[test]
+    fn test_decode() {
+        let latin1 = charset_for_label("latin1").unwrap();
+        assert_eq!(decode(b"caf\xe9 \x80", latin1), "café €");
+        let sjis = charset_for_label("shift_jis").unwrap();
+        assert_eq!(
+            decode(b"\x82\xb1\x82\xf1\x82\xc9\x82\xbf\x82\xcd", sjis),
+            "こんにちは"
+        );
+        assert_eq!(decode(b"caf\xe9", UTF_8), "caf\u{fffd}");
+    }
+}
@@ -0,0 +1,640 @@
+use super::curl::{bearer_header, cookie_pair, AuthKind, Curl, Method};
+use crate::display::AppOptions;
+use std::fmt::{Display, Formatter};
+
+// The languages and clients a request can be rendered for, in menu order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Reqwest,
+    Python,
+    Fetch,
+    Go,
+    Httpie,
+    PowerShell,
+}
+
+pub const LANGUAGES: [Language; 6] = [
+    Language::Reqwest,
+    Language::Python,
+    Language::Fetch,
+    Language::Go,
+    Language::Httpie,
+    Language::PowerShell,
+];
+
+impl Display for Language {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Language::Reqwest => "Rust (reqwest)",
+            Language::Python => "Python (requests)",
+            Language::Fetch => "JavaScript (fetch)",
+            Language::Go => "Go (net/http)",
+            Language::Httpie => "HTTPie",
+            Language::PowerShell => "PowerShell (Invoke-RestMethod)",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Language {
+    // Suggested extension when the snippet is written to a file
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Language::Reqwest => "rs",
+            Language::Python => "py",
+            Language::Fetch => "mjs",
+            Language::Go => "go",
+            Language::Httpie => "sh",
+            Language::PowerShell => "ps1",
+        }
+    }
+
+    fn comment(&self) -> &'static str {
+        match self {
+            Language::Python | Language::Httpie | Language::PowerShell => "#",
+            _ => "//",
+        }
+    }
+}
+
+// What a snippet is made of, read from the options of the command
+#[derive(Debug, Default)]
+struct Request {
+    method: String,
+    url: String,
+    headers: Vec<(String, String)>,
+    body: Option<String>,
+    upload_file: Option<String>,
+    outfile: Option<String>,
+    auth: Option<AuthKind>,
+    follow_redirects: bool,
+    max_redirects: Option<usize>,
+    ca_path: Option<String>,
+    // options the language has no equivalent for
+    notes: Vec<String>,
+}
+
+impl Request {
+    fn new(curl: &Curl) -> Self {
+        let mut request = Request {
+            method: curl.get_method().to_string(),
+            url: curl.get_url().to_string(),
+            ..Default::default()
+        };
+        let mut cookies = Vec::new();
+        for opt in curl.opts.iter() {
+            match opt {
+                AppOptions::URL(url) if request.url.is_empty() => request.url = url.clone(),
+                AppOptions::Headers(header) => request.add_header(header),
+                AppOptions::ContentHeaders(kind) => request.add_header(&kind.to_string()),
+                AppOptions::Auth(AuthKind::None) => {}
+                AppOptions::Auth(AuthKind::Bearer(token)) => {
+                    request.add_header(&bearer_header(token))
+                }
+                AppOptions::Auth(kind) => request.auth = Some(kind.clone()),
+                AppOptions::UserAgent(agent) => {
+                    request.add_header(&format!("User-Agent: {}", agent))
+                }
+                AppOptions::Referrer(referrer) => {
+                    request.add_header(&format!("Referer: {}", referrer))
+                }
+                AppOptions::NewCookie(cookie) if !cookie.is_empty() => {
+                    cookies.push(cookie_pair(cookie))
+                }
+                AppOptions::RequestBody(body) => request.body = Some(body.clone()),
+                AppOptions::UploadFile(file) if !file.is_empty() => {
+                    request.upload_file = Some(file.clone())
+                }
+                AppOptions::Outfile(file) if !file.is_empty() => {
+                    request.outfile = Some(file.clone())
+                }
+                AppOptions::FollowRedirects => request.follow_redirects = true,
+                AppOptions::MaxRedirects(max) => request.max_redirects = Some(*max),
+                AppOptions::CaPath(path) => request.ca_path = Some(path.clone()),
+                AppOptions::UnixSocket(socket) if !socket.is_empty() => request.notes.push(
+                    format!("the request is sent over the unix socket {}", socket),
+                ),
+                AppOptions::ProxyTunnel => request
+                    .notes
+                    .push(String::from("proxy tunneling is not set")),
+                AppOptions::CookieJar(path) | AppOptions::CookiePath(path) => request
+                    .notes
+                    .push(format!("cookies are not read from or saved to {}", path)),
+                _ => {}
+            }
+        }
+        // the bearer header is also kept in the headers of a command that was executed
+        if let AuthKind::Bearer(token) = curl.get_auth() {
+            request.add_header(&bearer_header(token));
+        }
+        if !cookies.is_empty() {
+            request.add_header(&format!("Cookie: {}", cookies.join("; ")));
+        }
+        request
+    }
+
+    fn add_header(&mut self, header: &str) {
+        if let Some((name, value)) = header.split_once(':') {
+            let header = (name.trim().to_string(), value.trim().to_string());
+            if !header.0.is_empty() && !self.headers.contains(&header) {
+                self.headers.push(header);
+            }
+        }
+    }
+
+    fn content_type(&self) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+            .map(|(_, value)| value.as_str())
+    }
+
+    // (user, password) of basic and digest auth
+    fn login(&self) -> Option<(String, String)> {
+        match self.auth {
+            Some(AuthKind::Basic(ref login)) | Some(AuthKind::Digest(ref login)) => {
+                let (user, password) = login.split_once(':').unwrap_or((login, ""));
+                Some((user.to_string(), password.to_string()))
+            }
+            _ => None,
+        }
+    }
+
+    fn note_auth(&mut self, supported: &[&str]) {
+        let name = match self.auth {
+            Some(AuthKind::Basic(_)) => "basic",
+            Some(AuthKind::Digest(_)) => "digest",
+            Some(AuthKind::Ntlm) => "NTLM",
+            Some(AuthKind::AwsSigv4) => "AWS SigV4",
+            Some(AuthKind::Spnego) => "SPNEGO",
+            _ => return,
+        };
+        if !supported.contains(&name) {
+            self.notes
+                .push(format!("{} auth has to be added by hand", name));
+        }
+    }
+}
+
+// Renders the command as code for the language, options with no
+// equivalent are listed in a comment at the top
+pub fn generate(curl: &Curl, language: Language) -> String {
+    let mut request = Request::new(curl);
+    let code = match language {
+        Language::Reqwest => reqwest(&mut request),
+        Language::Python => python(&mut request),
+        Language::Fetch => fetch(&mut request),
+        Language::Go => go(&mut request),
+        Language::Httpie => httpie(&mut request),
+        Language::PowerShell => powershell(&mut request),
+    };
+    let notes: String = request
+        .notes
+        .iter()
+        .map(|note| format!("{} Note: {}\n", language.comment(), note))
+        .collect();
+    format!("{}{}", notes, code)
+}
+
+// A string literal that is valid in Python, JavaScript and Go
+fn quote(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_default()
+}
+
+// Bodies are kept readable in a raw string, r#"{"id": 1}"#
+fn rust_raw(text: &str) -> String {
+    let mut hashes = String::from("#");
+    while text.contains(&format!("\"{}", hashes)) {
+        hashes.push('#');
+    }
+    format!("r{}\"{}\"{}", hashes, text, hashes)
+}
+
+fn sh_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+fn ps_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+fn reqwest(request: &mut Request) -> String {
+    request.note_auth(&["basic"]);
+    let mut code = String::from("fn main() -> Result<(), Box<dyn std::error::Error>> {\n");
+    code.push_str("    let client = reqwest::blocking::Client::builder()\n");
+    // reqwest follows up to 10 redirects on its own
+    match (request.follow_redirects, request.max_redirects) {
+        (false, _) => code.push_str("        .redirect(reqwest::redirect::Policy::none())\n"),
+        (true, Some(max)) => code.push_str(&format!(
+            "        .redirect(reqwest::redirect::Policy::limited({}))\n",
+            max
+        )),
+        (true, None) => {}
+    }
+    if let Some(ref path) = request.ca_path {
+        code.push_str(&format!(
+            "        .add_root_certificate(reqwest::Certificate::from_pem(&std::fs::read({:?})?)?)\n",
+            path
+        ));
+    }
+    code.push_str("        .build()?;\n");
+    code.push_str("    let response = client\n");
+    code.push_str(&format!(
+        "        .request(reqwest::Method::{}, {:?})\n",
+        request.method, request.url
+    ));
+    for (name, value) in request.headers.iter() {
+        code.push_str(&format!("        .header({:?}, {:?})\n", name, value));
+    }
+    if let Some(AuthKind::Basic(_)) = request.auth {
+        let (user, password) = request.login().unwrap_or_default();
+        code.push_str(&format!(
+            "        .basic_auth({:?}, Some({:?}))\n",
+            user, password
+        ));
+    }
+    match (&request.upload_file, &request.body) {
+        (Some(file), _) => code.push_str(&format!("        .body(std::fs::read({:?})?)\n", file)),
+        (None, Some(body)) => code.push_str(&format!("        .body({})\n", rust_raw(body))),
+        (None, None) => {}
+    }
+    code.push_str("        .send()?;\n");
+    code.push_str("    println!(\"{}\", response.status());\n");
+    match request.outfile {
+        Some(ref file) => code.push_str(&format!(
+            "    std::fs::write({:?}, response.bytes()?)?;\n",
+            file
+        )),
+        None => code.push_str("    println!(\"{}\", response.text()?);\n"),
+    }
+    code.push_str("    Ok(())\n}\n");
+    code
+}
+
+fn python(request: &mut Request) -> String {
+    request.note_auth(&["basic", "digest"]);
+    if let (true, Some(max)) = (request.follow_redirects, request.max_redirects) {
+        request.notes.push(format!(
+            "requests follows up to 30 redirects, set Session.max_redirects = {}",
+            max
+        ));
+    }
+    let mut code = String::from("import requests\n");
+    if let Some(AuthKind::Digest(_)) = request.auth {
+        code.push_str("from requests.auth import HTTPDigestAuth\n");
+    }
+    code.push_str("\nresponse = requests.request(\n");
+    code.push_str(&format!("    {},\n", quote(&request.method)));
+    code.push_str(&format!("    {},\n", quote(&request.url)));
+    if !request.headers.is_empty() {
+        code.push_str("    headers={\n");
+        for (name, value) in request.headers.iter() {
+            code.push_str(&format!("        {}: {},\n", quote(name), quote(value)));
+        }
+        code.push_str("    },\n");
+    }
+    match (&request.upload_file, &request.body) {
+        (Some(file), _) => code.push_str(&format!("    data=open({}, \"rb\"),\n", quote(file))),
+        (None, Some(body)) => code.push_str(&format!("    data={},\n", quote(body))),
+        (None, None) => {}
+    }
+    if let Some((user, password)) = request.login() {
+        match request.auth {
+            Some(AuthKind::Digest(_)) => code.push_str(&format!(
+                "    auth=HTTPDigestAuth({}, {}),\n",
+                quote(&user),
+                quote(&password)
+            )),
+            _ => code.push_str(&format!(
+                "    auth=({}, {}),\n",
+                quote(&user),
+                quote(&password)
+            )),
+        }
+    }
+    // requests follows redirects by default, curl doesn't
+    if !request.follow_redirects {
+        code.push_str("    allow_redirects=False,\n");
+    }
+    if let Some(ref path) = request.ca_path {
+        code.push_str(&format!("    verify={},\n", quote(path)));
+    }
+    code.push_str(")\nprint(response.status_code)\n");
+    match request.outfile {
+        Some(ref file) => code.push_str(&format!(
+            "with open({}, \"wb\") as f:\n    f.write(response.content)\n",
+            quote(file)
+        )),
+        None => code.push_str("print(response.text)\n"),
+    }
+    code
+}
+
+fn fetch(request: &mut Request) -> String {
+    request.note_auth(&["basic"]);
+    if let Some(ref path) = request.ca_path {
+        request.notes.push(format!(
+            "run node with NODE_EXTRA_CA_CERTS={} to trust the CA",
+            path
+        ));
+    }
+    if let (true, Some(max)) = (request.follow_redirects, request.max_redirects) {
+        request
+            .notes
+            .push(format!("fetch follows up to 20 redirects, not {}", max));
+    }
+    let mut code = String::new();
+    if request.upload_file.is_some() || request.outfile.is_some() {
+        code.push_str("import { readFileSync, writeFileSync } from \"node:fs\";\n\n");
+    }
+    code.push_str(&format!(
+        "const response = await fetch({}, {{\n",
+        quote(&request.url)
+    ));
+    code.push_str(&format!("  method: {},\n", quote(&request.method)));
+    let login = request.login();
+    if !request.headers.is_empty() || login.is_some() {
+        code.push_str("  headers: {\n");
+        for (name, value) in request.headers.iter() {
+            code.push_str(&format!("    {}: {},\n", quote(name), quote(value)));
+        }
+        if let Some((user, password)) = login {
+            code.push_str(&format!(
+                "    \"Authorization\": \"Basic \" + btoa({}),\n",
+                quote(&format!("{}:{}", user, password))
+            ));
+        }
+        code.push_str("  },\n");
+    }
+    match (&request.upload_file, &request.body) {
+        (Some(file), _) => code.push_str(&format!("  body: readFileSync({}),\n", quote(file))),
+        (None, Some(body)) => code.push_str(&format!("  body: {},\n", quote(body))),
+        (None, None) => {}
+    }
+    if !request.follow_redirects {
+        code.push_str("  redirect: \"manual\",\n");
+    }
+    code.push_str("});\nconsole.log(response.status);\n");
+    match request.outfile {
+        Some(ref file) => code.push_str(&format!(
+            "writeFileSync({}, Buffer.from(await response.arrayBuffer()));\n",
+            quote(file)
+        )),
+        None => code.push_str("console.log(await response.text());\n"),
+    }
+    code
+}
+
+fn go(request: &mut Request) -> String {
+    request.note_auth(&["basic"]);
+    if let Some(ref path) = request.ca_path {
+        request.notes.push(format!(
+            "add {} to the RootCAs of the client's TLS config",
+            path
+        ));
+    }
+    let mut imports = vec!["\"fmt\"", "\"io\"", "\"net/http\""];
+    let body = match (&request.upload_file, &request.body) {
+        (Some(file), _) => {
+            imports.push("\"os\"");
+            format!("\tbody, err := os.Open({})\n\tif err != nil {{\n\t\tpanic(err)\n\t}}\n\tdefer body.Close()\n", quote(file))
+        }
+        (None, Some(body)) => {
+            imports.push("\"strings\"");
+            format!("\tbody := strings.NewReader({})\n", quote(body))
+        }
+        (None, None) => String::new(),
+    };
+    if request.outfile.is_some() && !imports.contains(&"\"os\"") {
+        imports.push("\"os\"");
+    }
+    imports.sort();
+    let mut code = String::from("package main\n\nimport (\n");
+    for import in imports {
+        code.push_str(&format!("\t{}\n", import));
+    }
+    code.push_str(")\n\nfunc main() {\n");
+    code.push_str(&body);
+    let reader = if body.is_empty() { "nil" } else { "body" };
+    code.push_str(&format!(
+        "\treq, err := http.NewRequest({}, {}, {})\n\tif err != nil {{\n\t\tpanic(err)\n\t}}\n",
+        quote(&request.method),
+        quote(&request.url),
+        reader
+    ));
+    for (name, value) in request.headers.iter() {
+        code.push_str(&format!(
+            "\treq.Header.Add({}, {})\n",
+            quote(name),
+            quote(value)
+        ));
+    }
+    if let Some((user, password)) = request.login() {
+        code.push_str(&format!(
+            "\treq.SetBasicAuth({}, {})\n",
+            quote(&user),
+            quote(&password)
+        ));
+    }
+    // the client follows up to 10 redirects on its own
+    match (request.follow_redirects, request.max_redirects) {
+        (false, _) => code.push_str("\tclient := &http.Client{\n\t\tCheckRedirect: func(req *http.Request, via []*http.Request) error {\n\t\t\treturn http.ErrUseLastResponse\n\t\t},\n\t}\n"),
+        (true, Some(max)) => code.push_str(&format!("\tclient := &http.Client{{\n\t\tCheckRedirect: func(req *http.Request, via []*http.Request) error {{\n\t\t\tif len(via) > {} {{\n\t\t\t\treturn http.ErrUseLastResponse\n\t\t\t}}\n\t\t\treturn nil\n\t\t}},\n\t}}\n", max)),
+        (true, None) => code.push_str("\tclient := &http.Client{}\n"),
+    }
+    code.push_str("\tresp, err := client.Do(req)\n\tif err != nil {\n\t\tpanic(err)\n\t}\n\tdefer resp.Body.Close()\n");
+    code.push_str("\tdata, err := io.ReadAll(resp.Body)\n\tif err != nil {\n\t\tpanic(err)\n\t}\n");
+    code.push_str("\tfmt.Println(resp.Status)\n");
+    match request.outfile {
+        Some(ref file) => code.push_str(&format!(
+            "\tif err := os.WriteFile({}, data, 0644); err != nil {{\n\t\tpanic(err)\n\t}}\n",
+            quote(file)
+        )),
+        None => code.push_str("\tfmt.Println(string(data))\n"),
+    }
+    code.push_str("}\n");
+    code
+}
+
+fn httpie(request: &mut Request) -> String {
+    request.note_auth(&["basic", "digest"]);
+    let mut args = vec![String::from("http")];
+    if request.follow_redirects {
+        args.push(String::from("--follow"));
+        if let Some(max) = request.max_redirects {
+            args.push(format!("--max-redirects={}", max));
+        }
+    }
+    if let Some(ref path) = request.ca_path {
+        args.push(format!("--verify={}", sh_quote(path)));
+    }
+    if let Some((user, password)) = request.login() {
+        if let Some(AuthKind::Digest(_)) = request.auth {
+            args.push(String::from("--auth-type=digest"));
+        }
+        args.push(format!(
+            "--auth={}",
+            sh_quote(&format!("{}:{}", user, password))
+        ));
+    }
+    if let Some(ref file) = request.outfile {
+        args.push(format!("--output={}", sh_quote(file)));
+    }
+    if let Some(body) = request
+        .body
+        .as_ref()
+        .filter(|_| request.upload_file.is_none())
+    {
+        args.push(format!("--raw={}", sh_quote(body)));
+    }
+    args.push(request.method.clone());
+    args.push(sh_quote(&request.url));
+    for (name, value) in request.headers.iter() {
+        args.push(sh_quote(&format!("{}:{}", name, value)));
+    }
+    if let Some(ref file) = request.upload_file {
+        args.push(format!("< {}", sh_quote(file)));
+    }
+    format!("{}\n", args.join(" \\\n  "))
+}
+
+fn powershell(request: &mut Request) -> String {
+    request.note_auth(&["basic"]);
+    if let Some(ref path) = request.ca_path {
+        request.notes.push(format!(
+            "import {} into the certificate store to trust the CA",
+            path
+        ));
+    }
+    let method = match request.method.parse::<Method>().ok() {
+        Some(Method::Get) => "Get",
+        Some(Method::Post) => "Post",
+        Some(Method::Put) => "Put",
+        Some(Method::Patch) => "Patch",
+        Some(Method::Delete) => "Delete",
+        Some(Method::Head) => "Head",
+        None => "Get",
+    };
+    let mut code = String::from("$params = @{\n");
+    code.push_str(&format!("    Uri = {}\n", ps_quote(&request.url)));
+    code.push_str(&format!("    Method = '{}'\n", method));
+    // Invoke-RestMethod takes the content type as a parameter of its own
+    let headers: Vec<&(String, String)> = request
+        .headers
+        .iter()
+        .filter(|(name, _)| !name.eq_ignore_ascii_case("content-type"))
+        .collect();
+    let login = request.login();
+    if !headers.is_empty() || login.is_some() {
+        code.push_str("    Headers = @{\n");
+        for (name, value) in headers {
+            code.push_str(&format!(
+                "        {} = {}\n",
+                ps_quote(name),
+                ps_quote(value)
+            ));
+        }
+        if let Some((user, password)) = login {
+            code.push_str(&format!(
+                "        'Authorization' = 'Basic ' + [Convert]::ToBase64String([Text.Encoding]::UTF8.GetBytes({}))\n",
+                ps_quote(&format!("{}:{}", user, password))
+            ));
+        }
+        code.push_str("    }\n");
+    }
+    if let Some(content_type) = request.content_type() {
+        code.push_str(&format!("    ContentType = {}\n", ps_quote(content_type)));
+    }
+    match (&request.upload_file, &request.body) {
+        (Some(file), _) => code.push_str(&format!("    InFile = {}\n", ps_quote(file))),
+        (None, Some(body)) => code.push_str(&format!("    Body = {}\n", ps_quote(body))),
+        (None, None) => {}
+    }
+    if let Some(ref file) = request.outfile {
+        code.push_str(&format!("    OutFile = {}\n", ps_quote(file)));
+    }
+    // Invoke-RestMethod follows redirects by default
+    match (request.follow_redirects, request.max_redirects) {
+        (false, _) => code.push_str("    MaximumRedirection = 0\n"),
+        (true, Some(max)) => code.push_str(&format!("    MaximumRedirection = {}\n", max)),
+        (true, None) => {}
+    }
+    code.push_str("}\nInvoke-RestMethod @params\n");
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request::ExecuteOption;
+
+    fn command() -> Curl {
+        let mut curl = Curl::new_serializing();
+        curl.set_url("https://api.example.com/items?q=it's");
+        curl.set_method(Method::Post);
+        curl.add_option(&AppOptions::Headers(String::from(
+            "Content-Type: application/json",
+        )));
+        curl.add_option(&AppOptions::Auth(AuthKind::Basic(String::from(
+            "admin:s3cret",
+        ))));
+        curl.add_option(&AppOptions::RequestBody(String::from(
+            "{\"name\": \"CuTE\", \"tag\": \"#1\"}",
+        )));
+        curl.add_option(&AppOptions::FollowRedirects);
+        curl
+    }
+
+    #[test]
+    fn test_generate_code() {
+        let curl = command();
+        let reqwest = generate(&curl, Language::Reqwest);
+        assert!(reqwest
+            .contains(".request(reqwest::Method::POST, \"https://api.example.com/items?q=it's\")"));
+        assert!(reqwest.contains(".header(\"Content-Type\", \"application/json\")"));
+        assert!(reqwest.contains(".basic_auth(\"admin\", Some(\"s3cret\"))"));
+        assert!(reqwest.contains(".body(r##\"{\"name\": \"CuTE\", \"tag\": \"#1\"}\"##)"));
+        assert!(!reqwest.contains("redirect"));
+
+        let python = generate(&curl, Language::Python);
+        assert!(python.contains("data=\"{\\\"name\\\": \\\"CuTE\\\", \\\"tag\\\": \\\"#1\\\"}\","));
+        assert!(python.contains("auth=(\"admin\", \"s3cret\"),"));
+        assert!(!python.contains("allow_redirects"));
+
+        let fetch = generate(&curl, Language::Fetch);
+        assert!(fetch.contains("method: \"POST\","));
+        assert!(fetch.contains("\"Authorization\": \"Basic \" + btoa(\"admin:s3cret\"),"));
+
+        let go = generate(&curl, Language::Go);
+        assert!(go.contains("req.SetBasicAuth(\"admin\", \"s3cret\")"));
+        assert!(go.contains("\t\"strings\"\n"));
+        assert!(go.contains("client := &http.Client{}"));
+
+        assert_eq!(
+            generate(&curl, Language::Httpie),
+            "http \\\n  --follow \\\n  --auth='admin:s3cret' \\\n  --raw='{\"name\": \"CuTE\", \"tag\": \"#1\"}' \\\n  POST \\\n  'https://api.example.com/items?q=it'\\''s' \\\n  'Content-Type:application/json'\n"
+        );
+
+        let powershell = generate(&curl, Language::PowerShell);
+        assert!(powershell.contains("    Uri = 'https://api.example.com/items?q=it''s'\n"));
+        assert!(powershell.contains("    Method = 'Post'\n"));
+        assert!(powershell.contains("    ContentType = 'application/json'\n"));
+        assert!(!powershell.contains("MaximumRedirection"));
+    }
+
+    #[test]
+    fn test_generate_notes() {
+        let mut curl = Curl::new_serializing();
+        curl.set_url("https://example.com");
+        curl.add_option(&AppOptions::Auth(AuthKind::Ntlm));
+        curl.add_option(&AppOptions::UnixSocket(String::from("/run/docker.sock")));
+        let go = generate(&curl, Language::Go);
+        assert!(go.starts_with(
+            "// Note: the request is sent over the unix socket /run/docker.sock\n// Note: NTLM auth has to be added by hand\npackage main"
+        ));
+        assert!(go.contains("return http.ErrUseLastResponse"));
+        let python = generate(&curl, Language::Python);
+        assert!(python.starts_with("# Note: the request"));
+        assert!(python.contains("allow_redirects=False,"));
+    }
+}
@@ -1,13 +1,24 @@
+use super::charset::{decode, detect_charset};
+use super::problem::{explain_curl_error, http_failure};
+use super::shell::{Argv, Shell};
+use super::summary::ResponseSummary;
 use super::ExecuteOption;
 use crate::database::db::DB;
-use crate::display::{menuopts::CURL, AppOptions, HeaderKind};
+use crate::display::{
+    menuopts::{CURL, WGET},
+    AppOptions, HeaderKind,
+};
 use curl::easy::{Auth, Easy2, Handler, List, WriteError};
+use encoding_rs::Encoding;
 use serde::{Deserialize, Serialize};
 use std::ops::{Deref, DerefMut};
 use std::{
     fmt::{Display, Formatter},
+    fs::File,
     io::{Read, Write},
+    path::PathBuf,
     str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering},
 };
 impl DerefMut for CurlHandler {
     fn deref_mut(&mut self) -> &mut Self::Target {
@@ -23,13 +34,132 @@ impl Deref for CurlHandler {
 
 #[derive(Debug)]
 pub struct CurlHandler(Easy2<Collector>);
-#[derive(Debug, Serialize, Deserialize, Eq, Clone, PartialEq)]
-pub struct Collector(Vec<u8>);
+
+// The response body and headers. Only the first `limit` bytes of the body are
+// kept in memory, the rest is written to a temporary file that is only read
+// back when the whole body is asked for
+#[derive(Debug, Default)]
+pub struct Collector {
+    body: Vec<u8>,
+    headers: Vec<u8>,
+    limit: Option<usize>,
+    spill: Option<(PathBuf, File)>,
+    size: usize,
+}
+
 impl Handler for Collector {
     fn write(&mut self, data: &[u8]) -> Result<usize, WriteError> {
-        self.0.extend_from_slice(data);
+        let room = self
+            .limit
+            .map_or(data.len(), |limit| limit.saturating_sub(self.body.len()));
+        let (kept, rest) = data.split_at(room.min(data.len()));
+        self.body.extend_from_slice(kept);
+        if !rest.is_empty() {
+            // returning less than we were given aborts the transfer
+            if self.spill_write(rest).is_err() {
+                return Ok(kept.len());
+            }
+        }
+        self.size += data.len();
         Ok(data.len())
     }
+    fn header(&mut self, data: &[u8]) -> bool {
+        // a new status line means we were redirected, we only keep the final set of headers
+        if data.starts_with(b"HTTP/") {
+            self.headers.clear();
+        }
+        self.headers.extend_from_slice(data);
+        true
+    }
+}
+
+impl Drop for Collector {
+    fn drop(&mut self) {
+        self.remove_spill();
+    }
+}
+
+// JSON bodies are pretty printed, unless only the start of the body was kept
+pub fn response_text(body: &[u8], truncated: bool, encoding: &'static Encoding) -> String {
+    let res = decode(body, encoding);
+    if truncated {
+        return res;
+    }
+    match serde_json::from_str::<serde_json::Value>(&res) {
+        Ok(json) => serde_json::to_string_pretty(&json).unwrap_or(res),
+        Err(_) => res,
+    }
+}
+
+impl Collector {
+    // Empty the buffers so the handle can be reused for another transfer
+    pub fn clear(&mut self) {
+        self.body.clear();
+        self.headers.clear();
+        self.size = 0;
+        self.remove_spill();
+    }
+
+    // bodies over this many bytes only keep their start in memory
+    pub fn set_limit(&mut self, limit: Option<usize>) {
+        self.limit = limit;
+    }
+
+    // The part of the body kept in memory
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+
+    pub fn headers(&self) -> &[u8] {
+        &self.headers
+    }
+
+    // How many bytes of body were received, kept in memory or not
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_truncated(&self) -> bool {
+        self.size > self.body.len()
+    }
+
+    // Writes the whole body, as it was received
+    pub fn write_body(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        writer.write_all(&self.body)?;
+        if let Some((ref path, _)) = self.spill {
+            std::io::copy(&mut File::open(path)?, writer)?;
+        }
+        Ok(())
+    }
+
+    // Reads the whole body into memory
+    pub fn read_body(&self) -> std::io::Result<Vec<u8>> {
+        let mut body = Vec::with_capacity(self.size);
+        self.write_body(&mut body)?;
+        Ok(body)
+    }
+
+    fn spill_write(&mut self, data: &[u8]) -> std::io::Result<()> {
+        if self.spill.is_none() {
+            static SPILLS: AtomicUsize = AtomicUsize::new(0);
+            let path = std::env::temp_dir().join(format!(
+                "cute-body-{}-{}",
+                std::process::id(),
+                SPILLS.fetch_add(1, Ordering::Relaxed)
+            ));
+            self.spill = Some((path.clone(), File::create(path)?));
+        }
+        match self.spill {
+            Some((_, ref mut file)) => file.write_all(data),
+            None => Ok(()),
+        }
+    }
+
+    fn remove_spill(&mut self) {
+        if let Some((path, _)) = self.spill.take() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -50,11 +180,18 @@ pub struct Curl {
     // Whether to save the (command, auth/key) to DB after execution
     save: (bool, bool),
     ser: bool,
+    // only this many bytes of the body are kept in memory, larger bodies are
+    // not pretty printed and the rest of them is kept in a temporary file
+    #[serde(skip)]
+    max_body_size: Option<usize>,
+    // decodes the response instead of the charset the server declared
+    #[serde(skip)]
+    charset: Option<&'static Encoding>,
 }
 
 impl Default for CurlHandler {
     fn default() -> Self {
-        Self(Easy2::new(Collector(Vec::new())))
+        Self(Easy2::new(Collector::default()))
     }
 }
 
@@ -143,6 +280,8 @@ impl Clone for Curl {
             outfile: self.outfile.clone(),
             save: self.save,
             ser: self.ser,
+            max_body_size: self.max_body_size,
+            charset: self.charset,
         }
     }
 }
@@ -190,6 +329,28 @@ impl AuthKind {
         }
     }
 }
+// A bearer token without the scheme, in case it was pasted along with it
+pub fn bearer_token(token: &str) -> &str {
+    let token = token.trim();
+    match token.get(..7) {
+        Some(scheme) if scheme.eq_ignore_ascii_case("bearer ") => token[7..].trim_start(),
+        _ => token,
+    }
+}
+
+pub fn bearer_header(token: &str) -> String {
+    format!("Authorization: Bearer {}", bearer_token(token))
+}
+
+// Cookies imported from other clients are kept as "name: value",
+// a Cookie header wants "name=value"
+pub fn cookie_pair(cookie: &str) -> String {
+    match cookie.contains('=') {
+        true => cookie.to_string(),
+        false => cookie.replacen(": ", "=", 1),
+    }
+}
+
 #[rustfmt::skip]
 impl Display for AuthKind {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
@@ -197,7 +358,7 @@ impl Display for AuthKind {
             AuthKind::None            => write!(f, "None"),
             AuthKind::Ntlm            => write!(f, "NTLM"),
             AuthKind::Basic(login)    => write!(f, "Basic: {}", login),
-            AuthKind::Bearer(token)   => write!(f, "Authorization: Bearer {}", token),
+            AuthKind::Bearer(token)   => write!(f, "{}", bearer_header(token)),
             AuthKind::Digest(login)   => write!(f, "Digest Auth: {}", login),
             AuthKind::AwsSigv4        => write!(f, "AWS SignatureV4"),
             AuthKind::Spnego          => write!(f, "SPNEGO Auth"),
@@ -220,6 +381,8 @@ impl Default for Curl {
             outfile: None,
             save: (false, false),
             ser: false,
+            max_body_size: None,
+            charset: None,
         }
     }
 }
@@ -257,21 +420,122 @@ impl Curl {
         self.resp.clone()
     }
 
-    pub fn build_command_string(&mut self) {
-        let mut cmd: Vec<String> = vec![self.cmd.clone()];
-        cmd.push(String::from("-X"));
-        cmd.push(self.method.to_string());
-        cmd.push(self.url.clone());
-        for flag in self.opts.iter() {
-            cmd.push(flag.get_curl_flag_value());
-        }
-        if self.headers.is_some() {
-            self.headers.as_ref().unwrap().iter().for_each(|h| {
-                cmd.push(String::from("-H"));
-                cmd.push(h.clone());
-            });
+    pub fn get_response_str(&self) -> Option<&str> {
+        self.resp.as_deref()
+    }
+
+    pub fn clear_response(&mut self) {
+        self.resp = None;
+    }
+
+    pub fn set_max_body_size(&mut self, size: Option<usize>) {
+        self.max_body_size = size;
+    }
+
+    // Whether only the start of the body is kept in memory
+    pub fn is_response_truncated(&self) -> bool {
+        self.curl.get_ref().is_truncated()
+    }
+
+    // The size of the body received, including what isn't kept in memory
+    pub fn get_response_size(&self) -> usize {
+        self.curl.get_ref().size()
+    }
+
+    // The whole body, read back from the temporary file if it was too large to keep
+    pub fn read_response_body(&self) -> std::io::Result<Vec<u8>> {
+        self.curl.get_ref().read_body()
+    }
+
+    // Only available after the request has been performed
+    pub fn get_response_code(&mut self) -> Option<u32> {
+        match self.curl.response_code() {
+            Ok(0) | Err(_) => None,
+            Ok(code) => Some(code),
         }
-        self.cmd = cmd.join(" ").trim().to_string();
+    }
+
+    // The raw body of the last response received, or its start if it was too large
+    pub fn get_response_bytes(&self) -> &[u8] {
+        self.curl.get_ref().body()
+    }
+
+    // The raw status line + headers of the last response received
+    pub fn get_response_headers(&self) -> String {
+        String::from_utf8_lossy(self.curl.get_ref().headers())
+            .trim_end()
+            .to_string()
+    }
+
+    // Only available after the request has been performed
+    pub fn get_response_summary(&mut self) -> ResponseSummary {
+        let duration = self.curl.total_time().unwrap_or_default();
+        let url = self.curl.effective_url().ok().flatten().map(String::from);
+        ResponseSummary::new(
+            self.get_response_code(),
+            &self.get_response_headers(),
+            duration,
+            self.get_response_size(),
+            url,
+        )
+    }
+
+    // Content-Type of the last response received, if the server sent one
+    pub fn get_response_content_type(&self) -> Option<String> {
+        self.get_response_headers()
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            // after redirects, the last response is the one shown
+            .rev()
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-type"))
+            .map(|(_, value)| value.trim().to_string())
+    }
+
+    // The encoding the response body is decoded with
+    pub fn get_response_charset(&self) -> &'static Encoding {
+        self.charset.unwrap_or_else(|| {
+            detect_charset(
+                self.get_response_content_type().as_deref(),
+                self.get_response_bytes(),
+            )
+        })
+    }
+
+    // Overrides the charset of the response and decodes it again, None goes
+    // back to the one the server declared
+    pub fn set_charset(&mut self, charset: Option<&'static Encoding>) {
+        self.charset = charset;
+        if !self.get_response_bytes().is_empty() {
+            self.resp = Some(response_text(
+                self.get_response_bytes(),
+                self.is_response_truncated(),
+                self.get_response_charset(),
+            ));
+        }
+    }
+
+    // The request as curl arguments. The options are the canonical list of
+    // what was set, self.headers only keeps the same headers for libcurl
+    pub fn get_command_argv(&self) -> Argv {
+        let mut argv = Argv::new(CURL);
+        argv.push(vec![String::from("-X"), self.method.to_string()]);
+        if !self.url.is_empty() {
+            argv.push(vec![self.url.clone()]);
+        }
+        for (i, opt) in self.opts.iter().enumerate() {
+            // a serializing command keeps its headers in the options twice, once from
+            // App::add_app_option and once from add_headers, libcurl sends them once
+            if matches!(opt, AppOptions::Headers(_)) && self.opts[..i].contains(opt) {
+                continue;
+            }
+            argv.push(opt.get_curl_args());
+        }
+        argv
+    }
+
+    // The command string that is saved with the request is written for a POSIX shell
+    pub fn build_command_string(&mut self) {
+        self.cmd = self.get_command_argv().render(Shell::Posix, false);
     }
 
     // this is only called after execution, we need to
@@ -283,6 +547,59 @@ impl Curl {
         self.cmd.clone()
     }
 
+    pub fn render_command(&self, shell: Shell, multiline: bool) -> String {
+        self.get_command_argv().render(shell, multiline)
+    }
+
+    // The same request as wget arguments, along with the options of the
+    // request that wget has no equivalent for
+    pub fn get_wget_argv(&self) -> (Argv, Vec<String>) {
+        let mut argv = Argv::new(WGET);
+        let mut unsupported = Vec::new();
+        let upload = self
+            .opts
+            .iter()
+            .any(|opt| matches!(opt, AppOptions::UploadFile(_)));
+        match self.method {
+            // curl uploads a file with PUT unless a method is given
+            Method::Get if upload => argv.push(vec![String::from("--method=PUT")]),
+            Method::Get => {}
+            ref method => argv.push(vec![format!("--method={}", method)]),
+        }
+        let follow = self
+            .opts
+            .iter()
+            .any(|opt| matches!(opt, AppOptions::FollowRedirects));
+        for opt in self.opts.iter() {
+            match opt {
+                // wget follows redirects unless told otherwise
+                AppOptions::MaxRedirects(_) if !follow => {}
+                opt => match opt.get_wget_flag_value() {
+                    Ok(flags) => flags.into_iter().for_each(|flag| argv.push(vec![flag])),
+                    Err(option) => unsupported.push(option),
+                },
+            }
+        }
+        if !follow {
+            argv.push(vec![String::from("--max-redirect=0")]);
+        }
+        // curl writes the response to stdout, wget to a file named after the URL
+        if !self
+            .opts
+            .iter()
+            .any(|opt| matches!(opt, AppOptions::Outfile(_)))
+        {
+            argv.push(vec![String::from("--output-document=-")]);
+        }
+        argv.push(vec![self.url.clone()]);
+        (argv, unsupported)
+    }
+
+    pub fn build_wget_command(&self, shell: Shell, multiline: bool) -> (String, Vec<String>) {
+        let (argv, unsupported) = self.get_wget_argv();
+        (argv.render(shell, multiline), unsupported)
+    }
+
     pub fn set_outfile(&mut self, outfile: &str) {
         self.outfile = Some(String::from(outfile));
     }
@@ -328,20 +645,48 @@ impl Curl {
         self.upload_file.clone()
     }
 
+    // The headers sent with the request as "Name: value", including a bearer token
+    pub fn get_request_headers(&self) -> Vec<String> {
+        let mut headers: Vec<String> = Vec::new();
+        if let AuthKind::Bearer(ref token) = self.auth {
+            headers.push(bearer_header(token));
+        }
+        for header in self.headers.iter().flatten() {
+            if !header.is_empty() && !headers.contains(header) {
+                headers.push(header.clone());
+            }
+        }
+        headers
+    }
+
+    pub fn get_request_body(&self) -> Option<String> {
+        self.opts.iter().rev().find_map(|opt| match opt {
+            AppOptions::RequestBody(body) => Some(body.clone()),
+            _ => None,
+        })
+    }
+
+    pub fn get_auth(&self) -> &AuthKind {
+        &self.auth
+    }
+
+    // Cookies added to the request as "name=value"
+    pub fn get_cookies(&self) -> Vec<String> {
+        self.opts
+            .iter()
+            .filter_map(|opt| match opt {
+                AppOptions::NewCookie(cookie) => Some(cookie.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
     #[rustfmt::skip]
     pub fn execute(&mut self, mut db: Option<Box<&mut DB>>) -> Result<(), String> {
-        let mut list = List::new();
         curl::init();
-        // we do this again because if it's a patch | put and there's a
-        // body, it will default to post
-        self.apply_method(); 
-        let mut has_headers = self.handle_auth_exec(&mut list);
-        if let Some(ref headers) = self.headers {
-            headers
-                .iter()
-                .for_each(|h| list.append(h.as_str()).unwrap());
-            has_headers = true;
-        }
+        // a response from an earlier run of the handle isn't kept
+        self.curl.get_mut().clear();
+        self.curl.get_mut().set_limit(self.max_body_size);
         if self.will_save_command() {
             if let Some(ref mut db) = db {
                 self.build_command_string();
@@ -364,12 +709,7 @@ impl Curl {
                 }
             }
         }
-        // Append headers if needed
-        if has_headers {
-            self.curl
-                .http_headers(list)
-                .map_err(|e| format!("Error setting headers: {:?}", e))?;
-        }
+        self.prepare_request()?;
 
         // Upload file if specified
         if let Some(ref upload_file) = self.upload_file {
@@ -388,7 +728,7 @@ impl Curl {
                 // Handle upload errors
                 self.curl
                     .perform()
-                    .map_err(|err| format!("Error making connection: {:?}", err))?;
+                    .map_err(|err| explain_curl_error(&err))?;
                 self.curl
                     .send(buff.as_slice())
                     .map_err(|e| format!("Error with upload: {}", e))?;
@@ -396,15 +736,50 @@ impl Curl {
         }
 
         // Perform the main request
-        self.curl
-            .perform()
-            .map_err(|err| format!("Error: {:?}", err))?;
-        let contents = self.curl.get_ref();
-        let res = String::from_utf8_lossy(&contents.0);
-        if let Ok(json) = serde_json::from_str::<serde_json::Value>(&res) {
-            self.resp = Some(serde_json::to_string_pretty(&json).unwrap());
-        } else {
-            self.resp = Some(res.to_string());
+        if let Err(err) = self.curl.perform() {
+            return Err(match err.is_http_returned_error() {
+                true => format!(
+                    "{}\n\nThe body was not read because 'Fail on error' is set",
+                    http_failure(&self.get_response_summary(), "")
+                ),
+                false => explain_curl_error(&err),
+            });
+        }
+        self.resp = Some(response_text(
+            self.get_response_bytes(),
+            self.is_response_truncated(),
+            self.get_response_charset(),
+        ));
+        Ok(())
+    }
+
+    // Sets up the request without performing it and hands back the libcurl handle,
+    // so it can be driven elsewhere (e.g. by a multi handle when benchmarking)
+    pub fn into_handle(mut self) -> Result<Easy2<Collector>, String> {
+        self.prepare_request()?;
+        self.curl.get_mut().set_limit(self.max_body_size);
+        Ok(self.curl.0)
+    }
+
+    // Applies the method, the auth and the header list to the handle, right
+    // before it's performed
+    fn prepare_request(&mut self) -> Result<(), String> {
+        let mut list = List::new();
+        // we do this again because if it's a patch | put and there's a
+        // body, it will default to post
+        self.apply_method();
+        let mut has_headers = self.handle_auth_exec(&mut list);
+        if let Some(ref headers) = self.headers {
+            for header in headers.iter() {
+                list.append(header)
+                    .map_err(|e| format!("Error setting headers: {:?}", e))?;
+            }
+            has_headers = true;
+        }
+        if has_headers {
+            self.curl
+                .http_headers(list)
+                .map_err(|e| format!("Error setting headers: {:?}", e))?;
         }
         Ok(())
     }
@@ -465,7 +840,7 @@ impl Curl {
 
     pub fn set_fail_on_error(&mut self, fail: bool) {
         if self.ser {
-        self.opts.push(AppOptions::FailOnError);
+            self.opts.push(AppOptions::FailOnError);
         }
         self.curl.fail_on_error(fail).unwrap();
     }
@@ -496,8 +871,8 @@ impl Curl {
         if self.ser {
             self.opts.push(AppOptions::Headers(headers.to_string()));
         }
-        if self.headers.is_some() {
-            self.headers.as_mut().unwrap().push(headers.to_string());
+        if let Some(ref mut hdrs) = self.headers {
+            hdrs.push(headers.to_string());
         } else {
             self.headers = Some(vec![headers.to_string()]);
         }
@@ -512,11 +887,8 @@ impl Curl {
     }
 
     pub fn remove_headers(&mut self, headers: &str) {
-        if self.headers.is_some() {
-            self.headers
-                .as_mut()
-                .unwrap()
-                .retain(|x| !headers.contains(x));
+        if let Some(ref mut hdrs) = self.headers {
+            hdrs.retain(|x| !headers.contains(x.as_str()));
         }
     }
     pub fn match_wildcard(&mut self, opt: bool) {
@@ -567,8 +939,14 @@ impl Curl {
         if self.ser {
             self.opts.push(AppOptions::RequestBody(body.to_string()));
         }
-        if self.opts.iter().any(|x| std::mem::discriminant(x) == std::mem::discriminant(&AppOptions::RequestBody(body.to_string()))) {
-            self.opts.retain(|x| std::mem::discriminant(x) != std::mem::discriminant(&AppOptions::RequestBody(body.to_string())));
+        if self.opts.iter().any(|x| {
+            std::mem::discriminant(x)
+                == std::mem::discriminant(&AppOptions::RequestBody(body.to_string()))
+        }) {
+            self.opts.retain(|x| {
+                std::mem::discriminant(x)
+                    != std::mem::discriminant(&AppOptions::RequestBody(body.to_string()))
+            });
         }
         self.opts.push(AppOptions::RequestBody(body.to_string()));
         self.curl
@@ -610,11 +988,14 @@ impl Curl {
                 };
                 let mut writer = std::io::BufWriter::new(&mut file);
 
-                if let Some(resp) = &self.resp {
-                    if let Err(e) = writer.write_all(resp.as_bytes()) {
-                        eprintln!("Error writing to file: {:?}", e);
-                        return Err(e);
-                    }
+                // the body is written as it was received, not as it is displayed
+                let written = match self.get_response_bytes().is_empty() {
+                    true => writer.write_all(self.resp.as_deref().unwrap_or_default().as_bytes()),
+                    false => self.curl.get_ref().write_body(&mut writer),
+                };
+                if let Err(e) = written.and_then(|_| writer.flush()) {
+                    eprintln!("Error writing to file: {:?}", e);
+                    return Err(e);
                 }
 
                 Ok(())
@@ -654,7 +1035,8 @@ impl Curl {
 
     pub fn set_basic_auth(&mut self, login: &str) {
         if self.ser {
-            self.opts.push(AppOptions::Auth(AuthKind::Basic(login.to_string())));
+            self.opts
+                .push(AppOptions::Auth(AuthKind::Basic(login.to_string())));
         }
         self.auth = AuthKind::Basic(String::from(login));
     }
@@ -666,7 +1048,8 @@ impl Curl {
 
     pub fn set_digest_auth(&mut self, login: &str) {
         if self.ser {
-            self.opts.push(AppOptions::Auth(AuthKind::Digest(login.to_string())));
+            self.opts
+                .push(AppOptions::Auth(AuthKind::Digest(login.to_string())));
         }
         self.auth = AuthKind::Digest(String::from(login));
     }
@@ -738,12 +1121,12 @@ impl Curl {
                     .username(login.split(':').next().unwrap())
                     .unwrap();
                 self.curl
-                    .password(login.split(':').last().unwrap())
+                    .password(login.split(':').next_back().unwrap())
                     .unwrap();
                 let _ = self.curl.http_auth(Auth::new().basic(true));
             }
             AuthKind::Bearer(ref token) => {
-                list.append(&format!("Authorization: {token}")).unwrap();
+                list.append(&bearer_header(token)).unwrap();
                 return true;
             }
             AuthKind::Digest(login) => {
@@ -751,7 +1134,7 @@ impl Curl {
                     .username(login.split(':').next().unwrap())
                     .unwrap();
                 self.curl
-                    .password(login.split(':').last().unwrap())
+                    .password(login.split(':').next_back().unwrap())
                     .unwrap();
                 let _ = self.curl.http_auth(Auth::new().digest(true));
             }
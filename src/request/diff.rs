@@ -0,0 +1,277 @@
+use crate::database::db::HistoryEntry;
+use serde_json::{Map, Value};
+use similar::{DiffOp, TextDiff};
+use std::collections::{BTreeMap, BTreeSet};
+
+// One side of a comparison
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResponseSnapshot {
+    pub label: String,
+    // raw status line + headers
+    pub headers: String,
+    pub body: String,
+}
+
+impl From<&HistoryEntry> for ResponseSnapshot {
+    fn from(entry: &HistoryEntry) -> Self {
+        Self {
+            label: format!(
+                "#{} {} {} ({})",
+                entry.get_id(),
+                entry.method,
+                entry.url,
+                entry.get_age()
+            ),
+            headers: entry.headers.clone(),
+            body: entry.body.clone(),
+        }
+    }
+}
+
+impl ResponseSnapshot {
+    // A response saved as an example, the status line and headers are read
+    // too when it was saved with them (e.g. with `curl -i`)
+    pub fn from_example(label: &str, text: &str) -> Self {
+        let split = match text.starts_with("HTTP/") {
+            true => ["\r\n\r\n", "\n\n"]
+                .iter()
+                .find_map(|sep| text.split_once(sep)),
+            false => None,
+        };
+        let (headers, body) = split.unwrap_or(("", text));
+        Self {
+            label: label.to_string(),
+            headers: headers.to_string(),
+            body: body.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    Same,
+    Changed,
+    Removed,
+    Added,
+}
+
+// A row of the side by side view, with line numbers on either side
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffRow {
+    pub kind: DiffKind,
+    pub left: Option<(usize, String)>,
+    pub right: Option<(usize, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeaderChange {
+    pub name: String,
+    pub left: Option<String>,
+    pub right: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResponseDiff {
+    pub left_label: String,
+    pub right_label: String,
+    pub headers: Vec<HeaderChange>,
+    pub rows: Vec<DiffRow>,
+}
+
+impl ResponseDiff {
+    pub fn new(left: &ResponseSnapshot, right: &ResponseSnapshot) -> Self {
+        Self {
+            left_label: left.label.clone(),
+            right_label: right.label.clone(),
+            headers: header_changes(&left.headers, &right.headers),
+            rows: diff_rows(&normalize_body(&left.body), &normalize_body(&right.body)),
+        }
+    }
+
+    pub fn is_identical(&self) -> bool {
+        self.headers.is_empty() && self.rows.iter().all(|row| row.kind == DiffKind::Same)
+    }
+}
+
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(k, v)| (k, sort_keys(v)))
+                .collect::<BTreeMap<_, _>>()
+                .into_iter()
+                .collect::<Map<_, _>>(),
+        ),
+        Value::Array(values) => Value::Array(values.into_iter().map(sort_keys).collect()),
+        value => value,
+    }
+}
+
+// JSON bodies are pretty printed with sorted keys, so key order and
+// whitespace don't show up as differences
+pub fn normalize_body(body: &str) -> String {
+    match serde_json::from_str::<Value>(body) {
+        Ok(json) => serde_json::to_string_pretty(&sort_keys(json)).unwrap_or(body.to_string()),
+        Err(_) => body.to_string(),
+    }
+}
+
+// Header names are case insensitive, the status line is kept under ":status"
+fn parse_headers(raw: &str) -> BTreeMap<String, String> {
+    let mut headers = BTreeMap::new();
+    for line in raw.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        if line.starts_with("HTTP/") {
+            headers.insert(String::from(":status"), line.to_string());
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers
+                .entry(name.trim().to_lowercase())
+                .and_modify(|v: &mut String| {
+                    v.push_str(", ");
+                    v.push_str(value.trim());
+                })
+                .or_insert(value.trim().to_string());
+        }
+    }
+    headers
+}
+
+pub fn header_changes(left: &str, right: &str) -> Vec<HeaderChange> {
+    let (left, right) = (parse_headers(left), parse_headers(right));
+    left.keys()
+        .chain(right.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .filter(|name| left.get(*name) != right.get(*name))
+        .map(|name| HeaderChange {
+            name: name.clone(),
+            left: left.get(name).cloned(),
+            right: right.get(name).cloned(),
+        })
+        .collect()
+}
+
+pub fn diff_rows(left: &str, right: &str) -> Vec<DiffRow> {
+    let diff = TextDiff::from_lines(left, right);
+    let (old, new) = (diff.old_slices(), diff.new_slices());
+    let line = |lines: &[&str], i: usize| {
+        Some((i + 1, lines[i].trim_end_matches(['\r', '\n']).to_string()))
+    };
+    let mut rows = Vec::new();
+    for op in diff.ops() {
+        match *op {
+            DiffOp::Equal {
+                old_index,
+                new_index,
+                len,
+            } => rows.extend((0..len).map(|i| DiffRow {
+                kind: DiffKind::Same,
+                left: line(old, old_index + i),
+                right: line(new, new_index + i),
+            })),
+            DiffOp::Delete {
+                old_index, old_len, ..
+            } => rows.extend((0..old_len).map(|i| DiffRow {
+                kind: DiffKind::Removed,
+                left: line(old, old_index + i),
+                right: None,
+            })),
+            DiffOp::Insert {
+                new_index, new_len, ..
+            } => rows.extend((0..new_len).map(|i| DiffRow {
+                kind: DiffKind::Added,
+                left: None,
+                right: line(new, new_index + i),
+            })),
+            DiffOp::Replace {
+                old_index,
+                old_len,
+                new_index,
+                new_len,
+            } => rows.extend((0..old_len.max(new_len)).map(|i| DiffRow {
+                kind: DiffKind::Changed,
+                left: (i < old_len).then(|| line(old, old_index + i)).flatten(),
+                right: (i < new_len).then(|| line(new, new_index + i)).flatten(),
+            })),
+        }
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_body() {
+        assert_eq!(
+            normalize_body(r#"{"b": 1, "a": {"d": [1], "c": null}}"#),
+            "{\n  \"a\": {\n    \"c\": null,\n    \"d\": [\n      1\n    ]\n  },\n  \"b\": 1\n}"
+        );
+        assert_eq!(normalize_body("plain text"), "plain text");
+    }
+
+    #[test]
+    fn test_snapshot_from_example() {
+        let example = ResponseSnapshot::from_example(
+            "example",
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\r\n{\"id\": 1}",
+        );
+        assert_eq!(
+            example.headers,
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json"
+        );
+        assert_eq!(example.body, "{\"id\": 1}");
+        let body = ResponseSnapshot::from_example("example", "{\"id\": 1}\n\n");
+        assert!(body.headers.is_empty());
+        assert_eq!(body.body, "{\"id\": 1}\n\n");
+    }
+
+    #[test]
+    fn test_response_diff() {
+        let left = ResponseSnapshot {
+            label: String::from("staging"),
+            headers: String::from(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nX-Env: staging",
+            ),
+            body: String::from(r#"{"id": 1, "name": "a", "tags": []}"#),
+        };
+        let right = ResponseSnapshot {
+            label: String::from("prod"),
+            headers: String::from("HTTP/1.1 200 OK\r\ncontent-type: application/json\r\nETag: 1"),
+            body: String::from(r#"{"tags": [], "name": "b", "id": 1}"#),
+        };
+        let diff = ResponseDiff::new(&left, &right);
+        assert_eq!(
+            diff.headers,
+            vec![
+                HeaderChange {
+                    name: String::from("etag"),
+                    left: None,
+                    right: Some(String::from("1")),
+                },
+                HeaderChange {
+                    name: String::from("x-env"),
+                    left: Some(String::from("staging")),
+                    right: None,
+                },
+            ]
+        );
+        let changed = diff
+            .rows
+            .iter()
+            .filter(|row| row.kind != DiffKind::Same)
+            .collect::<Vec<_>>();
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].kind, DiffKind::Changed);
+        assert_eq!(
+            changed[0].left,
+            Some((3, String::from("  \"name\": \"a\",")))
+        );
+        assert_eq!(
+            changed[0].right,
+            Some((3, String::from("  \"name\": \"b\",")))
+        );
+        assert!(!diff.is_identical());
+        assert!(ResponseDiff::new(&left, &left).is_identical());
+    }
+}
@@ -0,0 +1,525 @@
+use super::charset::decode;
+use crate::display::viewer::Syntax;
+use encoding_rs::Encoding;
+use serde_json::{Map, Number, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Formatter {
+    Text,
+    Json,
+    Xml,
+    Html,
+    Form,
+    MessagePack,
+    Cbor,
+    Csv,
+}
+
+// Content types handled by each formatter, entries starting with '+'
+// match structured syntax suffixes like `application/problem+json`
+const FORMATTERS: [(&str, Formatter); 18] = [
+    ("application/json", Formatter::Json),
+    ("text/json", Formatter::Json),
+    ("+json", Formatter::Json),
+    ("text/html", Formatter::Html),
+    ("application/xhtml+xml", Formatter::Html),
+    ("application/xml", Formatter::Xml),
+    ("text/xml", Formatter::Xml),
+    ("+xml", Formatter::Xml),
+    ("application/x-www-form-urlencoded", Formatter::Form),
+    ("application/msgpack", Formatter::MessagePack),
+    ("application/x-msgpack", Formatter::MessagePack),
+    ("application/vnd.msgpack", Formatter::MessagePack),
+    ("application/cbor", Formatter::Cbor),
+    ("+cbor", Formatter::Cbor),
+    ("text/csv", Formatter::Csv),
+    ("application/csv", Formatter::Csv),
+    ("text/plain", Formatter::Text),
+    ("application/octet-stream", Formatter::Text),
+];
+
+// The order formatters are cycled through when overriding the Content-Type
+const ALL: [Formatter; 8] = [
+    Formatter::Text,
+    Formatter::Json,
+    Formatter::Xml,
+    Formatter::Html,
+    Formatter::Form,
+    Formatter::MessagePack,
+    Formatter::Cbor,
+    Formatter::Csv,
+];
+
+// HTML elements that never have a closing tag
+const VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+// HTML elements whose content is kept as it was sent
+const RAW_ELEMENTS: [&str; 4] = ["script", "style", "pre", "textarea"];
+
+impl Formatter {
+    pub fn for_content_type(content_type: &str) -> Option<Self> {
+        let content_type = content_type.to_lowercase();
+        let mime = content_type.split(';').next().unwrap_or_default().trim();
+        FORMATTERS
+            .iter()
+            .find(|(pattern, _)| match pattern.starts_with('+') {
+                true => mime.ends_with(pattern),
+                false => mime == *pattern,
+            })
+            .map(|(_, formatter)| *formatter)
+    }
+
+    // Picks the formatter from the response Content-Type, falling back to
+    // sniffing the body when the server didn't send a known one
+    pub fn detect(content_type: Option<&str>, body: &[u8]) -> Self {
+        match content_type.and_then(Self::for_content_type) {
+            Some(Formatter::Text) | None => {}
+            Some(formatter) => return formatter,
+        }
+        let text = String::from_utf8_lossy(body);
+        match Syntax::detect(None, &text) {
+            Syntax::Json if serde_json::from_str::<Value>(&text).is_ok() => Formatter::Json,
+            Syntax::Html => Formatter::Html,
+            Syntax::Xml => Formatter::Xml,
+            _ => Formatter::Text,
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        let index = ALL.iter().position(|f| f == self).unwrap_or_default();
+        ALL[(index + 1) % ALL.len()]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Formatter::Text => "Text",
+            Formatter::Json => "JSON",
+            Formatter::Xml => "XML",
+            Formatter::Html => "HTML",
+            Formatter::Form => "Form",
+            Formatter::MessagePack => "MessagePack",
+            Formatter::Cbor => "CBOR",
+            Formatter::Csv => "CSV",
+        }
+    }
+
+    // How the formatted output is highlighted
+    pub fn syntax(&self) -> Syntax {
+        match self {
+            Formatter::Json | Formatter::Form | Formatter::MessagePack | Formatter::Cbor => {
+                Syntax::Json
+            }
+            Formatter::Xml => Syntax::Xml,
+            Formatter::Html => Syntax::Html,
+            Formatter::Text | Formatter::Csv => Syntax::Plain,
+        }
+    }
+
+    // Text formats are decoded with `encoding` first, binary ones are read as is
+    pub fn format(&self, body: &[u8], encoding: &'static Encoding) -> Result<String, String> {
+        let text = || decode(body, encoding);
+        let pretty = |value: Value| serde_json::to_string_pretty(&value).map_err(|e| e.to_string());
+        match self {
+            Formatter::Text => Ok(text()),
+            Formatter::Json => pretty(serde_json::from_str(&text()).map_err(|e| e.to_string())?),
+            Formatter::Xml => Ok(indent_markup(&text(), false)),
+            Formatter::Html => Ok(indent_markup(&text(), true)),
+            Formatter::Form => pretty(decode_form(&text())),
+            Formatter::MessagePack => {
+                let value = rmpv::decode::read_value(&mut &body[..]).map_err(|e| e.to_string())?;
+                pretty(msgpack_to_json(value))
+            }
+            Formatter::Cbor => {
+                let value: ciborium::Value =
+                    ciborium::de::from_reader(body).map_err(|e| e.to_string())?;
+                pretty(cbor_to_json(value))
+            }
+            Formatter::Csv => Ok(csv_table(&text())),
+        }
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn float(f: f64) -> Value {
+    Number::from_f64(f)
+        .map(Value::Number)
+        .unwrap_or(Value::Null)
+}
+
+fn msgpack_to_json(value: rmpv::Value) -> Value {
+    match value {
+        rmpv::Value::Nil => Value::Null,
+        rmpv::Value::Boolean(b) => Value::Bool(b),
+        rmpv::Value::Integer(i) => match (i.as_i64(), i.as_u64()) {
+            (Some(i), _) => i.into(),
+            (_, Some(u)) => u.into(),
+            _ => Value::Null,
+        },
+        rmpv::Value::F32(f) => float(f as f64),
+        rmpv::Value::F64(f) => float(f),
+        rmpv::Value::String(s) => match s.as_str() {
+            Some(s) => Value::String(s.to_string()),
+            None => Value::String(String::from_utf8_lossy(s.as_bytes()).to_string()),
+        },
+        rmpv::Value::Binary(b) => Value::String(hex(&b)),
+        rmpv::Value::Array(items) => Value::Array(items.into_iter().map(msgpack_to_json).collect()),
+        rmpv::Value::Map(pairs) => Value::Object(
+            pairs
+                .into_iter()
+                .map(|(key, value)| {
+                    let key = match key {
+                        rmpv::Value::String(ref s) if s.as_str().is_some() => {
+                            s.as_str().unwrap_or_default().to_string()
+                        }
+                        key => key.to_string(),
+                    };
+                    (key, msgpack_to_json(value))
+                })
+                .collect(),
+        ),
+        rmpv::Value::Ext(kind, data) => serde_json::json!({ "ext": kind, "data": hex(&data) }),
+    }
+}
+
+fn cbor_to_json(value: ciborium::Value) -> Value {
+    match value {
+        ciborium::Value::Null => Value::Null,
+        ciborium::Value::Bool(b) => Value::Bool(b),
+        ciborium::Value::Integer(i) => {
+            let i = i128::from(i);
+            match (i64::try_from(i), u64::try_from(i)) {
+                (Ok(i), _) => i.into(),
+                (_, Ok(u)) => u.into(),
+                _ => Value::String(i.to_string()),
+            }
+        }
+        ciborium::Value::Float(f) => float(f),
+        ciborium::Value::Text(s) => Value::String(s),
+        ciborium::Value::Bytes(b) => Value::String(hex(&b)),
+        ciborium::Value::Tag(tag, value) => {
+            serde_json::json!({ "tag": tag, "value": cbor_to_json(*value) })
+        }
+        ciborium::Value::Array(items) => {
+            Value::Array(items.into_iter().map(cbor_to_json).collect())
+        }
+        ciborium::Value::Map(pairs) => Value::Object(
+            pairs
+                .into_iter()
+                .map(|(key, value)| {
+                    let key = match cbor_to_json(key) {
+                        Value::String(s) => s,
+                        key => key.to_string(),
+                    };
+                    (key, cbor_to_json(value))
+                })
+                .collect(),
+        ),
+        _ => Value::Null,
+    }
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let byte = std::str::from_utf8(&bytes[i + 1..i + 3])
+                    .ok()
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                match byte {
+                    Some(byte) => {
+                        out.push(byte);
+                        i += 2;
+                    }
+                    None => out.push(b'%'),
+                }
+            }
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+// Decodes `a=1&b=2&b=3` into an object, repeated keys become arrays
+fn decode_form(body: &str) -> Value {
+    let mut map = Map::new();
+    for pair in body.trim().split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let (key, value) = (percent_decode(key), Value::String(percent_decode(value)));
+        match map.get_mut(&key) {
+            Some(Value::Array(values)) => values.push(value),
+            Some(existing) => *existing = Value::Array(vec![existing.take(), value]),
+            None => {
+                map.insert(key, value);
+            }
+        }
+    }
+    Value::Object(map)
+}
+
+fn tag_name(tag: &str) -> String {
+    tag.trim_start_matches(['<', '/'])
+        .split(|c: char| c.is_whitespace() || c == '>' || c == '/')
+        .next()
+        .unwrap_or_default()
+        .to_lowercase()
+}
+
+// Length of the tag at the start of `s`, ignoring '>' inside quoted attributes
+fn tag_len(s: &str) -> usize {
+    let terminator = match s {
+        s if s.starts_with("<!--") => Some("-->"),
+        s if s.starts_with("<![CDATA[") => Some("]]>"),
+        _ => None,
+    };
+    if let Some(end) = terminator {
+        return s.find(end).map(|i| i + end.len()).unwrap_or(s.len());
+    }
+    let mut quote = None;
+    for (i, c) in s.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '>') => return i + 1,
+            _ => {}
+        }
+    }
+    s.len()
+}
+
+// Byte offset of the first ASCII case-insensitive match, tag names are ASCII
+// so the offset is always a char boundary of the original text
+fn find_ignore_case(text: &str, pattern: &str) -> Option<usize> {
+    text.as_bytes()
+        .windows(pattern.len())
+        .position(|window| window.eq_ignore_ascii_case(pattern.as_bytes()))
+}
+
+// Puts every element on its own line, indented by depth. Elements that only
+// contain text are kept on one line
+fn indent_markup(text: &str, html: bool) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut depth = 0;
+    let mut rest = text.trim();
+    let mut push = |depth: usize, line: &str| lines.push(format!("{}{}", "  ".repeat(depth), line));
+    while !rest.is_empty() {
+        if !rest.starts_with('<') {
+            let end = rest.find('<').unwrap_or(rest.len());
+            for line in rest[..end].lines().map(str::trim).filter(|l| !l.is_empty()) {
+                push(depth, line);
+            }
+            rest = &rest[end..];
+            continue;
+        }
+        let tag = &rest[..tag_len(rest)];
+        rest = &rest[tag.len()..];
+        let name = tag_name(tag);
+        if tag.starts_with("</") {
+            depth = depth.saturating_sub(1);
+            push(depth, tag);
+        } else if tag.starts_with("<!")
+            || tag.starts_with("<?")
+            || tag.ends_with("/>")
+            || (html && VOID_ELEMENTS.contains(&name.as_str()))
+        {
+            push(depth, tag);
+        } else {
+            let close = format!("</{}", name);
+            let content_end = find_ignore_case(rest, &close);
+            if let Some(end) = content_end {
+                let content = &rest[..end];
+                let raw = html && RAW_ELEMENTS.contains(&name.as_str());
+                if raw || !content.contains('<') {
+                    let close_len = tag_len(&rest[end..]);
+                    let closing = &rest[end..end + close_len];
+                    match content.trim().lines().count() {
+                        0 | 1 => push(depth, &format!("{}{}{}", tag, content.trim(), closing)),
+                        _ => {
+                            push(depth, tag);
+                            for line in content.trim().lines() {
+                                push(depth + 1, line.trim_end());
+                            }
+                            push(depth, closing);
+                        }
+                    }
+                    rest = &rest[end + close_len..];
+                    continue;
+                }
+            }
+            push(depth, tag);
+            depth += 1;
+        }
+        rest = rest.trim_start();
+    }
+    lines.join("\n")
+}
+
+fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            (true, '"') => quoted = false,
+            (true, c) => field.push(c),
+            (false, '"') => quoted = true,
+            (false, ',') => record.push(std::mem::take(&mut field)),
+            (false, '\r') => {}
+            (false, '\n') => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            (false, c) => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records.retain(|r| !(r.len() == 1 && r[0].is_empty()));
+    records
+}
+
+// Lines up the columns of a CSV body, with a rule under the header row
+fn csv_table(text: &str) -> String {
+    let records = parse_csv(text);
+    let columns = records.iter().map(Vec::len).max().unwrap_or_default();
+    let widths: Vec<usize> = (0..columns)
+        .map(|col| {
+            records
+                .iter()
+                .filter_map(|r| r.get(col))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+    let mut lines = Vec::new();
+    for (i, record) in records.iter().enumerate() {
+        let cells: Vec<String> = widths
+            .iter()
+            .enumerate()
+            .map(|(col, width)| {
+                let cell = record.get(col).map(String::as_str).unwrap_or_default();
+                format!("{:<width$}", cell.replace('\n', " "), width = width)
+            })
+            .collect();
+        lines.push(cells.join(" | ").trim_end().to_string());
+        if i == 0 {
+            let rule: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+            lines.push(rule.join("-+-"));
+        }
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use encoding_rs::UTF_8;
+
+    #[test]
+    fn test_for_content_type() {
+        let detect = |ct| Formatter::detect(Some(ct), b"");
+        assert_eq!(detect("application/json; charset=utf-8"), Formatter::Json);
+        assert_eq!(detect("application/problem+json"), Formatter::Json);
+        assert_eq!(detect("application/xhtml+xml"), Formatter::Html);
+        assert_eq!(detect("application/atom+xml"), Formatter::Xml);
+        assert_eq!(detect("application/x-msgpack"), Formatter::MessagePack);
+        assert_eq!(detect("text/CSV"), Formatter::Csv);
+        assert_eq!(detect("image/png"), Formatter::Text);
+        assert_eq!(
+            Formatter::detect(Some("text/plain"), b"{\"a\": 1}"),
+            Formatter::Json
+        );
+        assert_eq!(Formatter::detect(None, b"<html></html>"), Formatter::Html);
+        assert_eq!(Formatter::Csv.next(), Formatter::Text);
+    }
+
+    #[test]
+    fn test_format_markup() {
+        let xml = "<?xml version=\"1.0\"?><a><b x=\"1>2\">text</b><c/><d><e>1</e></d></a>";
+        assert_eq!(
+            Formatter::Xml.format(xml.as_bytes(), UTF_8).unwrap(),
+            "<?xml version=\"1.0\"?>\n<a>\n  <b x=\"1>2\">text</b>\n  <c/>\n  <d>\n    <e>1</e>\n  </d>\n</a>"
+        );
+        let html = "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><script>if (a < b) {}</script></head><body><p>hi<br>there</p></body></html>";
+        assert_eq!(
+            Formatter::Html.format(html.as_bytes(), UTF_8).unwrap(),
+            "<!DOCTYPE html>\n<html>\n  <head>\n    <meta charset=\"utf-8\">\n    <script>if (a < b) {}</script>\n  </head>\n  <body>\n    <p>\n      hi\n      <br>\n      there\n    </p>\n  </body>\n</html>"
+        );
+    }
+
+    #[test]
+    fn test_format_markup_non_ascii() {
+        // lowercasing İ changes its length, the closing tag must be found in the original
+        assert_eq!(indent_markup("<p>İİİİİ</p>é", true), "<p>İİİİİ</p>\né");
+        assert_eq!(
+            indent_markup("<DIV><P>Ünïcödé</p></div>", true),
+            "<DIV>\n  <P>Ünïcödé</p>\n</div>"
+        );
+    }
+
+    #[test]
+    fn test_format_form() {
+        let body = b"name=Jane+Doe&tag=a&tag=b%26c&empty";
+        let formatted = Formatter::Form.format(body, UTF_8).unwrap();
+        let value: Value = serde_json::from_str(&formatted).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({"name": "Jane Doe", "tag": ["a", "b&c"], "empty": ""})
+        );
+    }
+
+    #[test]
+    fn test_format_binary() {
+        let expected = serde_json::json!({"id": 7, "ok": true, "tags": ["x"], "raw": "0102"});
+        let msgpack = rmpv::Value::Map(vec![
+            ("id".into(), 7.into()),
+            ("ok".into(), true.into()),
+            ("tags".into(), rmpv::Value::Array(vec!["x".into()])),
+            ("raw".into(), rmpv::Value::Binary(vec![1, 2])),
+        ]);
+        let mut bytes = Vec::new();
+        rmpv::encode::write_value(&mut bytes, &msgpack).unwrap();
+        let formatted = Formatter::MessagePack.format(&bytes, UTF_8).unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&formatted).unwrap(), expected);
+
+        let cbor = ciborium::Value::Map(vec![
+            ("id".into(), 7.into()),
+            ("ok".into(), true.into()),
+            ("tags".into(), ciborium::Value::Array(vec!["x".into()])),
+            ("raw".into(), ciborium::Value::Bytes(vec![1, 2])),
+        ]);
+        let mut bytes = Vec::new();
+        ciborium::ser::into_writer(&cbor, &mut bytes).unwrap();
+        let formatted = Formatter::Cbor.format(&bytes, UTF_8).unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&formatted).unwrap(), expected);
+        assert!(Formatter::Cbor.format(b"", UTF_8).is_err());
+    }
+
+    #[test]
+    fn test_format_csv() {
+        let csv = "id,name,note\r\n1,Jane,\"says \"\"hi\"\"\"\n22,Bob,\"a, b\"\n";
+        assert_eq!(
+            Formatter::Csv.format(csv.as_bytes(), UTF_8).unwrap(),
+            "id | name | note\n---+------+----------\n1  | Jane | says \"hi\"\n22 | Bob  | a, b"
+        );
+    }
+}
@@ -0,0 +1,687 @@
+use serde_json::Value;
+use std::cmp::Ordering;
+
+// A small subset of jq: paths (.a.b, .[0], .[1:3], .[]), pipes, commas,
+// array construction, comparisons with and/or, and a handful of builtins
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    Identity,
+    Field(String),
+    Index(i64),
+    Slice(Option<i64>, Option<i64>),
+    Iterate,
+    // suppresses errors, e.g. .foo?
+    Try(Box<Filter>),
+    Pipe(Box<Filter>, Box<Filter>),
+    Comma(Box<Filter>, Box<Filter>),
+    Literal(Value),
+    Array(Option<Box<Filter>>),
+    Compare(Box<Filter>, CompareOp, Box<Filter>),
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+    Call(String, Vec<Filter>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+// name, number of arguments
+const BUILTINS: [(&str, usize); 13] = [
+    ("keys", 0),
+    ("length", 0),
+    ("select", 1),
+    ("map", 1),
+    ("not", 0),
+    ("has", 1),
+    ("type", 0),
+    ("first", 0),
+    ("last", 0),
+    ("sort", 0),
+    ("unique", 0),
+    ("values", 0),
+    ("to_entries", 0),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Dot,
+    Field(String),
+    Ident(String),
+    Str(String),
+    Num(f64),
+    LBracket,
+    RBracket,
+    LParen,
+    RParen,
+    Pipe,
+    Comma,
+    Colon,
+    Semicolon,
+    Question,
+    Op(CompareOp),
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '.' => {
+                i += 1;
+                if i < chars.len() && (chars[i].is_alphabetic() || chars[i] == '_') {
+                    let start = i;
+                    while i < chars.len() && is_ident(chars[i]) {
+                        i += 1;
+                    }
+                    tokens.push(Token::Field(chars[start..i].iter().collect()));
+                } else if i < chars.len() && chars[i] == '"' {
+                    let (s, end) = read_string(&chars, i)?;
+                    tokens.push(Token::Field(s));
+                    i = end;
+                } else {
+                    tokens.push(Token::Dot);
+                }
+            }
+            '"' => {
+                let (s, end) = read_string(&chars, i)?;
+                tokens.push(Token::Str(s));
+                i = end;
+            }
+            '[' | ']' | '(' | ')' | '|' | ',' | ':' | ';' | '?' => {
+                tokens.push(match c {
+                    '[' => Token::LBracket,
+                    ']' => Token::RBracket,
+                    '(' => Token::LParen,
+                    ')' => Token::RParen,
+                    '|' => Token::Pipe,
+                    ',' => Token::Comma,
+                    ':' => Token::Colon,
+                    ';' => Token::Semicolon,
+                    _ => Token::Question,
+                });
+                i += 1;
+            }
+            '=' | '!' | '<' | '>' => {
+                let eq = chars.get(i + 1) == Some(&'=');
+                let op = match (c, eq) {
+                    ('=', true) => CompareOp::Eq,
+                    ('!', true) => CompareOp::Ne,
+                    ('<', true) => CompareOp::Le,
+                    ('>', true) => CompareOp::Ge,
+                    ('<', false) => CompareOp::Lt,
+                    ('>', false) => CompareOp::Gt,
+                    _ => return Err(format!("Unexpected '{}'", c)),
+                };
+                tokens.push(Token::Op(op));
+                i += if eq { 2 } else { 1 };
+            }
+            c if c.is_ascii_digit() || c == '-' => {
+                let start = i;
+                i += 1;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let num: String = chars[start..i].iter().collect();
+                tokens.push(Token::Num(
+                    num.parse()
+                        .map_err(|_| format!("Invalid number: {}", num))?,
+                ));
+            }
+            c if is_ident(c) => {
+                let start = i;
+                while i < chars.len() && is_ident(chars[i]) {
+                    i += 1;
+                }
+                tokens.push(Token::Ident(chars[start..i].iter().collect()));
+            }
+            c => return Err(format!("Unexpected '{}'", c)),
+        }
+    }
+    Ok(tokens)
+}
+
+fn read_string(chars: &[char], start: usize) -> Result<(String, usize), String> {
+    let mut s = String::new();
+    let mut i = start + 1;
+    while i < chars.len() {
+        match chars[i] {
+            '"' => return Ok((s, i + 1)),
+            '\\' if i + 1 < chars.len() => {
+                s.push(match chars[i + 1] {
+                    'n' => '\n',
+                    't' => '\t',
+                    c => c,
+                });
+                i += 2;
+            }
+            c => {
+                s.push(c);
+                i += 1;
+            }
+        }
+    }
+    Err(String::from("Unterminated string"))
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, token: Token) -> Result<(), String> {
+        match self.next() {
+            Some(ref t) if *t == token => Ok(()),
+            Some(t) => Err(format!("Expected {:?}, found {:?}", token, t)),
+            None => Err(format!("Expected {:?}", token)),
+        }
+    }
+
+    // pipe < comma < or < and < comparison < postfix
+    fn pipe(&mut self) -> Result<Filter, String> {
+        let mut left = self.comma()?;
+        while self.peek() == Some(&Token::Pipe) {
+            self.pos += 1;
+            left = Filter::Pipe(Box::new(left), Box::new(self.comma()?));
+        }
+        Ok(left)
+    }
+
+    fn comma(&mut self) -> Result<Filter, String> {
+        let mut left = self.or()?;
+        while self.peek() == Some(&Token::Comma) {
+            self.pos += 1;
+            left = Filter::Comma(Box::new(left), Box::new(self.or()?));
+        }
+        Ok(left)
+    }
+
+    fn or(&mut self) -> Result<Filter, String> {
+        let mut left = self.and()?;
+        while self.peek() == Some(&Token::Ident(String::from("or"))) {
+            self.pos += 1;
+            left = Filter::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Filter, String> {
+        let mut left = self.comparison()?;
+        while self.peek() == Some(&Token::Ident(String::from("and"))) {
+            self.pos += 1;
+            left = Filter::And(Box::new(left), Box::new(self.comparison()?));
+        }
+        Ok(left)
+    }
+
+    fn comparison(&mut self) -> Result<Filter, String> {
+        let left = self.postfix()?;
+        if let Some(Token::Op(op)) = self.peek().cloned() {
+            self.pos += 1;
+            return Ok(Filter::Compare(
+                Box::new(left),
+                op,
+                Box::new(self.postfix()?),
+            ));
+        }
+        Ok(left)
+    }
+
+    fn postfix(&mut self) -> Result<Filter, String> {
+        let mut filter = self.primary()?;
+        loop {
+            let suffix = match self.peek() {
+                Some(Token::Field(name)) => {
+                    let name = name.clone();
+                    self.pos += 1;
+                    Filter::Field(name)
+                }
+                Some(Token::LBracket) => {
+                    self.pos += 1;
+                    self.brackets()?
+                }
+                Some(Token::Dot) if self.tokens.get(self.pos + 1) == Some(&Token::LBracket) => {
+                    self.pos += 2;
+                    self.brackets()?
+                }
+                Some(Token::Question) => {
+                    self.pos += 1;
+                    filter = Filter::Try(Box::new(filter));
+                    continue;
+                }
+                _ => return Ok(filter),
+            };
+            filter = match filter {
+                Filter::Identity => suffix,
+                filter => Filter::Pipe(Box::new(filter), Box::new(suffix)),
+            };
+        }
+    }
+
+    // after '[': ], n], n:m], "key"]
+    fn brackets(&mut self) -> Result<Filter, String> {
+        let index = |token: Option<&Token>| match token {
+            Some(Token::Num(n)) => Some(*n as i64),
+            _ => None,
+        };
+        if self.peek() == Some(&Token::RBracket) {
+            self.pos += 1;
+            return Ok(Filter::Iterate);
+        }
+        if let Some(Token::Str(key)) = self.peek().cloned() {
+            self.pos += 1;
+            self.expect(Token::RBracket)?;
+            return Ok(Filter::Field(key));
+        }
+        let start = index(self.peek());
+        if start.is_some() {
+            self.pos += 1;
+        }
+        if self.peek() == Some(&Token::Colon) {
+            self.pos += 1;
+            let end = index(self.peek());
+            if end.is_some() {
+                self.pos += 1;
+            }
+            self.expect(Token::RBracket)?;
+            return Ok(Filter::Slice(start, end));
+        }
+        self.expect(Token::RBracket)?;
+        start
+            .map(Filter::Index)
+            .ok_or_else(|| String::from("Expected an index"))
+    }
+
+    fn primary(&mut self) -> Result<Filter, String> {
+        match self.next() {
+            // the suffix loop picks up what follows the dot
+            Some(Token::Dot) => Ok(Filter::Identity),
+            Some(Token::Field(name)) => Ok(Filter::Field(name)),
+            Some(Token::Str(s)) => Ok(Filter::Literal(Value::String(s))),
+            Some(Token::Num(n)) => Ok(Filter::Literal(number(n))),
+            Some(Token::LParen) => {
+                let filter = self.pipe()?;
+                self.expect(Token::RParen)?;
+                Ok(filter)
+            }
+            Some(Token::LBracket) => {
+                if self.peek() == Some(&Token::RBracket) {
+                    self.pos += 1;
+                    return Ok(Filter::Array(None));
+                }
+                let filter = self.pipe()?;
+                self.expect(Token::RBracket)?;
+                Ok(Filter::Array(Some(Box::new(filter))))
+            }
+            Some(Token::Ident(name)) => match name.as_str() {
+                "true" => Ok(Filter::Literal(Value::Bool(true))),
+                "false" => Ok(Filter::Literal(Value::Bool(false))),
+                "null" => Ok(Filter::Literal(Value::Null)),
+                _ => self.call(name),
+            },
+            Some(token) => Err(format!("Unexpected {:?}", token)),
+            None => Err(String::from("Unexpected end of filter")),
+        }
+    }
+
+    fn call(&mut self, name: String) -> Result<Filter, String> {
+        let arity = BUILTINS
+            .iter()
+            .find(|(builtin, _)| *builtin == name)
+            .map(|(_, arity)| *arity)
+            .ok_or_else(|| format!("Unknown function: {}", name))?;
+        let mut args = Vec::new();
+        if arity > 0 {
+            self.expect(Token::LParen)?;
+            args.push(self.pipe()?);
+            while self.peek() == Some(&Token::Semicolon) {
+                self.pos += 1;
+                args.push(self.pipe()?);
+            }
+            self.expect(Token::RParen)?;
+        }
+        if args.len() != arity {
+            return Err(format!("{} takes {} argument(s)", name, arity));
+        }
+        Ok(Filter::Call(name, args))
+    }
+}
+
+fn number(n: f64) -> Value {
+    if n.fract() == 0.0 && n.abs() < i64::MAX as f64 {
+        Value::from(n as i64)
+    } else {
+        Value::from(n)
+    }
+}
+
+impl std::str::FromStr for Filter {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            tokens: tokenize(s)?,
+            pos: 0,
+        };
+        if parser.tokens.is_empty() {
+            return Ok(Filter::Identity);
+        }
+        let filter = parser.pipe()?;
+        match parser.peek() {
+            Some(token) => Err(format!("Unexpected {:?}", token)),
+            None => Ok(filter),
+        }
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn truthy(value: &Value) -> bool {
+    !matches!(value, Value::Null | Value::Bool(false))
+}
+
+// jq ordering: null < false < true < numbers < strings < arrays < objects
+fn compare(a: &Value, b: &Value) -> Ordering {
+    let rank = |v: &Value| match v {
+        Value::Null => 0,
+        Value::Bool(false) => 1,
+        Value::Bool(true) => 2,
+        Value::Number(_) => 3,
+        Value::String(_) => 4,
+        Value::Array(_) => 5,
+        Value::Object(_) => 6,
+    };
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => x
+            .as_f64()
+            .partial_cmp(&y.as_f64())
+            .unwrap_or(Ordering::Equal),
+        (Value::String(x), Value::String(y)) => x.cmp(y),
+        (Value::Array(x), Value::Array(y)) => x
+            .iter()
+            .zip(y.iter())
+            .map(|(x, y)| compare(x, y))
+            .find(|o| o.is_ne())
+            .unwrap_or(x.len().cmp(&y.len())),
+        (Value::Object(_), Value::Object(_)) => a.to_string().cmp(&b.to_string()),
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+fn resolve_index(index: i64, len: usize) -> Option<usize> {
+    let index = if index < 0 { len as i64 + index } else { index };
+    (0..len as i64).contains(&index).then_some(index as usize)
+}
+
+impl Filter {
+    // Runs the filter, producing a stream of outputs like jq does
+    pub fn apply(&self, input: &Value) -> Result<Vec<Value>, String> {
+        match self {
+            Filter::Identity => Ok(vec![input.clone()]),
+            Filter::Field(name) => match input {
+                Value::Object(map) => Ok(vec![map.get(name).cloned().unwrap_or(Value::Null)]),
+                Value::Null => Ok(vec![Value::Null]),
+                v => Err(format!("Cannot index {} with \"{}\"", type_name(v), name)),
+            },
+            Filter::Index(index) => match input {
+                Value::Array(values) => Ok(vec![resolve_index(*index, values.len())
+                    .map(|i| values[i].clone())
+                    .unwrap_or(Value::Null)]),
+                Value::Null => Ok(vec![Value::Null]),
+                v => Err(format!("Cannot index {} with number", type_name(v))),
+            },
+            Filter::Slice(start, end) => {
+                let bounds = |len: usize| {
+                    let clamp = |i: i64| {
+                        let i = if i < 0 { len as i64 + i } else { i };
+                        i.clamp(0, len as i64) as usize
+                    };
+                    let start = start.map(clamp).unwrap_or(0);
+                    (start, end.map(clamp).unwrap_or(len).max(start))
+                };
+                match input {
+                    Value::Array(values) => {
+                        let (start, end) = bounds(values.len());
+                        Ok(vec![Value::Array(values[start..end].to_vec())])
+                    }
+                    Value::String(s) => {
+                        let chars: Vec<char> = s.chars().collect();
+                        let (start, end) = bounds(chars.len());
+                        Ok(vec![Value::String(chars[start..end].iter().collect())])
+                    }
+                    Value::Null => Ok(vec![Value::Null]),
+                    v => Err(format!("Cannot slice {}", type_name(v))),
+                }
+            }
+            Filter::Iterate => match input {
+                Value::Array(values) => Ok(values.clone()),
+                Value::Object(map) => Ok(map.values().cloned().collect()),
+                v => Err(format!("Cannot iterate over {}", type_name(v))),
+            },
+            Filter::Try(filter) => Ok(filter.apply(input).unwrap_or_default()),
+            Filter::Pipe(left, right) => {
+                let mut out = Vec::new();
+                for value in left.apply(input)? {
+                    out.extend(right.apply(&value)?);
+                }
+                Ok(out)
+            }
+            Filter::Comma(left, right) => {
+                let mut out = left.apply(input)?;
+                out.extend(right.apply(input)?);
+                Ok(out)
+            }
+            Filter::Literal(value) => Ok(vec![value.clone()]),
+            Filter::Array(None) => Ok(vec![Value::Array(Vec::new())]),
+            Filter::Array(Some(filter)) => Ok(vec![Value::Array(filter.apply(input)?)]),
+            Filter::Compare(left, op, right) => {
+                let mut out = Vec::new();
+                for r in right.apply(input)? {
+                    for l in left.apply(input)? {
+                        let ord = compare(&l, &r);
+                        out.push(Value::Bool(match op {
+                            CompareOp::Eq => ord.is_eq(),
+                            CompareOp::Ne => ord.is_ne(),
+                            CompareOp::Lt => ord.is_lt(),
+                            CompareOp::Le => ord.is_le(),
+                            CompareOp::Gt => ord.is_gt(),
+                            CompareOp::Ge => ord.is_ge(),
+                        }));
+                    }
+                }
+                Ok(out)
+            }
+            Filter::And(left, right) | Filter::Or(left, right) => {
+                let is_and = matches!(self, Filter::And(..));
+                let mut out = Vec::new();
+                for l in left.apply(input)? {
+                    // short circuit like jq
+                    if truthy(&l) != is_and {
+                        out.push(Value::Bool(!is_and));
+                        continue;
+                    }
+                    for r in right.apply(input)? {
+                        out.push(Value::Bool(truthy(&r)));
+                    }
+                }
+                Ok(out)
+            }
+            Filter::Call(name, args) => call(name, args, input),
+        }
+    }
+}
+
+fn call(name: &str, args: &[Filter], input: &Value) -> Result<Vec<Value>, String> {
+    let value = match (name, input) {
+        ("keys", Value::Object(map)) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            Value::Array(keys.into_iter().cloned().map(Value::String).collect())
+        }
+        ("keys", Value::Array(values)) => {
+            Value::Array((0..values.len()).map(Value::from).collect())
+        }
+        ("length", Value::Null) => Value::from(0),
+        ("length", Value::Bool(_)) => return Err(String::from("boolean has no length")),
+        ("length", Value::Number(n)) => number(n.as_f64().unwrap_or_default().abs()),
+        ("length", Value::String(s)) => Value::from(s.chars().count()),
+        ("length", Value::Array(values)) => Value::from(values.len()),
+        ("length", Value::Object(map)) => Value::from(map.len()),
+        ("select", _) => {
+            let mut out = Vec::new();
+            for cond in args[0].apply(input)? {
+                if truthy(&cond) {
+                    out.push(input.clone());
+                }
+            }
+            return Ok(out);
+        }
+        ("map", _) => {
+            return Filter::Array(Some(Box::new(Filter::Pipe(
+                Box::new(Filter::Iterate),
+                Box::new(args[0].clone()),
+            ))))
+            .apply(input)
+        }
+        ("not", v) => Value::Bool(!truthy(v)),
+        ("has", Value::Object(map)) => {
+            return args[0]
+                .apply(input)?
+                .iter()
+                .map(|key| match key {
+                    Value::String(key) => Ok(Value::Bool(map.contains_key(key))),
+                    v => Err(format!(
+                        "Cannot check whether object has a {} key",
+                        type_name(v)
+                    )),
+                })
+                .collect()
+        }
+        ("has", Value::Array(values)) => {
+            return args[0]
+                .apply(input)?
+                .iter()
+                .map(|index| match index.as_i64() {
+                    Some(i) => Ok(Value::Bool(i >= 0 && (i as usize) < values.len())),
+                    None => Err(String::from(
+                        "Cannot check whether array has a non-number key",
+                    )),
+                })
+                .collect()
+        }
+        ("type", v) => Value::String(type_name(v).to_string()),
+        ("first", Value::Array(values)) => values.first().cloned().unwrap_or(Value::Null),
+        ("last", Value::Array(values)) => values.last().cloned().unwrap_or(Value::Null),
+        ("sort", Value::Array(values)) => {
+            let mut values = values.clone();
+            values.sort_by(compare);
+            Value::Array(values)
+        }
+        ("unique", Value::Array(values)) => {
+            let mut values = values.clone();
+            values.sort_by(compare);
+            values.dedup_by(|a, b| compare(a, b).is_eq());
+            Value::Array(values)
+        }
+        ("values", v) => return Ok(if v.is_null() { vec![] } else { vec![v.clone()] }),
+        ("to_entries", Value::Object(map)) => Value::Array(
+            map.iter()
+                .map(|(k, v)| serde_json::json!({"key": k, "value": v}))
+                .collect(),
+        ),
+        (name, v) => return Err(format!("{} cannot be applied to {}", name, type_name(v))),
+    };
+    Ok(vec![value])
+}
+
+// Parses and runs a filter, rendering each output as pretty printed JSON
+pub fn run_filter(filter: &str, input: &Value) -> Result<String, String> {
+    let filter: Filter = filter.parse()?;
+    let outputs = filter.apply(input)?;
+    Ok(outputs
+        .iter()
+        .map(|v| serde_json::to_string_pretty(v).unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn run(filter: &str, input: &Value) -> Vec<Value> {
+        filter.parse::<Filter>().unwrap().apply(input).unwrap()
+    }
+
+    #[test]
+    fn test_paths() {
+        let input =
+            json!({"data": [{"id": 1, "tags": ["a"]}, {"id": 2, "tags": []}], "next": null});
+        assert_eq!(run(".", &input), vec![input.clone()]);
+        assert_eq!(run(".data[].id", &input), vec![json!(1), json!(2)]);
+        assert_eq!(run(".data[-1].id", &input), vec![json!(2)]);
+        assert_eq!(run(".data[0].tags[0]", &input), vec![json!("a")]);
+        assert_eq!(run(".data[1:]|length", &input), vec![json!(1)]);
+        assert_eq!(run(".[\"next\"]", &input), vec![Value::Null]);
+        assert_eq!(run(".next.missing", &input), vec![Value::Null]);
+        assert_eq!(run("[.data[].id]", &input), vec![json!([1, 2])]);
+        assert_eq!(run(".data[0] | keys", &input), vec![json!(["id", "tags"])]);
+        assert_eq!(run(".data | length", &input), vec![json!(2)]);
+        assert_eq!(run(".data[].id, .next", &input).len(), 3);
+        assert!(".data.id".parse::<Filter>().unwrap().apply(&input).is_err());
+        assert!(run(".data.id?", &input).is_empty());
+    }
+
+    #[test]
+    fn test_select_and_builtins() {
+        let input = json!([{"id": 1, "ok": true}, {"id": 2, "ok": false}, {"id": 3, "ok": true}]);
+        assert_eq!(
+            run(".[] | select(.ok and .id > 1) | .id", &input),
+            vec![json!(3)]
+        );
+        assert_eq!(
+            run("map(select(.id != 2 or .ok)) | map(.id)", &input),
+            vec![json!([1, 3])]
+        );
+        assert_eq!(
+            run("map(.ok | not)", &input),
+            vec![json!([false, true, false])]
+        );
+        assert_eq!(run("map(.id) | sort | last", &input), vec![json!(3)]);
+        assert_eq!(run(".[0] | has(\"id\")", &input), vec![json!(true)]);
+        assert_eq!(run(".[0].id | type", &input), vec![json!("number")]);
+        assert!("nope".parse::<Filter>().is_err());
+        assert!(".[".parse::<Filter>().is_err());
+        assert!("select()".parse::<Filter>().is_err());
+    }
+}
@@ -2,9 +2,33 @@ use crate::display::{AppOptions, HeaderKind};
 
 use self::curl::Curl;
 
+// Concurrent load testing of a request
+pub mod bench;
+// Decoding response bodies in their declared charset
+pub mod charset;
+// Rendering a request as code for other languages and clients
+pub mod codegen;
 pub mod curl;
+// Comparing two responses
+pub mod diff;
+// Content-Type aware response formatters
+pub mod format;
+// jq style filters over JSON responses
+pub mod jq;
+// RFC 7807 problem details and explanations of failed requests
+pub mod problem;
 // Response parser
 pub mod response;
+// Results of running saved commands outside of the TUI
+pub mod runner;
+// Quoting command lines for POSIX sh, fish, PowerShell and cmd.exe
+pub mod shell;
+// Status, timing and size of a response
+pub mod summary;
+// JUnit/JSON reports of collection runs
+pub mod report;
+// {{variable}} references in saved commands
+pub mod variables;
 
 pub trait ExecuteOption {
     fn add_option(&mut self, opt: &AppOptions);
@@ -0,0 +1,219 @@
+use super::summary::ResponseSummary;
+use serde_json::Value;
+use std::fmt::{Display, Formatter};
+
+// How much of a failed response body is shown on the error screen
+const ERROR_BODY_LIMIT: usize = 400;
+
+type CurlErrorCheck = fn(&curl::Error) -> bool;
+
+// libcurl errors we can explain, with a hint on what to do about them
+const CURL_ERRORS: [(CurlErrorCheck, &str, &str); 14] = [
+    (
+        curl::Error::is_couldnt_resolve_host,
+        "DNS lookup failed, the host name could not be found.",
+        "Check the URL for typos and that you are connected to the network.",
+    ),
+    (
+        curl::Error::is_couldnt_resolve_proxy,
+        "DNS lookup failed for the proxy.",
+        "Check the proxy settings, e.g. the http_proxy/https_proxy environment variables.",
+    ),
+    (
+        curl::Error::is_couldnt_connect,
+        "Connection refused, nothing is listening at that address.",
+        "Check the host and port, and that the server is running.",
+    ),
+    (
+        curl::Error::is_operation_timedout,
+        "The request timed out.",
+        "The server may be down or overloaded, or a firewall is dropping the connection.",
+    ),
+    (
+        curl::Error::is_peer_failed_verification,
+        "TLS verification failed, the server certificate is not trusted.",
+        "Set a CA certificate or CA path for self signed certificates, and check the host name matches the certificate.",
+    ),
+    (
+        curl::Error::is_ssl_cacert,
+        "TLS verification failed, the server certificate is not trusted.",
+        "Set a CA certificate or CA path for self signed certificates.",
+    ),
+    (
+        curl::Error::is_ssl_cacert_badfile,
+        "The CA certificate file could not be read.",
+        "Check the CA certificate path.",
+    ),
+    (
+        curl::Error::is_ssl_connect_error,
+        "The TLS handshake failed.",
+        "The server may not support TLS on this port, try http:// instead of https://.",
+    ),
+    (
+        curl::Error::is_url_malformed,
+        "The URL is not valid.",
+        "URLs look like https://example.com/path?query=1",
+    ),
+    (
+        curl::Error::is_unsupported_protocol,
+        "The URL scheme is not supported.",
+        "Use a scheme like http:// or https://",
+    ),
+    (
+        curl::Error::is_too_many_redirects,
+        "Too many redirects were followed.",
+        "The server may be redirecting in a loop, or raise the maximum number of redirects.",
+    ),
+    (
+        curl::Error::is_got_nothing,
+        "The server closed the connection without sending a response.",
+        "The server may have crashed handling the request, or expects a different protocol.",
+    ),
+    (
+        curl::Error::is_send_error,
+        "Sending the request failed, the connection was lost.",
+        "Check your network connection and try again.",
+    ),
+    (
+        curl::Error::is_recv_error,
+        "Receiving the response failed, the connection was lost.",
+        "Check your network connection and try again.",
+    ),
+];
+
+// Explains why libcurl couldn't perform a request
+pub fn explain_curl_error(err: &curl::Error) -> String {
+    let message = err.extra_description().unwrap_or(err.description());
+    match CURL_ERRORS.iter().find(|(check, _, _)| check(err)) {
+        Some((_, explanation, hint)) => format!("{}\n{}\n\nHint: {}", message, explanation, hint),
+        None => format!("Error: {}", message),
+    }
+}
+
+/// An RFC 7807 `application/problem+json` error response
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Problem {
+    pub kind: Option<String>,
+    pub title: Option<String>,
+    pub status: Option<u64>,
+    pub detail: Option<String>,
+    pub instance: Option<String>,
+    /// Any other members of the problem object
+    pub extensions: Vec<(String, String)>,
+}
+
+impl Problem {
+    pub fn parse(content_type: Option<&str>, body: &str) -> Option<Self> {
+        let content_type = content_type?.split(';').next()?.trim().to_lowercase();
+        if !content_type.ends_with("problem+json") {
+            return None;
+        }
+        let Ok(Value::Object(members)) = serde_json::from_str::<Value>(body) else {
+            return None;
+        };
+        let mut problem = Problem::default();
+        for (name, value) in members {
+            let text = match value {
+                Value::String(ref s) => s.clone(),
+                ref value => value.to_string(),
+            };
+            match name.as_str() {
+                "type" => problem.kind = Some(text),
+                "title" => problem.title = Some(text),
+                "status" => problem.status = value.as_u64(),
+                "detail" => problem.detail = Some(text),
+                "instance" => problem.instance = Some(text),
+                _ => problem.extensions.push((name, text)),
+            }
+        }
+        Some(problem)
+    }
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let fields = [
+            ("Title", self.title.as_ref()),
+            ("Detail", self.detail.as_ref()),
+            ("Instance", self.instance.as_ref()),
+            ("Type", self.kind.as_ref()),
+        ];
+        let mut lines: Vec<String> = fields
+            .iter()
+            .filter_map(|(label, value)| value.map(|v| format!("{}: {}", label, v)))
+            .collect();
+        lines.extend(
+            self.extensions
+                .iter()
+                .map(|(name, value)| format!("{}: {}", name, value)),
+        );
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+// What the error screen shows for a 4xx/5xx response
+pub fn http_failure(summary: &ResponseSummary, body: &str) -> String {
+    let status = format!("HTTP {}", summary.status_text());
+    if let Some(problem) = Problem::parse(summary.content_type.as_deref(), body) {
+        return format!("{}\n\n{}", status, problem);
+    }
+    match body.trim() {
+        "" => status,
+        body if body.len() > ERROR_BODY_LIMIT => {
+            let end = (0..=ERROR_BODY_LIMIT)
+                .rev()
+                .find(|i| body.is_char_boundary(*i))
+                .unwrap_or_default();
+            format!("{}\n\n{}…", status, &body[..end])
+        }
+        body => format!("{}\n\n{}", status, body),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(status: u32, content_type: &str) -> ResponseSummary {
+        ResponseSummary {
+            status: Some(status),
+            reason: Some(String::from("Forbidden")),
+            content_type: Some(content_type.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_problem_details() {
+        let body = r#"{"type": "https://example.com/probs/out-of-credit", "title": "You do not have enough credit.",
+            "status": 403, "detail": "Your current balance is 30, but that costs 50.",
+            "instance": "/account/12345/msgs/abc", "balance": 30}"#;
+        let problem = Problem::parse(Some("application/problem+json"), body).unwrap();
+        assert_eq!(problem.status, Some(403));
+        assert_eq!(
+            problem.extensions,
+            vec![(String::from("balance"), String::from("30"))]
+        );
+        assert_eq!(
+            http_failure(&summary(403, "application/problem+json; charset=utf-8"), body),
+            "HTTP 403 Forbidden\n\nTitle: You do not have enough credit.\nDetail: Your current balance is 30, but that costs 50.\nInstance: /account/12345/msgs/abc\nType: https://example.com/probs/out-of-credit\nbalance: 30"
+        );
+        assert!(Problem::parse(Some("application/json"), body).is_none());
+        assert_eq!(
+            http_failure(&summary(403, "text/plain"), "go away\n"),
+            "HTTP 403 Forbidden\n\ngo away"
+        );
+        let long = "é".repeat(ERROR_BODY_LIMIT);
+        assert!(http_failure(&summary(403, "text/plain"), &long).ends_with("é…"));
+    }
+
+    #[test]
+    fn test_explain_curl_error() {
+        let explained = explain_curl_error(&curl::Error::new(7));
+        assert!(explained.contains("Connection refused"));
+        assert!(explained.contains("Hint: "));
+        assert!(explain_curl_error(&curl::Error::new(6)).contains("DNS lookup failed"));
+        assert!(explain_curl_error(&curl::Error::new(60)).contains("TLS verification failed"));
+        assert!(explain_curl_error(&curl::Error::new(3)).contains("not valid"));
+    }
+}
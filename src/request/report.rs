@@ -0,0 +1,225 @@
+use super::runner::{CollectionRun, RunResult};
+use serde_json::json;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+// Response bodies are truncated to this many bytes in reports
+pub const REPORT_BODY_LIMIT: usize = 2048;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    JUnit,
+    Json,
+}
+
+impl ReportFormat {
+    // Guess the format from a file extension, defaulting to JUnit XML
+    pub fn from_path(path: &str) -> Self {
+        if path.to_lowercase().ends_with(".json") {
+            ReportFormat::Json
+        } else {
+            ReportFormat::JUnit
+        }
+    }
+
+    pub fn render(&self, run: &CollectionRun) -> String {
+        match self {
+            ReportFormat::JUnit => junit_report(run),
+            ReportFormat::Json => json_report(run),
+        }
+    }
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "junit" | "xml" => Ok(ReportFormat::JUnit),
+            "json" => Ok(ReportFormat::Json),
+            _ => Err(format!("Unknown report format: {s}")),
+        }
+    }
+}
+
+impl Display for ReportFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReportFormat::JUnit => write!(f, "JUnit XML"),
+            ReportFormat::Json => write!(f, "JSON"),
+        }
+    }
+}
+
+pub fn write_report(path: &str, run: &CollectionRun, format: ReportFormat) -> std::io::Result<()> {
+    std::fs::write(path, format.render(run))
+}
+
+fn truncate_body(body: &str) -> String {
+    if body.len() <= REPORT_BODY_LIMIT {
+        return body.to_string();
+    }
+    let mut end = REPORT_BODY_LIMIT;
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}... ({} bytes truncated)", &body[..end], body.len() - end)
+}
+
+fn escape_xml(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // control characters are not allowed in XML 1.0
+            c if c.is_control() && !matches!(c, '\n' | '\r' | '\t') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn junit_testcase(suite: &str, result: &RunResult) -> String {
+    let mut case = format!(
+        "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\">\n",
+        escape_xml(&result.name),
+        escape_xml(suite),
+        result.duration.as_secs_f64()
+    );
+    if let Some(status) = result.status {
+        case.push_str(&format!(
+            "      <properties>\n        <property name=\"status\" value=\"{status}\"/>\n      </properties>\n"
+        ));
+    }
+    for failure in result.failures() {
+        // requests that never completed are errors, bad responses are failures
+        let tag = if result.error.is_some() || result.status.is_none() {
+            "error"
+        } else {
+            "failure"
+        };
+        case.push_str(&format!(
+            "      <{tag} message=\"{}\"/>\n",
+            escape_xml(&failure)
+        ));
+    }
+    if !result.body.is_empty() {
+        case.push_str(&format!(
+            "      <system-out>{}</system-out>\n",
+            escape_xml(&truncate_body(&result.body))
+        ));
+    }
+    case.push_str("    </testcase>\n");
+    case
+}
+
+pub fn junit_report(run: &CollectionRun) -> String {
+    let errors = run
+        .results
+        .iter()
+        .filter(|r| r.error.is_some() || r.status.is_none())
+        .count();
+    let failures = run.failed_count() - errors;
+    let time = run.total_duration().as_secs_f64();
+    let mut report = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    report.push_str(&format!(
+        "<testsuites name=\"CuTE\" tests=\"{}\" failures=\"{failures}\" errors=\"{errors}\" time=\"{time:.3}\">\n",
+        run.results.len()
+    ));
+    report.push_str(&format!(
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{failures}\" errors=\"{errors}\" time=\"{time:.3}\">\n",
+        escape_xml(&run.name),
+        run.results.len()
+    ));
+    run.results
+        .iter()
+        .for_each(|result| report.push_str(&junit_testcase(&run.name, result)));
+    report.push_str("  </testsuite>\n</testsuites>\n");
+    report
+}
+
+pub fn json_report(run: &CollectionRun) -> String {
+    let cases = run
+        .results
+        .iter()
+        .map(|result| {
+            json!({
+                "name": result.name,
+                "passed": result.passed(),
+                "status": result.status,
+                "duration_ms": result.duration.as_millis() as u64,
+                "failures": result.failures(),
+                "body": truncate_body(&result.body),
+            })
+        })
+        .collect::<Vec<_>>();
+    let report = json!({
+        "name": run.name,
+        "tests": run.results.len(),
+        "failures": run.failed_count(),
+        "duration_ms": run.total_duration().as_millis() as u64,
+        "results": cases,
+    });
+    serde_json::to_string_pretty(&report).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn run() -> CollectionRun {
+        CollectionRun {
+            name: String::from("My <API>"),
+            results: vec![
+                RunResult {
+                    name: String::from("ok"),
+                    status: Some(200),
+                    body: String::from("{\"a\": 1}"),
+                    duration: Duration::from_millis(1500),
+                    ..Default::default()
+                },
+                RunResult {
+                    name: String::from("missing"),
+                    status: Some(404),
+                    body: "x".repeat(REPORT_BODY_LIMIT + 10),
+                    ..Default::default()
+                },
+                RunResult {
+                    name: String::from("down"),
+                    error: Some(String::from("Couldn't connect")),
+                    ..Default::default()
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_junit_report() {
+        let report = junit_report(&run());
+        assert!(report.contains(
+            "<testsuite name=\"My &lt;API&gt;\" tests=\"3\" failures=\"1\" errors=\"1\""
+        ));
+        assert!(
+            report.contains("<testcase name=\"ok\" classname=\"My &lt;API&gt;\" time=\"1.500\">")
+        );
+        assert!(report
+            .contains("<failure message=\"Expected a successful status code, received: 404\"/>"));
+        assert!(report.contains("<error message=\"Request failed: Couldn&apos;t connect\"/>"));
+        assert!(report.contains("(10 bytes truncated)"));
+    }
+
+    #[test]
+    fn test_json_report() {
+        let report: serde_json::Value = serde_json::from_str(&json_report(&run())).unwrap();
+        assert_eq!(report["tests"], 3);
+        assert_eq!(report["failures"], 2);
+        assert_eq!(report["results"][0]["passed"], true);
+        assert_eq!(report["results"][0]["duration_ms"], 1500);
+        assert_eq!(report["results"][1]["status"], 404);
+        assert_eq!(report["results"][2]["status"], serde_json::Value::Null);
+    }
+}
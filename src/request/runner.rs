@@ -0,0 +1,84 @@
+use super::summary::ResponseSummary;
+use std::time::Duration;
+
+/// The outcome of executing a single saved command, used when running
+/// saved commands and collections without the TUI.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunResult {
+    /// The label of the saved command (or its id if unlabeled)
+    pub name: String,
+    /// HTTP status code, None if we never got a response
+    pub status: Option<u32>,
+    /// Raw status line + response headers
+    pub headers: String,
+    /// Response body
+    pub body: String,
+    /// libcurl error, if the request could not be performed
+    pub error: Option<String>,
+    /// Time taken to perform the request
+    pub duration: Duration,
+    /// Status, timing and size as reported by libcurl
+    pub summary: ResponseSummary,
+}
+
+/// The results of running every saved command in a collection (or a single command)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CollectionRun {
+    pub name: String,
+    pub results: Vec<RunResult>,
+}
+
+impl RunResult {
+    // The assertions we make about every response, empty if the request passed
+    pub fn failures(&self) -> Vec<String> {
+        let mut failures = Vec::new();
+        match (self.error.as_ref(), self.status) {
+            (Some(e), _) => failures.push(format!("Request failed: {e}")),
+            (None, None) => failures.push(String::from("No response received")),
+            (None, Some(status)) if status >= 400 => failures.push(format!(
+                "Expected a successful status code, received: {status}"
+            )),
+            _ => {}
+        }
+        failures
+    }
+
+    pub fn passed(&self) -> bool {
+        self.failures().is_empty()
+    }
+
+    // 0 on success, 1 if the request failed to execute, otherwise
+    // the class of the HTTP status code (4 for 4xx, 5 for 5xx)
+    pub fn exit_code(&self) -> i32 {
+        match (self.error.as_ref(), self.status) {
+            (Some(_), _) | (None, None) => 1,
+            (None, Some(status)) if status >= 400 => (status / 100) as i32,
+            _ => 0,
+        }
+    }
+}
+
+impl CollectionRun {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            results: Vec::new(),
+        }
+    }
+
+    pub fn failed_count(&self) -> usize {
+        self.results.iter().filter(|r| !r.passed()).count()
+    }
+
+    pub fn total_duration(&self) -> Duration {
+        self.results.iter().map(|r| r.duration).sum()
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        self.results
+            .iter()
+            .map(|r| r.exit_code())
+            .max()
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,208 @@
+use serde::{Deserialize, Serialize};
+
+// The shells a command can be quoted for when it's copied
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Shell {
+    #[default]
+    Posix,
+    Fish,
+    PowerShell,
+    Cmd,
+}
+
+impl Shell {
+    // Quotes one argument so the shell passes it to the program unchanged
+    pub fn quote(&self, arg: &str) -> String {
+        // a bare @ or , means splatting or an array to PowerShell
+        let safe = match self {
+            Shell::PowerShell => "-_=./:+",
+            _ => "-_=./:@,+",
+        };
+        let plain = |c: char| c.is_ascii_alphanumeric() || safe.contains(c);
+        if !arg.is_empty() && arg.chars().all(plain) {
+            return arg.to_string();
+        }
+        match self {
+            Shell::Posix => format!("'{}'", arg.replace('\'', "'\\''")),
+            // fish allows \\ and \' inside single quotes
+            Shell::Fish => format!("'{}'", arg.replace('\\', "\\\\").replace('\'', "\\'")),
+            // Windows PowerShell passes the argument to native programs
+            // without escaping its quotes, or the backslashes before them
+            Shell::PowerShell => format!(
+                "'{}'",
+                msvc_escape(arg, arg.contains(char::is_whitespace)).replace('\'', "''")
+            ),
+            Shell::Cmd => cmd_quote(arg),
+        }
+    }
+
+    fn continuation(&self) -> &'static str {
+        match self {
+            Shell::Posix | Shell::Fish => " \\\n  ",
+            Shell::PowerShell => " `\n  ",
+            Shell::Cmd => " ^\n  ",
+        }
+    }
+
+    // curl and wget are aliases of Invoke-WebRequest in Windows PowerShell
+    fn program(&self, program: &str) -> String {
+        match self {
+            Shell::PowerShell => format!("{}.exe", program),
+            _ => program.to_string(),
+        }
+    }
+}
+
+// Escapes the quotes of an argument the way programs read it with the MSVC
+// rules (backslashes are only special before a quote). The trailing
+// backslashes are doubled when the argument will be wrapped in quotes
+fn msvc_escape(arg: &str, quoted: bool) -> String {
+    let mut escaped = String::new();
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        match c {
+            '\\' => backslashes += 1,
+            '"' => {
+                escaped.push_str(&"\\".repeat(backslashes * 2 + 1));
+                backslashes = 0;
+            }
+            _ => {
+                escaped.push_str(&"\\".repeat(backslashes));
+                backslashes = 0;
+            }
+        }
+        if c != '\\' {
+            escaped.push(c);
+        }
+    }
+    let trailing = if quoted { backslashes * 2 } else { backslashes };
+    escaped.push_str(&"\\".repeat(trailing));
+    escaped
+}
+
+// The argument is escaped for the program, then every character cmd.exe
+// would interpret, quotes included, is escaped with a caret
+fn cmd_quote(arg: &str) -> String {
+    format!("\"{}\"", msvc_escape(arg, true))
+        .chars()
+        .map(|c| match c {
+            '^' | '&' | '|' | '<' | '>' | '(' | ')' | '"' | '%' | '!' => format!("^{}", c),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+// A command line as its arguments, grouped by flag (a flag and its value)
+// so it can be rendered with one flag per line
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Argv {
+    program: String,
+    groups: Vec<Vec<String>>,
+}
+
+impl Argv {
+    pub fn new(program: &str) -> Self {
+        Self {
+            program: program.to_string(),
+            groups: Vec::new(),
+        }
+    }
+
+    // Options without arguments leave empty groups, those are skipped
+    pub fn push(&mut self, group: Vec<String>) {
+        if !group.is_empty() {
+            self.groups.push(group);
+        }
+    }
+
+    pub fn args(&self) -> Vec<String> {
+        self.groups.iter().flatten().cloned().collect()
+    }
+
+    pub fn render(&self, shell: Shell, multiline: bool) -> String {
+        let separator = if multiline { shell.continuation() } else { " " };
+        std::iter::once(shell.program(&self.program))
+            .chain(self.groups.iter().map(|group| {
+                group
+                    .iter()
+                    .map(|arg| shell.quote(arg))
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            }))
+            .collect::<Vec<String>>()
+            .join(separator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn argv() -> Argv {
+        let mut argv = Argv::new("curl");
+        argv.push(vec!["-X".to_string(), "POST".to_string()]);
+        argv.push(vec!["https://example.com".to_string()]);
+        argv.push(vec![
+            "-H".to_string(),
+            "Content-Type: application/json".to_string(),
+        ]);
+        argv.push(vec!["-d".to_string(), r#"{"a": "it's b c"}"#.to_string()]);
+        argv.push(Vec::new());
+        argv
+    }
+
+    #[test]
+    fn test_render_shells() {
+        let argv = argv();
+        assert_eq!(argv.args().len(), 7);
+        assert_eq!(
+            argv.render(Shell::Posix, false),
+            r#"curl -X POST https://example.com -H 'Content-Type: application/json' -d '{"a": "it'\''s b c"}'"#
+        );
+        assert_eq!(
+            argv.render(Shell::Fish, false),
+            r#"curl -X POST https://example.com -H 'Content-Type: application/json' -d '{"a": "it\'s b c"}'"#
+        );
+        assert_eq!(
+            argv.render(Shell::PowerShell, false),
+            r#"curl.exe -X POST https://example.com -H 'Content-Type: application/json' -d '{\"a\": \"it''s b c\"}'"#
+        );
+        assert_eq!(
+            argv.render(Shell::Cmd, false),
+            r#"curl -X POST https://example.com -H ^"Content-Type: application/json^" -d ^"{\^"a\^": \^"it's b c\^"}^""#
+        );
+    }
+
+    #[test]
+    fn test_repeated_args() {
+        // repeated form fields are all sent
+        let mut argv = Argv::new("curl");
+        argv.push(vec!["-F".to_string(), "tag=a".to_string()]);
+        argv.push(vec!["-F".to_string(), "tag=a".to_string()]);
+        assert_eq!(argv.render(Shell::Posix, false), "curl -F tag=a -F tag=a");
+    }
+
+    #[test]
+    fn test_render_multiline() {
+        assert_eq!(
+            argv().render(Shell::Posix, true),
+            "curl \\\n  -X POST \\\n  https://example.com \\\n  -H 'Content-Type: application/json' \\\n  -d '{\"a\": \"it'\\''s b c\"}'"
+        );
+        assert!(argv()
+            .render(Shell::PowerShell, true)
+            .contains(" `\n  -X POST"));
+        assert!(argv().render(Shell::Cmd, true).contains(" ^\n  -X POST"));
+    }
+
+    #[test]
+    fn test_cmd_quote() {
+        assert_eq!(Shell::Cmd.quote(r"C:\dir\"), r#"^"C:\dir\\^""#);
+        assert_eq!(Shell::Cmd.quote("100% & more"), r#"^"100^% ^& more^""#);
+        assert_eq!(Shell::Fish.quote(r"a\b"), r"'a\\b'");
+        assert_eq!(Shell::Posix.quote(""), "''");
+        assert_eq!(Shell::PowerShell.quote("@body.json"), "'@body.json'");
+        assert_eq!(Shell::PowerShell.quote(r#"a\"b"#), r#"'a\\\"b'"#);
+        assert_eq!(Shell::PowerShell.quote(r"C:\my dir\"), r"'C:\my dir\\'");
+        assert_eq!(Shell::PowerShell.quote(r"C:\dir\"), r"'C:\dir\'");
+    }
+}
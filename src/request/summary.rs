@@ -0,0 +1,174 @@
+use std::time::Duration;
+
+// Reason phrases for responses that don't carry one, e.g. over HTTP/2
+const REASONS: [(u32, &str); 40] = [
+    (100, "Continue"),
+    (101, "Switching Protocols"),
+    (200, "OK"),
+    (201, "Created"),
+    (202, "Accepted"),
+    (203, "Non-Authoritative Information"),
+    (204, "No Content"),
+    (205, "Reset Content"),
+    (206, "Partial Content"),
+    (300, "Multiple Choices"),
+    (301, "Moved Permanently"),
+    (302, "Found"),
+    (303, "See Other"),
+    (304, "Not Modified"),
+    (307, "Temporary Redirect"),
+    (308, "Permanent Redirect"),
+    (400, "Bad Request"),
+    (401, "Unauthorized"),
+    (402, "Payment Required"),
+    (403, "Forbidden"),
+    (404, "Not Found"),
+    (405, "Method Not Allowed"),
+    (406, "Not Acceptable"),
+    (408, "Request Timeout"),
+    (409, "Conflict"),
+    (410, "Gone"),
+    (411, "Length Required"),
+    (412, "Precondition Failed"),
+    (413, "Content Too Large"),
+    (415, "Unsupported Media Type"),
+    (418, "I'm a teapot"),
+    (422, "Unprocessable Content"),
+    (429, "Too Many Requests"),
+    (500, "Internal Server Error"),
+    (501, "Not Implemented"),
+    (502, "Bad Gateway"),
+    (503, "Service Unavailable"),
+    (504, "Gateway Timeout"),
+    (505, "HTTP Version Not Supported"),
+    (511, "Network Authentication Required"),
+];
+
+/// Status, timing and size of a response, shown above the response menu
+/// and printed after each request by `cute run`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResponseSummary {
+    pub status: Option<u32>,
+    pub reason: Option<String>,
+    /// e.g. HTTP/1.1 or HTTP/2
+    pub version: Option<String>,
+    pub duration: Duration,
+    /// Size of the body as received
+    pub size: usize,
+    pub content_type: Option<String>,
+    /// The URL after following any redirects
+    pub url: Option<String>,
+}
+
+impl ResponseSummary {
+    // Takes the status line and headers of the final response
+    pub fn new(
+        status: Option<u32>,
+        headers: &str,
+        duration: Duration,
+        size: usize,
+        url: Option<String>,
+    ) -> Self {
+        let mut status_line = headers.lines().next().unwrap_or_default().splitn(3, ' ');
+        let version = status_line
+            .next()
+            .filter(|v| v.starts_with("HTTP/"))
+            .map(String::from);
+        let reason = status_line
+            .nth(1)
+            .map(str::trim)
+            .filter(|r| !r.is_empty())
+            .map(String::from)
+            .or_else(|| {
+                REASONS
+                    .iter()
+                    .find(|(code, _)| Some(*code) == status)
+                    .map(|(_, reason)| reason.to_string())
+            });
+        let content_type = headers
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-type"))
+            .map(|(_, value)| value.trim().to_string());
+        Self {
+            status,
+            reason,
+            version,
+            duration,
+            size,
+            content_type,
+            url,
+        }
+    }
+
+    // e.g. "404 Not Found"
+    pub fn status_text(&self) -> String {
+        match (self.status, self.reason.as_ref()) {
+            (Some(status), Some(reason)) => format!("{} {}", status, reason),
+            (Some(status), None) => status.to_string(),
+            (None, _) => String::from("No response"),
+        }
+    }
+
+    pub fn duration_text(&self) -> String {
+        match self.duration.as_millis() {
+            ms if ms >= 1000 => format!("{:.2} s", self.duration.as_secs_f64()),
+            ms => format!("{} ms", ms),
+        }
+    }
+
+    pub fn size_text(&self) -> String {
+        match self.size {
+            size if size >= 1024 * 1024 => format!("{:.1} MB", size as f64 / (1024.0 * 1024.0)),
+            size if size >= 1024 => format!("{:.1} KB", size as f64 / 1024.0),
+            size => format!("{} B", size),
+        }
+    }
+
+    // Everything on one line, for printing after a request in headless mode
+    pub fn one_line(&self) -> String {
+        let mut parts = vec![self.status_text(), self.duration_text(), self.size_text()];
+        parts.extend(self.content_type.clone());
+        parts.extend(self.version.clone());
+        parts.extend(self.url.clone());
+        parts.join(" | ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_from_headers() {
+        let headers =
+            "HTTP/1.1 404 Not Found\r\nContent-Type: application/json\r\nContent-Length: 2";
+        let summary = ResponseSummary::new(
+            Some(404),
+            headers,
+            Duration::from_millis(120),
+            2048,
+            Some(String::from("http://localhost/b")),
+        );
+        assert_eq!(summary.version.as_deref(), Some("HTTP/1.1"));
+        assert_eq!(summary.status_text(), "404 Not Found");
+        assert_eq!(
+            summary.one_line(),
+            "404 Not Found | 120 ms | 2.0 KB | application/json | HTTP/1.1 | http://localhost/b"
+        );
+        // HTTP/2 has no reason phrase
+        let summary = ResponseSummary::new(
+            Some(503),
+            "HTTP/2 503\r\n",
+            Duration::from_millis(1500),
+            12,
+            None,
+        );
+        assert_eq!(summary.version.as_deref(), Some("HTTP/2"));
+        assert_eq!(
+            summary.one_line(),
+            "503 Service Unavailable | 1.50 s | 12 B | HTTP/2"
+        );
+        assert_eq!(ResponseSummary::default().status_text(), "No response");
+    }
+}
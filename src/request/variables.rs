@@ -0,0 +1,116 @@
+use regex::{Captures, Regex};
+use serde_json::Value;
+use std::sync::OnceLock;
+
+// A `{{name}}` reference, spaces inside the braces are allowed
+const VARIABLE_PATTERN: &str = r"\{\{\s*([^{}\s]+)\s*\}\}";
+
+// Replaces `{{name}}` references with their values, unknown names are left as they are
+pub fn substitute(text: &str, variables: &[(String, String)]) -> String {
+    if variables.is_empty() || !text.contains("{{") {
+        return text.to_string();
+    }
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN
+        .get_or_init(|| Regex::new(VARIABLE_PATTERN).expect("valid variable regex"))
+        .replace_all(text, |captures: &Captures| {
+            variables
+                .iter()
+                .rev()
+                .find(|(name, _)| name == &captures[1])
+                .map(|(_, value)| value.clone())
+                .unwrap_or_else(|| captures[0].to_string())
+        })
+        .into_owned()
+}
+
+fn map_strings(value: &mut Value, f: &impl Fn(&str) -> String) {
+    match value {
+        Value::String(text) => *text = f(text),
+        Value::Array(items) => items.iter_mut().for_each(|item| map_strings(item, f)),
+        Value::Object(members) => members
+            .values_mut()
+            .for_each(|member| map_strings(member, f)),
+        _ => {}
+    }
+}
+
+fn map_json(json: &str, f: impl Fn(&str) -> String) -> String {
+    match serde_json::from_str::<Value>(json) {
+        Ok(mut value) => {
+            map_strings(&mut value, &f);
+            value.to_string()
+        }
+        Err(_) => json.to_string(),
+    }
+}
+
+// Resolves the variables in every string of a serialized command (URL, headers, body...)
+pub fn substitute_json(json: &str, variables: &[(String, String)]) -> String {
+    if variables.is_empty() {
+        return json.to_string();
+    }
+    map_json(json, |text| substitute(text, variables))
+}
+
+// Puts the `{{name}}` references back in place of secret values, so a resolved
+// command can be stored without them
+pub fn mask(text: &str, secrets: &[(String, String)]) -> String {
+    secrets
+        .iter()
+        .filter(|(_, value)| !value.is_empty())
+        .fold(text.to_string(), |text, (name, value)| {
+            text.replace(value.as_str(), &format!("{{{{{}}}}}", name))
+        })
+}
+
+pub fn mask_json(json: &str, secrets: &[(String, String)]) -> String {
+    if secrets.is_empty() {
+        return json.to_string();
+    }
+    map_json(json, |text| mask(text, secrets))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_substitute() {
+        let variables = vec![
+            (
+                String::from("baseUrl"),
+                String::from("https://api.example.com"),
+            ),
+            (String::from("id"), String::from("42")),
+        ];
+        assert_eq!(
+            substitute("{{baseUrl}}/users/{{ id }}?q={{unknown}}", &variables),
+            "https://api.example.com/users/42?q={{unknown}}"
+        );
+        let json = r#"{"url":"{{baseUrl}}/users","opts":[{"Headers":"X-Id: {{id}}"}]}"#;
+        assert_eq!(
+            substitute_json(json, &variables),
+            r#"{"opts":[{"Headers":"X-Id: 42"}],"url":"https://api.example.com/users"}"#
+        );
+        // values are escaped when put back into the JSON
+        let quoted = vec![(String::from("id"), String::from("say \"hi\""))];
+        let resolved = substitute_json(r#"{"body":"{{id}}"}"#, &quoted);
+        let value: Value = serde_json::from_str(&resolved).unwrap();
+        assert_eq!(value["body"], "say \"hi\"");
+    }
+
+    #[test]
+    fn test_mask() {
+        let secrets = vec![(String::from("token"), String::from("s3cret"))];
+        let json = r#"{"url":"https://example.com?key=s3cret","opts":[{"Headers":"Authorization: Bearer s3cret"}]}"#;
+        let masked = mask_json(json, &secrets);
+        assert!(!masked.contains("s3cret"));
+        assert!(masked.contains("Bearer {{token}}"));
+        assert_eq!(
+            substitute_json(&masked, &secrets),
+            substitute_json(json, &secrets)
+        );
+        assert_eq!(mask("no secrets here", &[]), "no secrets here");
+    }
+}
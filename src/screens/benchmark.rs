@@ -0,0 +1,139 @@
+use super::render::render_header_paragraph;
+use super::{centered_rect, Screen, ScreenArea};
+use crate::app::App;
+use crate::display::menuopts::{BENCHMARK_PARAGRAPH, BENCHMARK_TITLE};
+use crate::request::bench::{BenchReport, BENCH_HISTOGRAM_BUCKETS};
+use std::time::Duration;
+use tui::prelude::{Constraint, Direction, Layout};
+use tui::style::{Color, Style};
+use tui::text::{Line, Span};
+use tui::widgets::{BarChart, Block, Borders, Clear, ListState, Paragraph};
+use tui::Frame;
+
+fn ms(duration: Duration) -> String {
+    format!("{:.2}ms", duration.as_secs_f64() * 1000.0)
+}
+
+fn stat_line(label: &str, value: String) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(
+            format!("{label:<10}"),
+            Style::default().fg(Color::LightGreen),
+        ),
+        Span::raw(value),
+    ])
+}
+
+fn report_lines(report: &BenchReport) -> Vec<Line<'static>> {
+    let mut lines = vec![
+        stat_line("Requests", report.total().to_string()),
+        stat_line("Elapsed", format!("{:.2}s", report.elapsed.as_secs_f64())),
+        stat_line("Req/sec", format!("{:.1}", report.requests_per_sec())),
+        Line::raw(""),
+        stat_line("Min", ms(report.min())),
+        stat_line("Mean", ms(report.mean())),
+        stat_line("p50", ms(report.percentile(50.0))),
+        stat_line("p90", ms(report.percentile(90.0))),
+        stat_line("p99", ms(report.percentile(99.0))),
+        stat_line("Max", ms(report.max())),
+        Line::raw(""),
+    ];
+    for (status, count) in report.statuses.iter() {
+        let color = if *status >= 400 {
+            Color::Red
+        } else {
+            Color::Green
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("{status:<10}"), Style::default().fg(color)),
+            Span::raw(count.to_string()),
+        ]));
+    }
+    lines.push(Line::from(vec![
+        Span::styled(
+            format!("{:<10}", "Errors"),
+            Style::default().fg(if report.errors > 0 {
+                Color::Red
+            } else {
+                Color::LightGreen
+            }),
+        ),
+        Span::raw(report.errors.to_string()),
+    ]));
+    if let Some(ref e) = report.last_error {
+        lines.push(Line::styled(e.clone(), Style::default().fg(Color::Red)));
+    }
+    lines
+}
+
+pub fn handle_benchmark_screen(app: &mut App, frame: &mut Frame<'_>) {
+    let menu_options = app.current_screen.get_list(None);
+    let area = centered_rect(frame.size(), ScreenArea::Top);
+    let mut state = ListState::with_selected(ListState::default(), Some(app.cursor));
+    app.state = Some(state.clone());
+    app.state.as_mut().unwrap().select(Some(app.cursor));
+    frame.render_stateful_widget(menu_options, area, &mut state);
+    frame.render_widget(
+        render_header_paragraph(BENCHMARK_PARAGRAPH, BENCHMARK_TITLE, app.config.get_style()),
+        frame.size(),
+    );
+
+    // the screen is redrawn on every tick, so the results fill in as they arrive
+    if let Err(e) = app.poll_benchmark() {
+        app.goto_screen(&Screen::Error(e));
+        return;
+    }
+    let report = app.benchmark.clone().unwrap_or_default();
+    let status = if app.is_benchmark_running() {
+        " (running, press 'c' to cancel)"
+    } else if report.cancelled {
+        " (cancelled)"
+    } else {
+        ""
+    };
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(35), Constraint::Percentage(65)].as_ref())
+        .split(centered_rect(frame.size(), ScreenArea::Center));
+    frame.render_widget(Clear, chunks[0]);
+    frame.render_widget(
+        Paragraph::new(report_lines(&report)).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("* {}{status} *", report.config)),
+        ),
+        chunks[0],
+    );
+    let histogram = report.histogram(BENCH_HISTOGRAM_BUCKETS);
+    let data: Vec<(&str, u64)> = histogram
+        .iter()
+        .map(|(label, count)| (label.as_str(), *count))
+        .collect();
+    let bar_width = (chunks[1].width.saturating_sub(2) / BENCH_HISTOGRAM_BUCKETS as u16)
+        .saturating_sub(1)
+        .max(1);
+    frame.render_widget(Clear, chunks[1]);
+    frame.render_widget(
+        BarChart::default()
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("* Latency Histogram (ms) *"),
+            )
+            .data(&data)
+            .bar_width(bar_width)
+            .bar_gap(1)
+            .bar_style(Style::default().fg(Color::LightBlue))
+            .value_style(Style::default().fg(Color::Black).bg(Color::LightBlue)),
+        chunks[1],
+    );
+
+    match app.selected {
+        Some(0) => app.go_back_screen(),
+        Some(1) => {
+            app.clear_all_options();
+            app.goto_screen(&Screen::Home);
+        }
+        _ => {}
+    }
+}
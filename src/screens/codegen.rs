@@ -0,0 +1,73 @@
+use super::input::input_screen::handle_default_input_screen;
+use super::render::render_header_paragraph;
+use super::{centered_rect, error_alert_box, Screen, ScreenArea};
+use crate::app::{App, InputMode};
+use crate::display::inputopt::InputOpt;
+use crate::display::menuopts::{CODEGEN_PARAGRAPH, CODEGEN_TITLE};
+use crate::request::codegen::LANGUAGES;
+use tui::prelude::{Constraint, Direction, Layout};
+use tui::style::{Color, Modifier, Style};
+use tui::widgets::{Block, Borders, Clear, ListState, Paragraph};
+use tui::Frame;
+
+// The languages are listed on the left, the snippet of the highlighted one is previewed on the right
+pub fn handle_codegen_screen(
+    app: &mut App,
+    frame: &mut Frame<'_>,
+    id: Option<i32>,
+    opt: Option<InputOpt>,
+) {
+    frame.render_widget(
+        render_header_paragraph(CODEGEN_PARAGRAPH, CODEGEN_TITLE, app.config.get_style()),
+        frame.size(),
+    );
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(30), Constraint::Percentage(70)].as_ref())
+        .split(centered_rect(frame.size(), ScreenArea::Center));
+    let list = app.current_screen.get_list(None);
+    let mut state = ListState::with_selected(ListState::default(), Some(app.cursor));
+    app.state = Some(state.clone());
+    frame.render_widget(Clear, chunks[0]);
+    frame.render_stateful_widget(list, chunks[0], &mut state);
+
+    let preview = match LANGUAGES.get(app.cursor) {
+        Some(language) => app
+            .generate_code(id, *language)
+            .unwrap_or_else(|e| format!("Error: {}", e)),
+        None => String::new(),
+    };
+    frame.render_widget(Clear, chunks[1]);
+    frame.render_widget(
+        Paragraph::new(preview)
+            .block(Block::default().borders(Borders::ALL).title("* Preview *"))
+            .style(
+                Style::default()
+                    .fg(Color::White)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        chunks[1],
+    );
+    match opt {
+        Some(InputOpt::AlertMessage(msg)) | Some(InputOpt::RequestError(msg)) => {
+            error_alert_box(frame, &msg);
+        }
+        Some(opt) => handle_default_input_screen(app, frame, opt),
+        None => {}
+    }
+    match app.selected {
+        Some(num) if num < LANGUAGES.len() => {
+            app.goto_screen(&Screen::CodeGen {
+                id,
+                opt: Some(InputOpt::CodeFile(id, LANGUAGES[num])),
+            });
+            app.input_mode = InputMode::Editing;
+        }
+        // cancel
+        Some(_) => match id {
+            Some(id) => app.goto_screen(&Screen::CmdMenu { id, opt: None }),
+            None => app.goto_screen(&Screen::Response(None)),
+        },
+        None => {}
+    }
+}
@@ -2,7 +2,8 @@ use super::{error_alert_box, Screen};
 use crate::app::App;
 use crate::display::inputopt::InputOpt;
 use crate::display::menuopts::{
-    COLLECTION_ALERT_MENU_OPTS, DEFAULT_MENU_PARAGRAPH, POSTMAN_COLLECTION_TITLE,
+    COLLECTION_ALERT_MENU_OPTS, DEFAULT_MENU_PARAGRAPH, ENVIRONMENTS_PARAGRAPH, ENVIRONMENTS_TITLE,
+    ENVIRONMENT_MENU_OPTIONS, POSTMAN_COLLECTION_TITLE,
 };
 use crate::screens::render::handle_screen_defaults;
 use crate::screens::{
@@ -30,8 +31,15 @@ pub fn handle_collection_menu(app: &mut App, frame: &mut Frame<'_>, opt: Option<
         Some(0) => app.goto_screen(&Screen::SavedCollections(Some(InputOpt::ImportCollection))),
         // View Saved Collections
         Some(1) => app.goto_screen(&Screen::ViewSavedCollections),
+        // Environments
+        Some(2) => app.goto_screen(&Screen::Environments(None)),
+        // Create an empty collection to move saved commands to
+        Some(3) => app.goto_screen(&Screen::SavedCollections(Some(InputOpt::NewCollection(
+            Vec::new(),
+            false,
+        )))),
         // Cancel
-        Some(2) => {
+        Some(4) => {
             app.goto_screen(&Screen::Home);
         }
         _ => {}
@@ -65,6 +73,129 @@ pub fn handle_collections_screen(app: &mut App, frame: &mut Frame<'_>) {
     }
 }
 
+// Saved commands resolve their {{variables}} against the selected environment
+pub fn handle_environments_screen(app: &mut App, frame: &mut Frame<'_>, opt: Option<InputOpt>) {
+    let environments = app.db.as_ref().get_environments().unwrap_or_default();
+    let items = match environments.is_empty() {
+        true => None,
+        false => Some(environments.iter().map(|x| x.to_string()).collect()),
+    };
+    let menu_options = app.current_screen.get_list(items);
+    let area = centered_rect(frame.size(), ScreenArea::Center);
+    let mut state = ListState::with_selected(ListState::default(), Some(app.cursor));
+    app.state = Some(state.clone());
+    app.state.as_mut().unwrap().select(Some(app.cursor));
+    frame.render_stateful_widget(menu_options, area, &mut state);
+    frame.render_widget(
+        render_header_paragraph(
+            ENVIRONMENTS_PARAGRAPH,
+            ENVIRONMENTS_TITLE,
+            app.config.get_style(),
+        ),
+        frame.size(),
+    );
+    if let Some(InputOpt::AlertMessage(msg)) | Some(InputOpt::RequestError(msg)) = opt {
+        error_alert_box(frame, &msg);
+    }
+    if let Some(selected) = app.selected {
+        let Some(env) = environments.get(selected) else {
+            return;
+        };
+        // selecting the selected environment again unselects it
+        let (id, msg) = match env.selected {
+            true => (None, format!("Environment {} unselected", env.name)),
+            false => (
+                Some(env.get_id()),
+                format!("Environment {} selected", env.name),
+            ),
+        };
+        let opt = match app.db.select_environment(id) {
+            Ok(_) => InputOpt::AlertMessage(msg),
+            Err(e) => InputOpt::RequestError(e.to_string()),
+        };
+        app.goto_screen(&Screen::Environments(Some(opt)));
+    }
+}
+
+// Asks before an environment (and its secrets) is deleted
+pub fn handle_environment_menu(app: &mut App, frame: &mut Frame<'_>, id: i32) {
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Percentage(25),
+                Constraint::Percentage(50),
+                Constraint::Percentage(25),
+            ]
+            .as_ref(),
+        )
+        .horizontal_margin(5)
+        .split(frame.size());
+    let alert_box = layout[1];
+    let alert_text_chunk = Block::default()
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::Black).fg(Color::LightRed))
+        .title("Environment Menu");
+    let options_box = layout[1].inner(&Margin {
+        vertical: 1,
+        horizontal: 1,
+    });
+    let mut list_state = ListState::with_selected(ListState::default(), Some(app.cursor));
+    app.state = Some(list_state.clone());
+    let items: Vec<ListItem> = ENVIRONMENT_MENU_OPTIONS
+        .iter()
+        .map(|option| ListItem::new(*option))
+        .collect();
+    let list = List::new(items)
+        .block(Block::default())
+        .highlight_style(
+            Style::default()
+                .bg(Color::White)
+                .fg(Color::Black)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">> ");
+    let env_str = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+        .split(alert_box)[1];
+    let Some(env) = app
+        .db
+        .get_environments()
+        .unwrap_or_default()
+        .into_iter()
+        .find(|env| env.get_id() == id)
+    else {
+        app.goto_screen(&Screen::Environments(None));
+        return;
+    };
+    let count = env.variables.len();
+    let paragraph = Paragraph::new(format!(
+        "{:?}\nContains: {} {}",
+        env.name,
+        count,
+        if count == 1 { "variable" } else { "variables" }
+    ))
+    .block(Block::default().borders(Borders::ALL).title("Environment"))
+    .alignment(tui::layout::Alignment::Center);
+    frame.render_widget(paragraph, env_str);
+    frame.render_widget(alert_text_chunk, alert_box);
+    frame.render_stateful_widget(list, options_box, &mut list_state);
+    match app.selected {
+        // delete the environment
+        Some(0) => {
+            let opt = match app.db.delete_environment(id) {
+                Ok(_) => InputOpt::AlertMessage(format!("Environment {} deleted", env.name)),
+                Err(e) => InputOpt::RequestError(e.to_string()),
+            };
+            app.goto_screen(&Screen::Environments(Some(opt)));
+        }
+        // cancel
+        Some(_) => app.goto_screen(&Screen::Environments(None)),
+        None => {}
+    }
+}
+
 pub fn handle_collection_alert_menu(app: &mut App, frame: &mut Frame<'_>, cmd: i32) {
     let layout = Layout::default()
         .direction(Direction::Vertical)
@@ -137,15 +268,22 @@ pub fn handle_collection_alert_menu(app: &mut App, frame: &mut Frame<'_>, cmd: i
             id: Some(cmd),
             opt: None,
         }),
-        Some(1) => app.goto_screen(&Screen::SavedCollections(Some(
+        // Run every request in the collection
+        Some(1) => match app.run_collection(selected.get_id()) {
+            Ok(()) => app.goto_screen(&Screen::RunResults(None)),
+            Err(e) => app.goto_screen(&Screen::SavedCollections(Some(InputOpt::RequestError(
+                format!("Error: {e}"),
+            )))),
+        },
+        Some(2) => app.goto_screen(&Screen::SavedCollections(Some(
             InputOpt::CollectionDescription(selected.get_id()),
         ))),
         // Rename Collection
-        Some(2) => app.goto_screen(&Screen::SavedCollections(Some(InputOpt::RenameCollection(
+        Some(3) => app.goto_screen(&Screen::SavedCollections(Some(InputOpt::RenameCollection(
             selected.get_id(),
         )))),
         // delete collection
-        Some(3) => {
+        Some(4) => {
             if let Err(e) = app.db.as_ref().delete_collection(selected.get_id()) {
                 app.goto_screen(&Screen::SavedCollections(Some(InputOpt::RequestError(
                     format!("Error: {e}"),
@@ -155,8 +293,12 @@ pub fn handle_collection_alert_menu(app: &mut App, frame: &mut Frame<'_>, cmd: i
                 String::from("Success: collection deleted"),
             ))));
         }
+        // export to Postman, HAR or .http
+        Some(5) => app.goto_screen(&Screen::SavedCollections(Some(InputOpt::ExportCollection(
+            Some(selected.get_id()),
+        )))),
         // cancel
-        Some(4) => {
+        Some(6) => {
             app.goto_screen(&Screen::ViewSavedCollections);
         }
         _ => {}
@@ -0,0 +1,132 @@
+use super::render::render_header_paragraph;
+use super::{centered_rect, Screen, ScreenArea};
+use crate::app::App;
+use crate::display::menuopts::{DIFF_PARAGRAPH, DIFF_TITLE};
+use crate::request::diff::{DiffKind, DiffRow};
+use tui::prelude::{Constraint, Direction, Layout};
+use tui::style::{Color, Modifier, Style};
+use tui::text::{Line, Span};
+use tui::widgets::{Block, Borders, Clear, List, ListItem, ListState};
+use tui::Frame;
+
+fn cell(side: &Option<(usize, String)>, color: Option<Color>) -> ListItem<'static> {
+    let style = color.map(|c| Style::default().fg(c)).unwrap_or_default();
+    match side {
+        Some((num, text)) => ListItem::new(Line::from(vec![
+            Span::styled(format!("{:>4} ", num), Style::default().fg(Color::DarkGray)),
+            Span::styled(text.clone(), style),
+        ])),
+        None => ListItem::new(""),
+    }
+}
+
+fn side_colors(row: &DiffRow) -> (Option<Color>, Option<Color>) {
+    match row.kind {
+        DiffKind::Same => (None, None),
+        DiffKind::Removed => (Some(Color::Red), None),
+        DiffKind::Added => (None, Some(Color::Green)),
+        DiffKind::Changed => (Some(Color::Red), Some(Color::Green)),
+    }
+}
+
+pub fn handle_diff_screen(app: &mut App, frame: &mut Frame<'_>) {
+    let Some(diff) = app.diff.as_ref() else {
+        app.goto_screen(&Screen::Error(String::from("Nothing to compare")));
+        return;
+    };
+    frame.render_widget(
+        render_header_paragraph(DIFF_PARAGRAPH, DIFF_TITLE, app.config.get_style()),
+        frame.size(),
+    );
+    let area = centered_rect(frame.size(), ScreenArea::Center)
+        .union(centered_rect(frame.size(), ScreenArea::Bottom));
+    frame.render_widget(Clear, area);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Length(diff.headers.len().clamp(1, 6) as u16 + 2),
+                Constraint::Min(3),
+            ]
+            .as_ref(),
+        )
+        .split(area);
+
+    // Header differences are listed separately from the body
+    let headers: Vec<ListItem> = match diff.headers.is_empty() {
+        true => vec![ListItem::new("No header differences")],
+        false => diff
+            .headers
+            .iter()
+            .map(|change| {
+                let missing = || String::from("(missing)");
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("{}: ", change.name),
+                        Style::default().fg(Color::LightGreen),
+                    ),
+                    Span::styled(
+                        change.left.clone().unwrap_or_else(missing),
+                        Style::default().fg(Color::Red),
+                    ),
+                    Span::raw(" -> "),
+                    Span::styled(
+                        change.right.clone().unwrap_or_else(missing),
+                        Style::default().fg(Color::Green),
+                    ),
+                ]))
+            })
+            .collect(),
+    };
+    frame.render_widget(
+        List::new(headers).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("* Header Differences *"),
+        ),
+        chunks[0],
+    );
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+        .split(chunks[1]);
+    let (left_items, right_items): (Vec<ListItem>, Vec<ListItem>) = diff
+        .rows
+        .iter()
+        .map(|row| {
+            let (left, right) = side_colors(row);
+            (cell(&row.left, left), cell(&row.right, right))
+        })
+        .unzip();
+    let highlight = Style::default().add_modifier(Modifier::REVERSED);
+    let title = |label: &str| match diff.is_identical() {
+        true => format!("* {} * (identical)", label),
+        false => format!("* {} *", label),
+    };
+    // both sides share the cursor so they scroll together with j/k
+    let mut state = ListState::with_selected(ListState::default(), Some(app.cursor));
+    app.state = Some(state.clone());
+    frame.render_stateful_widget(
+        List::new(left_items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(title(&diff.left_label)),
+            )
+            .highlight_style(highlight),
+        columns[0],
+        &mut state.clone(),
+    );
+    frame.render_stateful_widget(
+        List::new(right_items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(title(&diff.right_label)),
+            )
+            .highlight_style(highlight),
+        columns[1],
+        &mut state,
+    );
+}
@@ -0,0 +1,238 @@
+use super::input::input_screen::handle_default_input_screen;
+use super::render::render_header_paragraph;
+use super::{centered_rect, error_alert_box, Screen, ScreenArea};
+use crate::app::{App, InputMode};
+use crate::display::inputopt::InputOpt;
+use crate::display::menuopts::{
+    HISTORY_MENU_OPTIONS, HISTORY_PARAGRAPH, HISTORY_TITLE, SUCCESS_MESSAGE,
+};
+use crate::request::curl::Curl;
+use crate::request::variables::substitute_json;
+use tui::prelude::{Constraint, Direction, Layout, Margin};
+use tui::style::{Color, Modifier, Style};
+use tui::text::{Line, Span};
+use tui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use tui::Frame;
+
+// The CLI command for a request stored in the history
+fn command_string(json: &str) -> Result<String, String> {
+    let mut cmd: Curl = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    Ok(cmd.get_command_string())
+}
+
+pub fn handle_history_screen(
+    app: &mut App,
+    frame: &mut Frame<'_>,
+    filter: Option<String>,
+    opt: Option<InputOpt>,
+) {
+    let history = app
+        .db
+        .as_ref()
+        .get_history(filter.as_deref())
+        .unwrap_or_default();
+    let items = match history.is_empty() {
+        true => None,
+        false => Some(history.iter().map(|x| x.to_string()).collect()),
+    };
+    let title = match (app.diff_base, filter.as_ref()) {
+        (Some(base), _) => format!("Select a response to compare with #{}", base),
+        (None, Some(filter)) => format!("Request History | Filter: {}", filter),
+        (None, None) => String::from("Request History"),
+    };
+    let menu_options = app
+        .current_screen
+        .get_list(items)
+        .block(Block::default().borders(Borders::ALL).title(title));
+    let area = centered_rect(frame.size(), ScreenArea::Center);
+    let mut state = ListState::with_selected(ListState::default(), Some(app.cursor));
+    app.state = Some(state.clone());
+    app.state.as_mut().unwrap().select(Some(app.cursor));
+    frame.render_stateful_widget(menu_options, area, &mut state);
+    frame.render_widget(
+        render_header_paragraph(HISTORY_PARAGRAPH, HISTORY_TITLE, app.config.get_style()),
+        frame.size(),
+    );
+
+    match opt {
+        Some(InputOpt::AlertMessage(msg)) | Some(InputOpt::RequestError(msg)) => {
+            error_alert_box(frame, &msg);
+        }
+        Some(opt) => handle_default_input_screen(app, frame, opt),
+        None => {}
+    }
+    if let Some(selected) = app.selected {
+        if let Some(entry) = history.get(selected) {
+            match app.diff_base.take() {
+                Some(base) => {
+                    let diff = app.diff_history(base, entry.get_id());
+                    app.show_diff(diff);
+                }
+                None => app.goto_screen(&Screen::HistoryMenu(entry.get_id())),
+            }
+        }
+    }
+}
+
+pub fn handle_history_menu(app: &mut App, frame: &mut Frame<'_>, id: i32) {
+    let entry = match app.db.as_ref().get_history_entry(id) {
+        Ok(entry) => entry,
+        Err(e) => {
+            app.goto_screen(&Screen::History {
+                filter: None,
+                opt: Some(InputOpt::RequestError(format!("Error: {}", e))),
+            });
+            return;
+        }
+    };
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Percentage(25),
+                Constraint::Percentage(50),
+                Constraint::Percentage(25),
+            ]
+            .as_ref(),
+        )
+        .horizontal_margin(5)
+        .split(frame.size());
+    let options_box = layout[1].inner(&Margin {
+        vertical: 1,
+        horizontal: 15,
+    });
+    let mut list_state = ListState::with_selected(ListState::default(), Some(app.cursor));
+    app.state = Some(list_state.clone());
+    let items: Vec<ListItem> = HISTORY_MENU_OPTIONS
+        .iter()
+        .map(|option| ListItem::new(*option))
+        .collect();
+    let list = List::new(items)
+        .block(Block::default())
+        .highlight_style(
+            Style::default()
+                .bg(Color::Blue)
+                .fg(Color::Black)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">> ");
+    let detail = |label: &str, value: String| {
+        Line::default().spans(vec![
+            Span::styled(
+                format!("{}: ", label),
+                Style::default().fg(Color::LightGreen),
+            ),
+            Span::styled(value, Style::default().fg(Color::White)),
+        ])
+    };
+    let details = List::new(vec![
+        Line::raw("\n"),
+        detail("Request", format!("{} {}", entry.method, entry.url)),
+        detail(
+            "Status",
+            entry
+                .status
+                .map(|s| s.to_string())
+                .unwrap_or(String::from("No response")),
+        ),
+        detail("Duration", format!("{}ms", entry.duration_ms)),
+        detail("Size", format!("{} bytes", entry.size)),
+        detail("Sent", entry.get_age()),
+    ])
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("History Details"),
+    )
+    .style(Style::default().fg(Color::Blue));
+    let body = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+        .split(layout[1])[1];
+    frame.render_stateful_widget(list, options_box, &mut list_state);
+    frame.render_widget(details, layout[0]);
+    frame.render_widget(
+        Paragraph::new(entry.body.as_str())
+            .block(Block::default().borders(Borders::ALL).title("* Response *"))
+            .wrap(Wrap::default()),
+        body,
+    );
+    match app.selected {
+        // replay request
+        Some(0) => {
+            // secrets were stored as their {{name}}
+            let json = substitute_json(entry.get_curl_json(), &app.secret_variables());
+            let result = app.execute_saved_command(&json);
+            app.show_response(result);
+        }
+        // pick another entry to compare against
+        Some(1) => {
+            app.goto_screen(&Screen::History {
+                filter: None,
+                opt: None,
+            });
+            app.diff_base = Some(id);
+        }
+        // compare with an example response saved to a file
+        Some(2) => {
+            app.goto_screen(&Screen::History {
+                filter: None,
+                opt: Some(InputOpt::DiffExample(id)),
+            });
+            app.input_mode = InputMode::Editing;
+        }
+        // promote to saved command
+        Some(3) => {
+            let saved = command_string(entry.get_curl_json()).and_then(|cmd| {
+                app.db
+                    .add_command(&cmd, entry.get_curl_json().to_string(), None)
+                    .map_err(|e| e.to_string())
+            });
+            app.goto_screen(&Screen::History {
+                filter: None,
+                opt: Some(match saved {
+                    Ok(()) => InputOpt::AlertMessage(String::from(SUCCESS_MESSAGE)),
+                    Err(e) => InputOpt::RequestError(format!("Error: {}", e)),
+                }),
+            });
+        }
+        // copy to clipboard
+        Some(4) => {
+            let copied = app.copy_curl_command(Some(entry.get_curl_json()));
+            app.goto_screen(&Screen::History {
+                filter: None,
+                opt: Some(match copied {
+                    Ok(()) => {
+                        InputOpt::AlertMessage(String::from("CLI Command copied to clipboard"))
+                    }
+                    Err(e) => InputOpt::RequestError(format!("Error: {}", e)),
+                }),
+            });
+        }
+        // copy as a wget command
+        Some(5) => {
+            let msg = match app.copy_wget_command(Some(entry.get_curl_json())) {
+                Ok(msg) => InputOpt::AlertMessage(msg),
+                Err(e) => InputOpt::RequestError(format!("Error: {}", e)),
+            };
+            app.goto_screen(&Screen::History {
+                filter: None,
+                opt: Some(msg),
+            });
+        }
+        // delete entry
+        Some(6) => {
+            let msg = match app.db.delete_history_entry(id) {
+                Ok(()) => InputOpt::AlertMessage(String::from("History entry deleted")),
+                Err(e) => InputOpt::RequestError(format!("Error: {}", e)),
+            };
+            app.goto_screen(&Screen::History {
+                filter: None,
+                opt: Some(msg),
+            });
+        }
+        // cancel
+        Some(7) => app.go_back_screen(),
+        _ => {}
+    }
+}
@@ -3,7 +3,9 @@ use crate::display::menuopts::{
     CERT_ERROR, HEADER_ERROR, PARSE_INT_ERROR, SOCKET_ERROR, UPLOAD_FILEPATH_ERROR,
 };
 use crate::display::AppOptions;
+use crate::request::bench::BenchConfig;
 use crate::request::curl::AuthKind;
+use crate::request::report::write_report;
 use crate::screens::Screen;
 use crate::{app::InputMode, display::inputopt::InputOpt};
 use std::path::Path;
@@ -49,12 +51,11 @@ pub fn handle_default_input_screen(app: &mut App, frame: &mut Frame<'_>, opt: In
                 url.clear();
             }
         }
-        InputOpt::Auth(ref kind) => {
-            if kind.has_token() {
-                let auth = app.command.get_token();
-                if auth.is_some() && app.input.value().is_empty() && app.input.cursor() == 0 {
+        InputOpt::Auth(ref kind) if kind.has_token() => {
+            if let Some(auth) = app.command.get_token() {
+                if app.input.value().is_empty() && app.input.cursor() == 0 {
                     let _ = app.input.handle(InputRequest::InsertChar(' ')).is_some();
-                    for ch in auth.unwrap().chars() {
+                    for ch in auth.chars() {
                         if app.input.handle(InputRequest::InsertChar(ch)).is_some() {}
                     }
                 }
@@ -62,9 +63,11 @@ pub fn handle_default_input_screen(app: &mut App, frame: &mut Frame<'_>, opt: In
         }
         InputOpt::UploadFile => {
             let file = app.command.get_upload_file();
-            if file.is_some() && app.input.value().is_empty() && app.input.cursor() == 0 {
+            if let Some(file) =
+                file.filter(|_| app.input.value().is_empty() && app.input.cursor() == 0)
+            {
                 let _ = app.input.handle(InputRequest::InsertChar(' ')).is_some();
-                for ch in file.unwrap().chars() {
+                for ch in file.chars() {
                     if app.input.handle(InputRequest::InsertChar(ch)).is_some() {}
                 }
             }
@@ -78,14 +81,16 @@ pub fn handle_default_input_screen(app: &mut App, frame: &mut Frame<'_>, opt: In
             }
             let socket = app.command.opts.iter().find_map(|f| {
                 if let AppOptions::UnixSocket(s) = f {
-                    Some(s)
+                    Some(s.clone())
                 } else {
                     None
                 }
             });
-            if socket.is_some() && app.input.value().is_empty() && app.input.cursor() == 0 {
+            if let Some(socket) =
+                socket.filter(|_| app.input.value().is_empty() && app.input.cursor() == 0)
+            {
                 let _ = app.input.handle(InputRequest::InsertChar(' ')).is_some();
-                for ch in socket.unwrap().chars() {
+                for ch in socket.chars() {
                     if app.input.handle(InputRequest::InsertChar(ch)).is_some() {}
                 }
             }
@@ -180,6 +185,8 @@ fn is_valid_unix_socket_path(path: &str) -> Result<(), String> {
     }
 }
 
+// Handles the submitted prompt, then goes back to the prompt's return screen.
+// The prompts added since that show an alert or the next prompt return early
 pub fn parse_input(message: String, opt: InputOpt, app: &mut App) {
     match opt {
         InputOpt::URL => {
@@ -284,11 +291,9 @@ pub fn parse_input(message: String, opt: InputOpt, app: &mut App) {
             // This means they have executed the HTTP Request, and want to write to a file
             app.command.set_outfile(&message);
             if let Err(e) = app.command.write_output() {
-                app.goto_screen(&Screen::Response(e.to_string()));
+                app.goto_screen(&Screen::Error(e.to_string()));
             } else {
-                app.goto_screen(&Screen::Response(String::from(
-                    app.response.as_ref().unwrap_or(&String::new()).as_str(),
-                )));
+                app.goto_screen(&Screen::Response(None));
             }
         }
         InputOpt::RequestBody => {
@@ -308,15 +313,20 @@ pub fn parse_input(message: String, opt: InputOpt, app: &mut App) {
             }
         }
         InputOpt::ImportCollection => {
-            if let Err(e) = app.import_postman_collection(&message) {
-                app.goto_screen(&Screen::SavedCollections(Some(InputOpt::AlertMessage(
+            match app.import_collection(&message) {
+                Err(e) => app.goto_screen(&Screen::SavedCollections(Some(InputOpt::AlertMessage(
                     e.to_string(),
-                ))));
-            } else {
-                app.goto_screen(&Screen::SavedCollections(Some(InputOpt::AlertMessage(
-                    String::from("Collection Imported"),
-                ))));
+                )))),
+                Ok(summary) if summary.skipped.is_empty() => app.goto_screen(
+                    &Screen::SavedCollections(Some(InputOpt::AlertMessage(summary.to_string()))),
+                ),
+                // the list of what was left out doesn't fit in the alert
+                Ok(summary) => {
+                    app.goto_screen(&Screen::SavedCollections(None));
+                    app.goto_screen(&Screen::Error(summary.to_string()));
+                }
             }
+            return;
         }
         InputOpt::KeyLabel(id) => match app.db.set_key_label(id, &message) {
             Ok(_) => app.goto_screen(&Screen::SavedKeys(Some(InputOpt::AlertMessage(
@@ -340,6 +350,149 @@ pub fn parse_input(message: String, opt: InputOpt, app: &mut App) {
         InputOpt::Auth(ref auth) => {
             parse_auth(auth, app, &message);
         }
+        InputOpt::Benchmark(id) => {
+            match message.parse::<BenchConfig>() {
+                Ok(config) => match app.run_benchmark(id, &config) {
+                    Ok(()) => app.goto_screen(&Screen::Benchmark),
+                    Err(e) => app.goto_screen(&Screen::Error(e)),
+                },
+                Err(e) => app.goto_screen(&Screen::Error(e)),
+            }
+            return;
+        }
+        InputOpt::Charset => {
+            match app.set_response_charset(&message) {
+                Ok(()) => app.goto_screen(&Screen::Response(None)),
+                Err(e) => app.goto_screen(&Screen::Error(e)),
+            }
+            return;
+        }
+        InputOpt::BodyFilter => {
+            app.viewer.status = app
+                .viewer
+                .set_filter(&message)
+                .err()
+                .map(|e| format!("Error: {}", e));
+        }
+        InputOpt::BodySearch => {
+            // already searched while typing, unless the query was pasted
+            if app.viewer.search.as_deref().unwrap_or_default() != message {
+                app.viewer.status = app
+                    .viewer
+                    .set_search(&message)
+                    .err()
+                    .map(|e| format!("Error: {}", e));
+            }
+            // keep the cursor on the match it moved to while typing
+            let cursor = app.cursor;
+            app.goto_screen(&Screen::ViewBody(None));
+            app.cursor = cursor;
+            return;
+        }
+        InputOpt::SaveBody => {
+            app.viewer.status = Some(match std::fs::write(&message, app.viewer.text()) {
+                Ok(()) => format!("Saved to {}", message),
+                Err(e) => format!("Error: {}", e),
+            });
+        }
+        InputOpt::ExportCollection(id) => {
+            let msg = match app.export_commands(id, &message) {
+                Ok(()) => {
+                    app.marked.clear();
+                    InputOpt::AlertMessage(format!("Exported to {}", message))
+                }
+                Err(e) => InputOpt::RequestError(format!("Error: {}", e)),
+            };
+            match id {
+                Some(_) => app.goto_screen(&Screen::SavedCollections(Some(msg))),
+                None => app.goto_screen(&Screen::SavedCommands {
+                    id: None,
+                    opt: Some(msg),
+                }),
+            }
+            return;
+        }
+        InputOpt::ExportHistory(ref filter) => {
+            let msg = match app.export_history_har(filter.as_deref(), &message) {
+                Ok(()) => InputOpt::AlertMessage(format!("History exported to {}", message)),
+                Err(e) => InputOpt::RequestError(format!("Error: {}", e)),
+            };
+            app.goto_screen(&Screen::History {
+                filter: filter.clone(),
+                opt: Some(msg),
+            });
+            return;
+        }
+        InputOpt::NewCollection(ref ids, copy) => {
+            match (app.create_collection(&message, ids, copy), ids.is_empty()) {
+                (Ok(_), true) => app.goto_screen(&Screen::SavedCollections(Some(
+                    InputOpt::AlertMessage(format!("Created collection {}", message)),
+                ))),
+                (Ok(id), false) => app.goto_screen(&Screen::SavedCommands {
+                    id: Some(id),
+                    opt: Some(InputOpt::AlertMessage(format!(
+                        "Created collection {}",
+                        message
+                    ))),
+                }),
+                (Err(e), true) => app.goto_screen(&Screen::SavedCollections(Some(
+                    InputOpt::RequestError(format!("Error: {}", e)),
+                ))),
+                (Err(e), false) => app.goto_screen(&Screen::MoveCommands {
+                    ids: ids.clone(),
+                    copy,
+                    opt: Some(InputOpt::RequestError(format!("Error: {}", e))),
+                }),
+            }
+            return;
+        }
+        InputOpt::CodeFile(id, language) => {
+            let msg = match app.generate_code(id, language) {
+                Ok(code) if message.is_empty() => match app.copy_to_clipboard(&code) {
+                    Ok(()) => {
+                        InputOpt::AlertMessage(format!("{} code copied to clipboard", language))
+                    }
+                    Err(e) => InputOpt::RequestError(format!("Error: {}", e)),
+                },
+                Ok(code) => match std::fs::write(&message, code) {
+                    Ok(()) => {
+                        InputOpt::AlertMessage(format!("{} code written to {}", language, message))
+                    }
+                    Err(e) => InputOpt::RequestError(format!("Error: {}", e)),
+                },
+                Err(e) => InputOpt::RequestError(format!("Error: {}", e)),
+            };
+            app.goto_screen(&Screen::CodeGen { id, opt: Some(msg) });
+            return;
+        }
+        InputOpt::DiffExample(id) => {
+            let diff = app.diff_example(&message, id);
+            app.show_diff(diff);
+            return;
+        }
+        InputOpt::DiffEnvironments(id) => {
+            let diff = app.diff_environments(id, &message);
+            app.show_diff(diff);
+            return;
+        }
+        InputOpt::HistoryFilter => {
+            app.goto_screen(&Screen::History {
+                filter: Some(message).filter(|f| !f.is_empty()),
+                opt: None,
+            });
+            return;
+        }
+        InputOpt::RunReport(format) => {
+            let written = match app.collection_run.as_ref() {
+                Some(run) => write_report(&message, run, format).map_err(|e| e.to_string()),
+                None => Err(String::from("No collection has been run")),
+            };
+            app.goto_screen(&Screen::RunResults(Some(match written {
+                Ok(()) => InputOpt::AlertMessage(format!("{format} report written to {message}")),
+                Err(e) => InputOpt::RequestError(format!("Error: {e}")),
+            })));
+            return;
+        }
         _ => {}
     }
     app.goto_screen(&opt.get_return_screen());
@@ -15,13 +15,24 @@ pub mod response;
 pub mod input;
 // Auth Screen
 pub mod auth;
+// Benchmark Results Screen
+pub mod benchmark;
+// Code Generation Screen
+pub mod codegen;
 pub mod render;
 pub mod saved_keys;
 pub use screen::Screen;
 pub mod collections;
+// Response Diff Screen
+pub mod diff;
 pub mod error;
 pub mod headers;
+// Request History Screens
+pub mod history;
+pub mod run_results;
 pub mod saved_commands;
+// Response Body Viewer
+pub mod view_body;
 pub fn error_alert_box(frame: &mut Frame<'_>, error_message: &str) -> Rect {
     let layout = Layout::default()
         .direction(Direction::Vertical)
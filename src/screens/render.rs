@@ -5,8 +5,8 @@ use super::*;
 use crate::display::inputopt::InputOpt;
 use crate::display::menuopts::{
     API_KEY_PARAGRAPH, API_KEY_TITLE, AUTH_MENU_TITLE, DEFAULT_MENU_PARAGRAPH, DEFAULT_MENU_TITLE,
-    ERROR_MENU_TITLE, INPUT_MENU_TITLE, POSTMAN_COLLECTION_TITLE, SAVED_COMMANDS_TITLE,
-    SUCCESS_MENU_TITLE, VIEW_BODY_TITLE,
+    ERROR_MENU_TITLE, INPUT_MENU_TITLE, MOVE_COMMANDS_TITLE, POSTMAN_COLLECTION_TITLE,
+    RUN_RESULTS_TITLE, SAVED_COMMANDS_TITLE, SUCCESS_MENU_TITLE, VIEW_BODY_TITLE,
 };
 use crate::display::AppOptions;
 use crate::{app::App, display::menuopts::SAVED_COMMANDS_PARAGRAPH};
@@ -26,7 +26,7 @@ use super::{centered_rect, Screen, ScreenArea};
 
 /// Renders the user interface widgets.
 pub fn render(app: &mut App, frame: &mut Frame<'_>) {
-    if app.response.is_none() {
+    if app.response().is_none() {
         // Render Display Options *******************************************
         // This is the box of options the user has selected so far in their current
         // command. This is rendered on the bottom of the screen. Each time we change
@@ -65,8 +65,15 @@ pub fn render(app: &mut App, frame: &mut Frame<'_>) {
         // ******************************************************************************************************
     } else {
         let area = centered_rect(frame.size(), ScreenArea::Bottom);
-        let response = app.response.clone().unwrap();
-        let paragraph = Paragraph::new(Text::from(response.as_str()))
+        // only the lines that fit are rendered, the body can be very large
+        let response = app
+            .response()
+            .unwrap_or_default()
+            .lines()
+            .take(area.height as usize)
+            .collect::<Vec<_>>()
+            .join("\n");
+        let paragraph = Paragraph::new(Text::from(response))
             .block(
                 Block::default()
                     .borders(Borders::ALL)
@@ -98,10 +105,12 @@ pub fn handle_screen_defaults(app: &mut App, frame: &mut Frame<'_>) {
         Screen::Authentication => (&DEFAULT_MENU_PARAGRAPH, &AUTH_MENU_TITLE),
         Screen::Success => (&DEFAULT_MENU_PARAGRAPH, &SUCCESS_MENU_TITLE),
         Screen::Error(_) => (&DEFAULT_MENU_PARAGRAPH, &ERROR_MENU_TITLE),
-        Screen::ViewBody => (&DEFAULT_MENU_PARAGRAPH, &VIEW_BODY_TITLE),
+        Screen::ViewBody(_) => (&DEFAULT_MENU_PARAGRAPH, &VIEW_BODY_TITLE),
         Screen::SavedKeys(_) => (&API_KEY_PARAGRAPH, &API_KEY_TITLE),
         Screen::HeaderAddRemove => (&DEFAULT_MENU_PARAGRAPH, &DEFAULT_MENU_TITLE),
         Screen::SavedCollections(_) => (&DEFAULT_MENU_PARAGRAPH, &POSTMAN_COLLECTION_TITLE),
+        Screen::RunResults(_) => (&DEFAULT_MENU_PARAGRAPH, &RUN_RESULTS_TITLE),
+        Screen::MoveCommands { .. } => (&DEFAULT_MENU_PARAGRAPH, &MOVE_COMMANDS_TITLE),
         _ => (&DEFAULT_MENU_PARAGRAPH, &DEFAULT_MENU_TITLE),
     };
     frame.render_widget(
@@ -124,6 +133,10 @@ pub fn handle_screen(app: &mut App, frame: &mut Frame<'_>, screen: Screen) {
                     }),
                     2 => app.goto_screen(&Screen::SavedCollections(None)),
                     3 => app.goto_screen(&Screen::SavedKeys(None)),
+                    4 => app.goto_screen(&Screen::History {
+                        filter: None,
+                        opt: None,
+                    }),
                     _ => {}
                 }
             }
@@ -134,14 +147,7 @@ pub fn handle_screen(app: &mut App, frame: &mut Frame<'_>, screen: Screen) {
         Screen::InputMenu(opt) => {
             input::input_screen::handle_default_input_screen(app, frame, opt.clone());
         }
-        Screen::ViewBody => {
-            let area = centered_rect(frame.size(), ScreenArea::Center);
-            let response = app.response.clone().unwrap_or_default();
-            let paragraph = Paragraph::new(Text::from(response.as_str()))
-                .style(app.config.get_style())
-                .alignment(Alignment::Center);
-            frame.render_widget(paragraph, area);
-        }
+        Screen::ViewBody(opt) => view_body::handle_view_body_screen(app, frame, opt),
         // REQUEST MENU *********************************************************
         Screen::RequestMenu(e) => {
             handle_request_menu_screen(app, frame, e.as_ref());
@@ -153,10 +159,7 @@ pub fn handle_screen(app: &mut App, frame: &mut Frame<'_>, screen: Screen) {
         // SUCESSS SCREEN *******************************************************
         Screen::Success => handle_screen_defaults(app, frame),
         // RESPONSE SCREEN ******************************************************
-        Screen::Response(resp) => {
-            app.set_response(&resp);
-            response::handle_response_screen(app, frame, resp.to_string());
-        }
+        Screen::Response(opt) => response::handle_response_screen(app, frame, opt),
         Screen::SavedCommands { id, opt } => {
             saved_commands::handle_saved_commands_screen(app, frame, id, opt);
         }
@@ -193,14 +196,41 @@ pub fn handle_screen(app: &mut App, frame: &mut Frame<'_>, screen: Screen) {
         Screen::ViewSavedCollections => {
             super::collections::handle_collections_screen(app, frame);
         }
+        Screen::Environments(opt) => {
+            super::collections::handle_environments_screen(app, frame, opt);
+        }
+        Screen::EnvironmentMenu(id) => {
+            super::collections::handle_environment_menu(app, frame, id);
+        }
+        Screen::RunResults(opt) => {
+            super::run_results::handle_run_results_screen(app, frame, opt);
+        }
+        Screen::Benchmark => {
+            super::benchmark::handle_benchmark_screen(app, frame);
+        }
+        Screen::History { filter, opt } => {
+            super::history::handle_history_screen(app, frame, filter, opt);
+        }
+        Screen::HistoryMenu(id) => {
+            super::history::handle_history_menu(app, frame, id);
+        }
+        Screen::Diff => {
+            super::diff::handle_diff_screen(app, frame);
+        }
+        Screen::MoveCommands { ids, copy, opt } => {
+            saved_commands::handle_move_commands_screen(app, frame, ids, copy, opt);
+        }
+        Screen::CodeGen { id, opt } => {
+            super::codegen::handle_codegen_screen(app, frame, id, opt);
+        }
         _ => {}
     }
 }
 
-fn handle_display_options(opts: &[AppOptions]) -> Vec<Line> {
+fn handle_display_options(opts: &[AppOptions]) -> Vec<Line<'_>> {
     opts.iter()
         .map(|x| Line::from(x.get_value()))
-        .collect::<Vec<Line>>()
+        .collect::<Vec<Line<'_>>>()
 }
 
 #[rustfmt::skip]
@@ -49,27 +49,15 @@ pub fn handle_request_menu_screen(app: &mut App, frame: &mut Frame<'_>, opt: Opt
         // Execute command
         Some(9) => {
             if app.command.get_url().is_empty()
-                && !app
-                    .command
-                    .opts
-                    .iter()
-                    .any(|x| *x == AppOptions::SaveCommand)
+                && !app.command.opts.contains(&AppOptions::SaveCommand)
             {
                 app.goto_screen(&Screen::RequestMenu(Some(InputOpt::RequestError(
                     String::from(VALID_COMMAND_ERROR),
                 ))));
                 return;
             }
-            match app.execute_command() {
-                Ok(()) => {
-                    let response = app.command.get_response().unwrap_or_default();
-                    app.set_response(&response);
-                    app.goto_screen(&Screen::Response(response));
-                }
-                Err(e) => {
-                    app.goto_screen(&Screen::Error(e.to_string()));
-                }
-            }
+            let result = app.execute_command();
+            app.show_response(result);
         }
         // more options
         Some(10) => app.goto_screen(&Screen::MoreFlags),
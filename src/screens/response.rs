@@ -1,13 +1,63 @@
 use crate::app::App;
 use crate::display::inputopt::InputOpt;
 use crate::request::response::Response;
-use crate::screens::{centered_rect, screen::Screen, ScreenArea};
-use tui::text::Text;
-use tui::widgets::{ListState, Paragraph};
+use crate::request::summary::ResponseSummary;
+use crate::screens::{centered_rect, error_alert_box, screen::Screen, ScreenArea};
+use tui::prelude::{Constraint, Direction, Layout};
+use tui::style::{Color, Modifier, Style};
+use tui::text::{Line, Span, Text};
+use tui::widgets::{Block, Borders, Clear, ListState, Paragraph};
 use tui::Frame;
 
-pub fn handle_response_screen(app: &mut App, frame: &mut Frame<'_>, resp: String) {
-    let area = centered_rect(frame.size(), ScreenArea::Center);
+fn status_color(status: Option<u32>) -> Color {
+    match status.unwrap_or_default() / 100 {
+        2 => Color::Green,
+        3 => Color::Cyan,
+        4 => Color::Yellow,
+        5 => Color::Red,
+        _ => Color::Gray,
+    }
+}
+
+fn summary_paragraph(summary: &ResponseSummary) -> Paragraph<'static> {
+    let label = Style::default().fg(Color::DarkGray);
+    let mut details = vec![
+        Span::styled(
+            summary.status_text(),
+            Style::default()
+                .fg(status_color(summary.status))
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled("  Time ", label),
+        Span::raw(summary.duration_text()),
+        Span::styled("  Size ", label),
+        Span::raw(summary.size_text()),
+    ];
+    if let Some(ref content_type) = summary.content_type {
+        details.push(Span::styled("  Type ", label));
+        details.push(Span::raw(content_type.clone()));
+    }
+    if let Some(ref version) = summary.version {
+        details.push(Span::styled("  ", label));
+        details.push(Span::raw(version.clone()));
+    }
+    let url = Line::from(vec![
+        Span::styled("URL ", label),
+        Span::raw(summary.url.clone().unwrap_or_default()),
+    ]);
+    Paragraph::new(vec![Line::from(details), url])
+        .block(Block::default().borders(Borders::ALL).title("* Response *"))
+}
+
+pub fn handle_response_screen(app: &mut App, frame: &mut Frame<'_>, opt: Option<InputOpt>) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(4), Constraint::Min(0)].as_ref())
+        .split(centered_rect(frame.size(), ScreenArea::Center));
+    let summary = app.command.get_response_summary();
+    frame.render_widget(Clear, chunks[0]);
+    frame.render_widget(summary_paragraph(&summary), chunks[0]);
+    let area = chunks[1];
     let new_list = app.current_screen.get_list(None);
     let mut state = ListState::with_selected(ListState::default(), Some(app.cursor));
     if !app.items.is_empty() {
@@ -17,6 +67,9 @@ pub fn handle_response_screen(app: &mut App, frame: &mut Frame<'_>, resp: String
     app.state = Some(state.clone());
     app.state.as_mut().unwrap().select(Some(app.cursor));
     frame.render_stateful_widget(new_list, area, &mut state);
+    if let Some(InputOpt::AlertMessage(msg)) = opt {
+        error_alert_box(frame, &msg);
+    }
     if let Some(num) = app.selected {
         match num {
             0 => {
@@ -24,9 +77,14 @@ pub fn handle_response_screen(app: &mut App, frame: &mut Frame<'_>, resp: String
             }
             // View response headers
             1 => {
+                if !app.command.get_response_headers().is_empty() {
+                    app.open_headers_viewer();
+                    app.goto_screen(&Screen::ViewBody(None));
+                    return;
+                }
                 let area_2 = centered_rect(frame.size(), ScreenArea::Center);
                 // Check for response error here
-                let response = match Response::from_raw_string(resp.as_str()) {
+                let response = match Response::from_raw_string(app.response().unwrap_or_default()) {
                     Ok(resp) => resp,
                     Err(e) => {
                         // Hit the error screen.
@@ -41,17 +99,52 @@ pub fn handle_response_screen(app: &mut App, frame: &mut Frame<'_>, resp: String
             }
             // View response body
             2 => {
-                app.goto_screen(&Screen::ViewBody);
+                app.open_body_viewer();
+                app.goto_screen(&Screen::ViewBody(None));
             }
             // Copy to clipboard
-            3 => {
-                let cmd_str = app.command.get_command_string();
-                app.copy_to_clipboard(&cmd_str).unwrap_or_else(|e| {
-                    app.goto_screen(&Screen::Error(e));
+            3 => match app.copy_curl_command(None) {
+                Ok(()) => app.goto_screen(&Screen::Success),
+                Err(e) => app.goto_screen(&Screen::Error(e)),
+            },
+            // Copy the same request as a wget command, with the options it can't carry over
+            4 => match app.copy_wget_command(None) {
+                Ok(msg) => app.goto_screen(&Screen::Response(Some(InputOpt::AlertMessage(msg)))),
+                Err(e) => app.goto_screen(&Screen::Error(e)),
+            },
+            // Benchmark the request
+            5 => {
+                app.goto_screen(&Screen::InputMenu(InputOpt::Benchmark(None)));
+            }
+            // Compare with a previous response, the current one is the latest in the history
+            6 => {
+                let latest = app.db.get_history(None).unwrap_or_default();
+                match latest.first() {
+                    Some(entry) => {
+                        let id = entry.get_id();
+                        app.goto_screen(&Screen::History {
+                            filter: None,
+                            opt: None,
+                        });
+                        app.diff_base = Some(id);
+                    }
+                    None => app.goto_screen(&Screen::Error(String::from(
+                        "No request history to compare with",
+                    ))),
+                }
+            }
+            // Decode the body with another charset, when the server declared the wrong one
+            7 => {
+                app.goto_screen(&Screen::InputMenu(InputOpt::Charset));
+            }
+            // Render the request as code for another language or client
+            8 => {
+                app.goto_screen(&Screen::CodeGen {
+                    id: None,
+                    opt: None,
                 });
-                app.goto_screen(&Screen::Success);
             }
-            4 => {
+            9 => {
                 // Return To Home
                 app.clear_all_options();
                 app.goto_screen(&Screen::Home);
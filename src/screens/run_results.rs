@@ -0,0 +1,80 @@
+use super::input::input_screen::handle_default_input_screen;
+use super::render::handle_screen_defaults;
+use super::{centered_rect, error_alert_box, Screen, ScreenArea};
+use crate::app::App;
+use crate::display::inputopt::InputOpt;
+use crate::request::report::ReportFormat;
+use tui::style::{Color, Style};
+use tui::text::{Line, Span};
+use tui::widgets::{Block, BorderType, Borders, Clear, List, ListItem};
+use tui::Frame;
+
+pub fn handle_run_results_screen(app: &mut App, frame: &mut Frame<'_>, opt: Option<InputOpt>) {
+    handle_screen_defaults(app, frame);
+    let run = app.collection_run.clone().unwrap_or_default();
+    let items: Vec<ListItem> = run
+        .results
+        .iter()
+        .map(|result| {
+            let (icon, color) = if result.passed() {
+                ("✔", Color::Green)
+            } else {
+                ("✘", Color::Red)
+            };
+            let status = match result.status {
+                Some(status) => status.to_string(),
+                None => String::from("---"),
+            };
+            let mut spans = vec![
+                Span::styled(format!(" {icon} {status} "), Style::default().fg(color)),
+                Span::raw(format!("{}  ", result.name)),
+                Span::styled(
+                    format!("{}ms", result.duration.as_millis()),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ];
+            if let Some(failure) = result.failures().first() {
+                spans.push(Span::styled(
+                    format!("  {failure}"),
+                    Style::default().fg(Color::Red),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+    let title = format!(
+        "* {} * {}/{} passed in {:.2}s",
+        run.name,
+        run.results.len() - run.failed_count(),
+        run.results.len(),
+        run.total_duration().as_secs_f64()
+    );
+    let area = centered_rect(frame.size(), ScreenArea::Bottom);
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        List::new(items).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Double)
+                .title(title),
+        ),
+        area,
+    );
+    match opt {
+        Some(InputOpt::AlertMessage(msg)) | Some(InputOpt::RequestError(msg)) => {
+            error_alert_box(frame, &msg);
+        }
+        Some(opt) => handle_default_input_screen(app, frame, opt),
+        None => {}
+    }
+    match app.selected {
+        Some(0) => app.goto_screen(&Screen::RunResults(Some(InputOpt::RunReport(
+            ReportFormat::JUnit,
+        )))),
+        Some(1) => app.goto_screen(&Screen::RunResults(Some(InputOpt::RunReport(
+            ReportFormat::Json,
+        )))),
+        Some(2) => app.goto_screen(&Screen::ViewSavedCollections),
+        _ => {}
+    }
+}
@@ -1,7 +1,7 @@
 use super::input::input_screen::handle_default_input_screen;
-use super::render::render_header_paragraph;
+use super::render::{handle_screen_defaults, render_header_paragraph};
 use super::{centered_rect, error_alert_box, Screen, ScreenArea};
-use crate::app::App;
+use crate::app::{App, InputMode};
 use crate::display::inputopt::InputOpt;
 use crate::display::menuopts::{CMD_MENU_OPTIONS, SAVED_COMMANDS_PARAGRAPH, SAVED_COMMANDS_TITLE};
 use tui::prelude::{Constraint, Direction, Layout, Margin};
@@ -23,7 +23,12 @@ pub fn handle_saved_commands_screen(
             .iter()
             .map(|x| {
                 format!(
-                    "Request: {}  |  Collection: {:?}",
+                    "{}Request: {}  |  Collection: {:?}",
+                    if app.marked.contains(&x.get_id()) {
+                        "[x] "
+                    } else {
+                        ""
+                    },
                     x.label.clone().unwrap_or(String::from("No label")),
                     match x.collection_name.clone() {
                         Some(name) => name,
@@ -49,7 +54,8 @@ pub fn handle_saved_commands_screen(
         Some(InputOpt::AlertMessage(msg)) | Some(InputOpt::RequestError(msg)) => {
             error_alert_box(frame, &msg);
         }
-        _ => {}
+        Some(opt) => handle_default_input_screen(app, frame, opt),
+        None => {}
     }
     if let Some(selected) = app.selected {
         let cmd = commands.get(selected);
@@ -161,8 +167,9 @@ pub fn handle_alert_menu(app: &mut App, frame: &mut Frame<'_>, cmd: i32, opt: Op
         match app.selected {
             // execute saved command
             Some(0) => {
-                app.execute_saved_command(command.get_curl_json());
-                app.goto_screen(&Screen::Response(app.response.clone().unwrap()));
+                let json = app.resolve_variables(&command);
+                let result = app.execute_saved_command(&json);
+                app.show_response(result);
             }
             // add a label
             Some(1) => {
@@ -196,18 +203,68 @@ pub fn handle_alert_menu(app: &mut App, frame: &mut Frame<'_>, cmd: i32, opt: Op
             }
             // copy to clipboard
             Some(4) => {
-                if let Err(e) = app.copy_to_clipboard(command.get_command()) {
-                    app.goto_screen(&Screen::Error(e.to_string()));
-                }
+                let msg = match app.copy_curl_command(Some(&app.resolve_variables(&command))) {
+                    Ok(()) => InputOpt::AlertMessage("CLI Command copied to clipboard".to_string()),
+                    Err(e) => InputOpt::RequestError(format!("Error: {}", e)),
+                };
                 app.goto_screen(&Screen::SavedCommands {
                     id: None,
-                    opt: Some(InputOpt::AlertMessage(
-                        "CLI Command copied to clipboard".to_string(),
-                    )),
+                    opt: Some(msg),
                 });
             }
-            // cancel
+            // copy as a wget command
             Some(5) => {
+                let msg = match app.copy_wget_command(Some(&app.resolve_variables(&command))) {
+                    Ok(msg) => InputOpt::AlertMessage(msg),
+                    Err(e) => InputOpt::RequestError(format!("Error: {}", e)),
+                };
+                app.goto_screen(&Screen::SavedCommands {
+                    id: None,
+                    opt: Some(msg),
+                });
+            }
+            // benchmark
+            Some(6) => {
+                app.goto_screen(&Screen::CmdMenu {
+                    id: cmd,
+                    opt: Some(InputOpt::Benchmark(Some(cmd))),
+                });
+            }
+            // generate code
+            Some(7) => {
+                app.goto_screen(&Screen::CodeGen {
+                    id: Some(cmd),
+                    opt: None,
+                });
+            }
+            // move or copy to a collection
+            Some(8) | Some(9) => {
+                app.goto_screen(&Screen::MoveCommands {
+                    ids: vec![cmd],
+                    copy: app.selected == Some(9),
+                    opt: None,
+                });
+            }
+            // take it out of its collection, the command itself is kept
+            Some(10) => {
+                let msg = match app.db.set_command_collection(cmd, None) {
+                    Ok(()) => InputOpt::AlertMessage(String::from("Removed from its collection")),
+                    Err(e) => InputOpt::RequestError(format!("Error: {}", e)),
+                };
+                app.goto_screen(&Screen::SavedCommands {
+                    id: None,
+                    opt: Some(msg),
+                });
+            }
+            // run it against two environments and compare
+            Some(11) => {
+                app.goto_screen(&Screen::CmdMenu {
+                    id: cmd,
+                    opt: Some(InputOpt::DiffEnvironments(cmd)),
+                });
+            }
+            // cancel
+            Some(12) => {
                 app.goto_screen(&Screen::SavedCommands {
                     id: None,
                     opt: None,
@@ -217,3 +274,47 @@ pub fn handle_alert_menu(app: &mut App, frame: &mut Frame<'_>, cmd: i32, opt: Op
         }
     }
 }
+
+pub fn handle_move_commands_screen(
+    app: &mut App,
+    frame: &mut Frame<'_>,
+    ids: Vec<i32>,
+    copy: bool,
+    opt: Option<InputOpt>,
+) {
+    handle_screen_defaults(app, frame);
+    match opt {
+        Some(InputOpt::AlertMessage(msg)) | Some(InputOpt::RequestError(msg)) => {
+            error_alert_box(frame, &msg);
+        }
+        Some(opt) => handle_default_input_screen(app, frame, opt),
+        None => {}
+    }
+    let collections = app.db.get_collections().unwrap_or_default();
+    match app.selected {
+        Some(num) if num < collections.len() => {
+            let collection = collections[num].get_id();
+            match app.move_commands(&ids, collection, copy) {
+                Ok(msg) => app.goto_screen(&Screen::SavedCommands {
+                    id: Some(collection),
+                    opt: Some(InputOpt::AlertMessage(msg)),
+                }),
+                Err(e) => app.goto_screen(&Screen::MoveCommands {
+                    ids,
+                    copy,
+                    opt: Some(InputOpt::RequestError(format!("Error: {}", e))),
+                }),
+            }
+        }
+        // create a new collection for them
+        Some(_) => {
+            app.goto_screen(&Screen::MoveCommands {
+                ids: ids.clone(),
+                copy,
+                opt: Some(InputOpt::NewCollection(ids, copy)),
+            });
+            app.input_mode = InputMode::Editing;
+        }
+        None => {}
+    }
+}
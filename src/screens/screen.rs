@@ -1,10 +1,13 @@
 use crate::display::inputopt::InputOpt;
 use crate::display::menuopts::{
-    AUTHENTICATION_MENU_OPTIONS, CMD_MENU_OPTIONS, COLLECTION_ALERT_MENU_OPTS,
-    COLLECTION_MENU_OPTIONS, COOKIE_MENU_OPTIONS, HEADER_MENU_OPTIONS, KEY_MENU_OPTIONS,
+    AUTHENTICATION_MENU_OPTIONS, BENCHMARK_MENU_OPTIONS, CMD_MENU_OPTIONS,
+    COLLECTION_ALERT_MENU_OPTS, COLLECTION_MENU_OPTIONS, COOKIE_MENU_OPTIONS,
+    ENVIRONMENT_MENU_OPTIONS, HEADER_MENU_OPTIONS, HISTORY_MENU_OPTIONS, KEY_MENU_OPTIONS,
     MAIN_MENU_OPTIONS, METHOD_MENU_OPTIONS, MORE_FLAGS_MENU, NEWLINE, OPTION_PADDING_MAX,
     OPTION_PADDING_MID, OPTION_PADDING_MIN, REQUEST_MENU_OPTIONS, RESPONSE_MENU_OPTIONS,
+    RUN_RESULTS_MENU_OPTIONS,
 };
+use crate::request::codegen::LANGUAGES;
 use std::fmt::{Display, Formatter};
 use tui::style::{Color, Modifier, Style};
 use tui::widgets::{Block, Borders, List, ListItem};
@@ -17,9 +20,12 @@ pub enum Screen {
     HeaderAddRemove,
     RequestMenu(Option<InputOpt>),
     InputMenu(InputOpt),
-    Response(String),
+    Response(Option<InputOpt>),
     SavedCollections(Option<InputOpt>),
     ViewSavedCollections,
+    Environments(Option<InputOpt>),
+    // environment id, confirms deleting it
+    EnvironmentMenu(i32),
     Authentication,
     Success,
     SavedKeys(Option<InputOpt>),
@@ -30,7 +36,7 @@ pub enum Screen {
         opt: Option<InputOpt>,
     },
     Error(String),
-    ViewBody,
+    ViewBody(Option<InputOpt>),
     MoreFlags,
     Headers,
     CmdMenu {
@@ -40,17 +46,46 @@ pub enum Screen {
     KeysMenu(usize),
     RequestBodyInput,
     CookieOptions,
+    RunResults(Option<InputOpt>),
+    Benchmark,
+    // takes optional filter
+    History {
+        filter: Option<String>,
+        opt: Option<InputOpt>,
+    },
+    HistoryMenu(i32),
+    // compares the responses in app.diff
+    Diff,
+    // saved command ids to move (or copy if true) into the picked collection
+    MoveCommands {
+        ids: Vec<i32>,
+        copy: bool,
+        opt: Option<InputOpt>,
+    },
+    // takes the saved command id, or None for the current command
+    CodeGen {
+        id: Option<i32>,
+        opt: Option<InputOpt>,
+    },
 }
 
 impl Screen {
+    // Screens showing a prompt, an alert or error message is not one
     pub fn is_input_screen(&self) -> bool {
+        let prompt = |opt: &Option<InputOpt>| opt.as_ref().is_some_and(|op| !op.is_error());
         match self {
-            Screen::RequestMenu(opt) => opt.is_some(),
+            Screen::RequestMenu(opt) => prompt(opt),
             Screen::InputMenu(_) => true,
-            Screen::SavedKeys(opt) => opt.is_some(),
+            Screen::SavedKeys(opt) => prompt(opt),
             Screen::RequestBodyInput => true,
-            Screen::SavedCollections(opt) => opt.is_some(),
-            Screen::CmdMenu { opt, .. } => opt.is_some(),
+            Screen::SavedCollections(opt) => prompt(opt),
+            Screen::CmdMenu { opt, .. } => prompt(opt),
+            Screen::RunResults(opt) => prompt(opt),
+            Screen::History { opt, .. } => prompt(opt),
+            Screen::ViewBody(opt) => prompt(opt),
+            Screen::CodeGen { opt, .. } => prompt(opt),
+            Screen::SavedCommands { opt, .. } => prompt(opt),
+            Screen::MoveCommands { opt, .. } => prompt(opt),
             _ => false,
         }
     }
@@ -70,7 +105,7 @@ impl Display for Screen {
             Screen::SavedKeys(_) => "Saved Keys",
             Screen::SavedCommands { .. } => "My Saved Commands",
             Screen::Error(_) => "Error",
-            Screen::ViewBody => "ViewBody",
+            Screen::ViewBody(_) => "ViewBody",
             Screen::MoreFlags => "MoreFlags",
             Screen::Headers => "Headers",
             Screen::CmdMenu { .. } => "CmdMenu",
@@ -78,8 +113,18 @@ impl Display for Screen {
             Screen::RequestBodyInput => "RequestBodyInput",
             Screen::SavedCollections(_) => "Saved Collections",
             Screen::ViewSavedCollections => "View Saved Collections",
+            Screen::Environments(_) => "Environments",
+            Screen::EnvironmentMenu(_) => "Environment Menu",
             Screen::ColMenu(_) => "Collection Menu",
             Screen::CookieOptions => "Cookie Options",
+            Screen::RunResults(_) => "Collection Run",
+            Screen::Benchmark => "Benchmark",
+            Screen::History { .. } => "Request History",
+            Screen::HistoryMenu(_) => "HistoryMenu",
+            Screen::Diff => "Response Diff",
+            Screen::CodeGen { .. } => "Generate Code",
+            Screen::MoveCommands { copy: true, .. } => "Copy to Collection",
+            Screen::MoveCommands { copy: false, .. } => "Move to Collection",
         };
         write!(f, "{}", screen)
     }
@@ -163,7 +208,7 @@ impl<'a> Screen {
             Screen::Error(_) => {
                 vec![ListItem::new("Error!").style(Style::default().fg(Color::Red))]
             }
-            Screen::ViewBody => {
+            Screen::ViewBody(_) => {
                 vec![ListItem::new("View Body").style(Style::default().fg(Color::Green))]
             }
             Screen::RequestBodyInput => {
@@ -177,11 +222,12 @@ impl<'a> Screen {
                 .iter()
                 .map(|i| ListItem::new(*i))
                 .collect(),
+            Screen::EnvironmentMenu(_) => ENVIRONMENT_MENU_OPTIONS
+                .iter()
+                .map(|i| ListItem::new(*i))
+                .collect(),
             Screen::SavedKeys(_) => {
-                let mut len = 0;
-                if items.is_some() {
-                    len = items.as_ref().unwrap().len();
-                }
+                let len = items.as_ref().map(|i| i.len()).unwrap_or_default();
                 items
                     .unwrap_or(vec!["No Saved Keys".to_string()])
                     .iter()
@@ -207,6 +253,13 @@ impl<'a> Screen {
                 .iter()
                 .map(|c| ListItem::new(format!("{}{}", c, OPTION_PADDING_MIN)))
                 .collect(),
+            Screen::Environments(_) => items
+                .unwrap_or(vec![
+                    "No Environments, import a Postman environment".to_string()
+                ])
+                .iter()
+                .map(|c| ListItem::new(format!("{}{}", c, OPTION_PADDING_MIN)))
+                .collect(),
 
             Screen::SavedCollections(_) => COLLECTION_MENU_OPTIONS
                 .iter()
@@ -217,10 +270,43 @@ impl<'a> Screen {
                 .iter()
                 .map(|c| ListItem::from(format!("{}{}", c, OPTION_PADDING_MID)))
                 .collect(),
+            Screen::RunResults(_) => RUN_RESULTS_MENU_OPTIONS
+                .iter()
+                .map(|c| ListItem::from(format!("{}{}", c, OPTION_PADDING_MID)))
+                .collect(),
+            Screen::Benchmark => BENCHMARK_MENU_OPTIONS
+                .iter()
+                .map(|c| ListItem::from(format!("{}{}", c, OPTION_PADDING_MID)))
+                .collect(),
+            Screen::History { .. } => items
+                .unwrap_or(vec!["No Request History".to_string()])
+                .iter()
+                .map(|c| ListItem::new(format!("{}{}", c, OPTION_PADDING_MIN)))
+                .collect(),
+            Screen::Diff => items
+                .unwrap_or_default()
+                .into_iter()
+                .map(ListItem::new)
+                .collect(),
+            Screen::HistoryMenu(_) => HISTORY_MENU_OPTIONS
+                .iter()
+                .map(|i| ListItem::new(format!("{i}{}", NEWLINE)))
+                .collect(),
+            Screen::MoveCommands { .. } => items
+                .unwrap_or_default()
+                .iter()
+                .map(|c| ListItem::new(format!("{}{}", c, OPTION_PADDING_MIN)))
+                .collect(),
+            Screen::CodeGen { .. } => LANGUAGES
+                .iter()
+                .map(|lang| lang.to_string())
+                .chain(std::iter::once(String::from("Cancel")))
+                .map(|lang| ListItem::new(format!("{}{}", lang, OPTION_PADDING_MIN)))
+                .collect(),
         }
     }
 
-    pub fn get_list(&self, items: Option<Vec<String>>) -> List {
+    pub fn get_list(&self, items: Option<Vec<String>>) -> List<'_> {
         List::new(self.get_opts(items))
             .block(
                 Block::default()
@@ -0,0 +1,180 @@
+use super::input::input_screen::handle_default_input_screen;
+use super::render::render_header_paragraph;
+use super::{centered_rect, ScreenArea};
+use crate::app::{App, InputMode};
+use crate::display::inputopt::InputOpt;
+use crate::display::menuopts::{VIEW_BODY_PARAGRAPH, VIEW_BODY_TITLE, VIEW_HEADERS_TITLE};
+use crate::display::viewer::{highlight, BodyViewer, Syntax, TokenKind};
+use std::ops::Range;
+use tui::style::{Color, Modifier, Style};
+use tui::text::{Line, Span};
+use tui::widgets::{Block, Borders, Clear, List, ListItem, ListState};
+use tui::Frame;
+
+fn token_style(kind: TokenKind) -> Style {
+    let color = match kind {
+        TokenKind::Key => Color::LightBlue,
+        TokenKind::Str => Color::Green,
+        TokenKind::Number => Color::Yellow,
+        TokenKind::Literal => Color::Magenta,
+        TokenKind::Punct => Color::Gray,
+        TokenKind::Tag => Color::LightBlue,
+        TokenKind::Attr => Color::Cyan,
+        TokenKind::Comment => Color::DarkGray,
+        TokenKind::Text => return Style::default(),
+    };
+    Style::default().fg(color)
+}
+
+// Splits the highlighted tokens of a line at search match boundaries,
+// so matches can be drawn over the syntax colours
+fn overlay_matches<'a>(
+    tokens: Vec<(TokenKind, &'a str)>,
+    matches: &[(Range<usize>, bool)],
+) -> Vec<Span<'a>> {
+    let mut spans = Vec::new();
+    let mut offset = 0;
+    for (kind, token) in tokens {
+        let end = offset + token.len();
+        let mut pos = offset;
+        while pos < end {
+            let found = matches.iter().find(|(r, _)| r.start <= pos && pos < r.end);
+            let (next, style) = match found {
+                Some((range, current)) => (
+                    range.end.min(end),
+                    Style::default().fg(Color::Black).bg(if *current {
+                        Color::LightRed
+                    } else {
+                        Color::Yellow
+                    }),
+                ),
+                None => (
+                    matches
+                        .iter()
+                        .map(|(r, _)| r.start)
+                        .filter(|start| *start > pos)
+                        .min()
+                        .unwrap_or(end)
+                        .min(end),
+                    token_style(kind),
+                ),
+            };
+            spans.push(Span::styled(&token[pos - offset..next - offset], style));
+            pos = next;
+        }
+        offset = end;
+    }
+    spans
+}
+
+fn megabytes(bytes: usize) -> String {
+    format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+}
+
+// line number gutter, fold marker and the highlighted line
+fn render_row<'a>(viewer: &'a BodyViewer, row: usize, width: usize) -> ListItem<'a> {
+    let Some((line, text, folded)) = viewer.row(row) else {
+        return ListItem::new("");
+    };
+    let marker = match (viewer.is_foldable(row), folded) {
+        (true, true) => "▸ ",
+        (true, false) => "▾ ",
+        _ => "  ",
+    };
+    let mut spans = vec![
+        Span::styled(
+            format!("{:>width$} ", line + 1),
+            Style::default().fg(Color::DarkGray),
+        ),
+        Span::styled(marker, Style::default().fg(Color::DarkGray)),
+    ];
+    spans.extend(overlay_matches(
+        highlight(text, viewer.syntax),
+        &viewer.line_matches(line),
+    ));
+    if folded {
+        spans.push(Span::styled(" … ", Style::default().fg(Color::DarkGray)));
+    }
+    ListItem::new(Line::from(spans))
+}
+
+pub fn handle_view_body_screen(app: &mut App, frame: &mut Frame<'_>, opt: Option<InputOpt>) {
+    let header_title = match app.viewer.syntax {
+        Syntax::Headers => VIEW_HEADERS_TITLE,
+        _ => VIEW_BODY_TITLE,
+    };
+    frame.render_widget(
+        render_header_paragraph(VIEW_BODY_PARAGRAPH, header_title, app.config.get_style()),
+        frame.size(),
+    );
+    if app.selected.take().is_some() {
+        app.toggle_fold();
+    }
+    // filters and searches are applied live while they are typed
+    if app.input_mode == InputMode::Editing {
+        let value = app.input.value().to_string();
+        match opt {
+            Some(InputOpt::BodyFilter) => app.viewer.preview_filter(&value),
+            Some(InputOpt::BodySearch) => {
+                app.cursor = app.viewer.preview_search(&value, app.cursor);
+            }
+            _ => {}
+        }
+    }
+    app.cursor = app.cursor.min(app.viewer.len().saturating_sub(1));
+    let area = centered_rect(frame.size(), ScreenArea::Center)
+        .union(centered_rect(frame.size(), ScreenArea::Bottom));
+    // only the rows on screen are highlighted and rendered
+    app.viewer
+        .scroll_into_view(app.cursor, area.height.saturating_sub(2) as usize);
+    let offset = app.viewer.offset;
+    let width = app.viewer.line_count().to_string().len();
+    let items: Vec<ListItem> = (offset..app.viewer.len().min(offset + app.viewer.page))
+        .map(|row| render_row(&app.viewer, row, width))
+        .collect();
+    let mut filter = match app.viewer.format {
+        Some(_) if app.viewer.raw => String::from(" | Raw"),
+        Some(format) if app.viewer.overridden => format!(" | {} (override)", format.name()),
+        Some(format) => format!(" | {}", format.name()),
+        None => String::new(),
+    };
+    if let Some(ref expr) = app.viewer.filter {
+        filter.push_str(&format!(" | Filter: {}", expr));
+    }
+    if app.viewer.search.is_some() {
+        filter.push_str(&match app.viewer.current_match() {
+            Some(current) => format!(" | Match {}/{}", current, app.viewer.match_count()),
+            None => format!(" | {} matches", app.viewer.match_count()),
+        });
+    }
+    if let Some(total) = app.viewer.truncated {
+        filter.push_str(&format!(
+            " | Showing first {} of {}, press 'L' to load it all",
+            megabytes(app.viewer.text().len()),
+            megabytes(total)
+        ));
+    }
+    let title = match app.viewer.status {
+        Some(ref status) => format!("* {:?}{} * {}", app.viewer.syntax, filter, status),
+        None => format!(
+            "* {:?}{} * {} lines",
+            app.viewer.syntax,
+            filter,
+            app.viewer.line_count()
+        ),
+    };
+    let mut state = ListState::with_selected(ListState::default(), Some(app.cursor));
+    app.state = Some(state.clone());
+    *state.selected_mut() = Some(app.cursor - offset);
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(
+        List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED)),
+        area,
+        &mut state,
+    );
+    if let Some(opt) = opt {
+        handle_default_input_screen(app, frame, opt);
+    }
+}